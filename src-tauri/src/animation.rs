@@ -0,0 +1,211 @@
+//! Animated GIF / WebP encoding for frame sequences read one at a time
+//!
+//! The `image` crate only writes still WebP, so animated WebP is muxed by hand:
+//! each frame is encoded losslessly and its VP8L chunk is wrapped in an ANMF
+//! chunk of an extended (VP8X) container. Chunks are streamed to the file and
+//! the RIFF size is patched in at the end.
+
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{ExtendedColorType, RgbaImage};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnimationFormat {
+    Gif,
+    WebP,
+}
+
+impl AnimationFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            AnimationFormat::Gif => "gif",
+            AnimationFormat::WebP => "webp",
+        }
+    }
+}
+
+/// Largest canvas side the WebP container can describe (24-bit fields)
+const WEBP_MAX_SIDE: u32 = 1 << 24;
+
+/// Write a looping animation to `path`, returning the number of frames written
+///
+/// Frames whose size differs from the first are resized to match it.
+pub fn write_animation<I>(path: &Path, format: AnimationFormat, frames: I, delay_ms: u32) -> Result<usize, String>
+where
+    I: IntoIterator<Item = Result<RgbaImage, String>>,
+{
+    let mut frames = frames.into_iter();
+    let first = frames.next().ok_or("No frames to export")??;
+    let (width, height) = first.dimensions();
+    let fit = move |frame: RgbaImage| {
+        if frame.dimensions() == (width, height) {
+            frame
+        } else {
+            image::imageops::resize(&frame, width, height, FilterType::Triangle)
+        }
+    };
+    let rest = frames.map(|f| f.map(fit));
+    let frames = std::iter::once(Ok(first)).chain(rest);
+
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut out = BufWriter::new(file);
+    let count = match format {
+        AnimationFormat::Gif => write_gif(&mut out, width, height, frames, delay_ms)?,
+        AnimationFormat::WebP => write_webp(&mut out, width, height, frames, delay_ms)?,
+    };
+    out.flush().map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+fn write_gif<W, I>(out: W, width: u32, height: u32, frames: I, delay_ms: u32) -> Result<usize, String>
+where
+    W: Write,
+    I: Iterator<Item = Result<RgbaImage, String>>,
+{
+    if width > u16::MAX as u32 || height > u16::MAX as u32 {
+        return Err("Image too large for GIF".to_string());
+    }
+    let mut encoder = gif::Encoder::new(out, width as u16, height as u16, &[]).map_err(|e| e.to_string())?;
+    encoder.set_repeat(gif::Repeat::Infinite).map_err(|e| e.to_string())?;
+
+    // GIF delays are in 1/100 s
+    let delay = (delay_ms / 10).clamp(1, u16::MAX as u32) as u16;
+    let mut count = 0;
+    for frame in frames {
+        let mut pixels = frame?.into_raw();
+        let mut gif_frame = gif::Frame::from_rgba_speed(width as u16, height as u16, &mut pixels, 10);
+        gif_frame.delay = delay;
+        encoder.write_frame(&gif_frame).map_err(|e| e.to_string())?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+fn write_webp<W, I>(mut out: W, width: u32, height: u32, frames: I, delay_ms: u32) -> Result<usize, String>
+where
+    W: Write + Seek,
+    I: Iterator<Item = Result<RgbaImage, String>>,
+{
+    if width > WEBP_MAX_SIDE || height > WEBP_MAX_SIDE {
+        return Err("Image too large for WebP".to_string());
+    }
+
+    // RIFF size is unknown until the last frame; written as 0 and patched below
+    out.write_all(b"RIFF\0\0\0\0WEBP").map_err(|e| e.to_string())?;
+    let mut riff_size: u64 = 4; // "WEBP"
+
+    // VP8X: animation + alpha flags, canvas size minus one
+    let mut vp8x = vec![0x02 | 0x10, 0, 0, 0];
+    vp8x.extend_from_slice(&u24(width - 1));
+    vp8x.extend_from_slice(&u24(height - 1));
+    riff_size += write_chunk(&mut out, b"VP8X", &vp8x)?;
+    // ANIM: transparent background, loop forever
+    riff_size += write_chunk(&mut out, b"ANIM", &[0, 0, 0, 0, 0, 0])?;
+
+    let duration = u24(delay_ms.min(WEBP_MAX_SIDE - 1));
+    let mut count = 0;
+    for frame in frames {
+        let frame = frame?;
+        let mut anmf = Vec::new();
+        anmf.extend_from_slice(&u24(0)); // x / 2
+        anmf.extend_from_slice(&u24(0)); // y / 2
+        anmf.extend_from_slice(&u24(width - 1));
+        anmf.extend_from_slice(&u24(height - 1));
+        anmf.extend_from_slice(&duration);
+        anmf.push(0x02); // Full frames: no blending, no disposal
+        anmf.extend_from_slice(&image_chunks(&frame)?);
+        riff_size += write_chunk(&mut out, b"ANMF", &anmf)?;
+        if riff_size > u32::MAX as u64 {
+            return Err("Animation too large for WebP (over 4 GiB)".to_string());
+        }
+        count += 1;
+    }
+
+    out.seek(SeekFrom::Start(4)).map_err(|e| e.to_string())?;
+    out.write_all(&(riff_size as u32).to_le_bytes()).map_err(|e| e.to_string())?;
+    out.seek(SeekFrom::End(0)).map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+/// Bitstream chunks (VP8L, or ALPH + VP8) of a still WebP encoding of `frame`
+fn image_chunks(frame: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut still = Vec::new();
+    WebPEncoder::new_lossless(&mut still)
+        .encode(frame.as_raw(), frame.width(), frame.height(), ExtendedColorType::Rgba8)
+        .map_err(|e| e.to_string())?;
+
+    let mut chunks = Vec::new();
+    let mut pos = 12; // "RIFF" size "WEBP"
+    while pos + 8 <= still.len() {
+        let fourcc = &still[pos..pos + 4];
+        let size = u32::from_le_bytes([still[pos + 4], still[pos + 5], still[pos + 6], still[pos + 7]]) as usize;
+        let end = (pos + 8 + size + size % 2).min(still.len());
+        if matches!(fourcc, b"VP8L" | b"VP8 " | b"ALPH") {
+            chunks.extend_from_slice(&still[pos..end]);
+        }
+        pos = end;
+    }
+    if chunks.is_empty() {
+        return Err("WebP encoder produced no image data".to_string());
+    }
+    Ok(chunks)
+}
+
+/// Write a RIFF chunk, padded to an even length; returns the bytes written
+fn write_chunk<W: Write>(out: &mut W, fourcc: &[u8; 4], payload: &[u8]) -> Result<u64, String> {
+    let size = u32::try_from(payload.len()).map_err(|_| "WebP chunk too large".to_string())?;
+    out.write_all(fourcc).map_err(|e| e.to_string())?;
+    out.write_all(&size.to_le_bytes()).map_err(|e| e.to_string())?;
+    out.write_all(payload).map_err(|e| e.to_string())?;
+    let padded = payload.len() % 2 == 1;
+    if padded {
+        out.write_all(&[0]).map_err(|e| e.to_string())?;
+    }
+    Ok(8 + payload.len() as u64 + padded as u64)
+}
+
+fn u24(v: u32) -> [u8; 3] {
+    let b = v.to_le_bytes();
+    [b[0], b[1], b[2]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{AnimationDecoder, Rgba};
+
+    #[test]
+    fn test_webp_animation_round_trip() {
+        let colors = [Rgba([255, 0, 0, 255]), Rgba([0, 255, 0, 255]), Rgba([0, 0, 255, 255])];
+        let frames = colors.iter().enumerate().map(|(i, &c)| {
+            // Last frame is smaller and gets resized to the canvas
+            let side = if i == 2 { 10 } else { 20 };
+            Ok(RgbaImage::from_pixel(side, side, c))
+        });
+
+        let path = std::env::temp_dir().join(format!("lovshot_anim_{}.webp", std::process::id()));
+        let count = write_animation(&path, AnimationFormat::WebP, frames, 250).unwrap();
+        assert_eq!(count, 3);
+
+        let reader = std::io::BufReader::new(File::open(&path).unwrap());
+        let decoded = image::codecs::webp::WebPDecoder::new(reader)
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(decoded.len(), 3);
+        for (frame, color) in decoded.iter().zip(colors) {
+            assert_eq!(frame.buffer().dimensions(), (20, 20));
+            assert_eq!(*frame.buffer().get_pixel(10, 10), color);
+            assert_eq!(frame.delay().numer_denom_ms(), (250, 1));
+        }
+    }
+}
@@ -0,0 +1,928 @@
+//! Annotation rendering from a scene description
+//!
+//! The scene uses the editor's own annotation JSON (`type` tag, camelCase
+//! fields) in editor coordinates; `scale` maps them to image pixels. Shapes
+//! are rasterised with imageproc into supersampled coverage masks so edges
+//! are antialiased, text goes through the shared font chain.
+
+use ab_glyph::PxScale;
+use image::{GrayImage, Luma, Rgba, RgbaImage};
+use imageproc::drawing::{draw_filled_circle_mut, draw_filled_ellipse_mut, draw_filled_rect_mut, draw_polygon_mut};
+use imageproc::point::Point;
+use imageproc::rect::Rect;
+use serde::{Deserialize, Serialize};
+
+use crate::fonts::{font_chain, FontChain, FontRole};
+use crate::watermark::{blend_layer, parse_hex_color};
+
+/// Supersampling factor for coverage masks
+const SS: u32 = 3;
+
+/// Full scene: annotations in drawing order plus the editor-to-image scale
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnnotationScene {
+    #[serde(default = "default_scale")]
+    pub scale: f32, // Image pixels per editor unit (devicePixelRatio)
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+/// One annotation; `id` is kept so the editor can round-trip its objects
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Annotation {
+    #[serde(default)]
+    pub id: String,
+    #[serde(flatten)]
+    pub shape: Shape,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RectStyle {
+    #[default]
+    Solid,
+    Dashed,
+    Filled, // Stroke plus a 25% fill
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArrowStyle {
+    #[default]
+    Single,
+    Double,
+    Thick,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MosaicStyle {
+    #[default]
+    Pixelate,
+    Blur,
+}
+
+/// Drawing primitives
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", rename_all_fields = "camelCase")]
+pub enum Shape {
+    Rect {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        #[serde(default)]
+        style: RectStyle,
+        color: String,
+        #[serde(default = "default_stroke_width")]
+        stroke_width: f32,
+    },
+    Ellipse {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        color: String,
+        #[serde(default = "default_stroke_width")]
+        stroke_width: f32,
+        #[serde(default)]
+        filled: bool,
+    },
+    Arrow {
+        points: [f32; 4], // x1, y1, x2, y2; the head is at (x2, y2)
+        #[serde(default)]
+        style: ArrowStyle,
+        color: String,
+        #[serde(default = "default_stroke_width")]
+        stroke_width: f32,
+    },
+    /// Pen stroke through flat [x0, y0, x1, y1, ...] points
+    Freehand {
+        points: Vec<f32>,
+        color: String,
+        #[serde(default = "default_stroke_width")]
+        stroke_width: f32,
+    },
+    /// Wide translucent marker, multiplied onto the image so text stays readable
+    Highlighter {
+        points: Vec<f32>,
+        #[serde(default = "default_highlighter_color")]
+        color: String,
+        #[serde(default = "default_highlighter_width")]
+        stroke_width: f32,
+    },
+    /// Top-left anchored text; '\n' starts a new line
+    Text {
+        x: f32,
+        y: f32,
+        text: String,
+        #[serde(default = "default_font_size")]
+        font_size: f32,
+        color: String,
+    },
+    /// Numbered step badge centred on (x, y)
+    Step {
+        x: f32,
+        y: f32,
+        number: u32,
+        color: String,
+        #[serde(default = "default_step_radius")]
+        radius: f32,
+    },
+    /// Pixelate or blur an area
+    Mosaic {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        #[serde(default)]
+        style: MosaicStyle,
+        #[serde(default = "default_block_size")]
+        block_size: f32,
+    },
+    /// Dim everything outside the area
+    Spotlight {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        #[serde(default)]
+        ellipse: bool,
+        #[serde(default = "default_spotlight_opacity")]
+        opacity: f32,
+    },
+}
+
+fn default_stroke_width() -> f32 {
+    2.0
+}
+
+fn default_highlighter_color() -> String {
+    "#F0AD4E".to_string()
+}
+
+fn default_highlighter_width() -> f32 {
+    16.0
+}
+
+fn default_font_size() -> f32 {
+    16.0
+}
+
+fn default_step_radius() -> f32 {
+    14.0
+}
+
+fn default_block_size() -> f32 {
+    10.0
+}
+
+fn default_spotlight_opacity() -> f32 {
+    0.6
+}
+
+/// How a mask's colour is combined with the image
+#[derive(Clone, Copy, PartialEq)]
+enum Blend {
+    Normal,
+    Multiply,
+}
+
+/// Pixel box containing (x0, y0)-(x1, y1) plus a 1px margin, clipped to the image
+///
+/// Bounds are clamped before the integer casts so huge or non-finite
+/// coordinates can't overflow.
+fn pixel_box(x0: f32, y0: f32, x1: f32, y1: f32, img_w: u32, img_h: u32) -> Option<(i32, i32, i32, i32)> {
+    let (fw, fh) = (img_w as f32, img_h as f32);
+    let left = (x0.min(x1).clamp(0.0, fw).floor() as i32).saturating_sub(1).max(0);
+    let top = (y0.min(y1).clamp(0.0, fh).floor() as i32).saturating_sub(1).max(0);
+    let right = (x0.max(x1).clamp(0.0, fw).ceil() as i32).saturating_add(1).min(img_w as i32);
+    let bottom = (y0.max(y1).clamp(0.0, fh).ceil() as i32).saturating_add(1).min(img_h as i32);
+    (right > left && bottom > top).then_some((left, top, right, bottom))
+}
+
+/// Binary shape drawn at SS x resolution over a pixel-aligned box; averaged down to coverage
+struct Mask {
+    x: i32,
+    y: i32,
+    w: u32,
+    h: u32,
+    /// Larger image side, bounds the supersampled coordinates
+    span: u32,
+    hi: GrayImage,
+}
+
+impl Mask {
+    /// Mask over the pixel box containing (x0, y0)-(x1, y1), clipped to the image
+    fn new(x0: f32, y0: f32, x1: f32, y1: f32, img_w: u32, img_h: u32) -> Option<Self> {
+        let (left, top, right, bottom) = pixel_box(x0, y0, x1, y1, img_w, img_h)?;
+        Some(Self::with_box(left, top, right, bottom, img_w, img_h))
+    }
+
+    /// Mask over an exact, already clipped pixel box
+    fn with_box(left: i32, top: i32, right: i32, bottom: i32, img_w: u32, img_h: u32) -> Self {
+        let (w, h) = ((right - left) as u32, (bottom - top) as u32);
+        Self { x: left, y: top, w, h, span: img_w.max(img_h), hi: GrayImage::new(w * SS, h * SS) }
+    }
+
+    /// Supersampled coordinates are kept within a few image sizes, so shapes
+    /// far off the mask stay off it without overflowing the drawing routines
+    fn limit(&self) -> f32 {
+        (self.span * SS * 4) as f32
+    }
+
+    fn to_hi(&self, x: f32, y: f32) -> (f32, f32) {
+        let limit = self.limit();
+        let hx = ((x - self.x as f32) * SS as f32).clamp(-limit, limit);
+        let hy = ((y - self.y as f32) * SS as f32).clamp(-limit, limit);
+        (hx, hy)
+    }
+
+    fn value(on: bool) -> Luma<u8> {
+        Luma([if on { 255 } else { 0 }])
+    }
+
+    fn polygon(&mut self, points: &[(f32, f32)], on: bool) {
+        let mut poly: Vec<Point<i32>> = points
+            .iter()
+            .map(|&(x, y)| {
+                let (hx, hy) = self.to_hi(x, y);
+                Point::new(hx.round() as i32, hy.round() as i32)
+            })
+            .collect();
+        poly.dedup();
+        // imageproc rejects closed or degenerate polygons
+        while poly.len() > 1 && poly.first() == poly.last() {
+            poly.pop();
+        }
+        if poly.len() >= 3 {
+            draw_polygon_mut(&mut self.hi, &poly, Self::value(on));
+        }
+    }
+
+    fn circle(&mut self, cx: f32, cy: f32, r: f32, on: bool) {
+        let (hx, hy) = self.to_hi(cx, cy);
+        let r = (r * SS as f32).min(self.limit()).round() as i32;
+        if r > 0 {
+            draw_filled_circle_mut(&mut self.hi, (hx.round() as i32, hy.round() as i32), r, Self::value(on));
+        }
+    }
+
+    fn ellipse(&mut self, cx: f32, cy: f32, rx: f32, ry: f32, on: bool) {
+        let (hx, hy) = self.to_hi(cx, cy);
+        let limit = self.limit();
+        let (rx, ry) = ((rx * SS as f32).min(limit).round() as i32, (ry * SS as f32).min(limit).round() as i32);
+        if rx > 0 && ry > 0 {
+            draw_filled_ellipse_mut(&mut self.hi, (hx.round() as i32, hy.round() as i32), rx, ry, Self::value(on));
+        }
+    }
+
+    fn rect(&mut self, x: f32, y: f32, w: f32, h: f32, on: bool) {
+        // Clip to the mask so the integer rect can't overflow
+        let (hx0, hy0) = self.to_hi(x, y);
+        let (hx1, hy1) = self.to_hi(x + w, y + h);
+        let (hx0, hy0) = (hx0.round().max(0.0) as i32, hy0.round().max(0.0) as i32);
+        let hx1 = (hx1.round() as i32).min(self.hi.width() as i32);
+        let hy1 = (hy1.round() as i32).min(self.hi.height() as i32);
+        if hx1 > hx0 && hy1 > hy0 {
+            let rect = Rect::at(hx0, hy0).of_size((hx1 - hx0) as u32, (hy1 - hy0) as u32);
+            draw_filled_rect_mut(&mut self.hi, rect, Self::value(on));
+        }
+    }
+
+    /// Thick segment; round caps double as round joins for polylines
+    fn line(&mut self, a: (f32, f32), b: (f32, f32), width: f32, round_caps: bool) {
+        let half = width / 2.0;
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let len = (dx * dx + dy * dy).sqrt();
+        if len > f32::EPSILON {
+            let (nx, ny) = (-dy / len * half, dx / len * half);
+            self.polygon(&[(a.0 + nx, a.1 + ny), (b.0 + nx, b.1 + ny), (b.0 - nx, b.1 - ny), (a.0 - nx, a.1 - ny)], true);
+        }
+        if round_caps {
+            self.circle(a.0, a.1, half, true);
+            self.circle(b.0, b.1, half, true);
+        }
+    }
+
+    /// Fraction of the pixel (image coordinates) covered by the shape
+    fn coverage(&self, px: u32, py: u32) -> f32 {
+        let (lx, ly) = (px as i32 - self.x, py as i32 - self.y);
+        if lx < 0 || ly < 0 || lx >= self.w as i32 || ly >= self.h as i32 {
+            return 0.0;
+        }
+        let mut sum = 0u32;
+        for sy in 0..SS {
+            for sx in 0..SS {
+                sum += self.hi.get_pixel(lx as u32 * SS + sx, ly as u32 * SS + sy)[0] as u32;
+            }
+        }
+        sum as f32 / (255 * SS * SS) as f32
+    }
+
+    /// Paint `color` through the mask
+    fn composite(&self, img: &mut RgbaImage, color: Rgba<u8>, blend: Blend) {
+        let strength = color[3] as f32 / 255.0;
+        for py in self.y as u32..self.y as u32 + self.h {
+            for px in self.x as u32..self.x as u32 + self.w {
+                let a = self.coverage(px, py) * strength;
+                if a <= 0.0 {
+                    continue;
+                }
+                let p = img.get_pixel_mut(px, py);
+                for c in 0..3 {
+                    let src = match blend {
+                        Blend::Normal => color[c] as f32,
+                        Blend::Multiply => p[c] as f32 * color[c] as f32 / 255.0,
+                    };
+                    p[c] = (p[c] as f32 * (1.0 - a) + src * a).round() as u8;
+                }
+                p[3] = (p[3] as f32 + (255.0 - p[3] as f32) * a).round() as u8;
+            }
+        }
+    }
+}
+
+/// Flat [x0, y0, x1, y1, ...] into points
+fn pairs(points: &[f32], scale: f32) -> Vec<(f32, f32)> {
+    points.chunks_exact(2).map(|p| (p[0] * scale, p[1] * scale)).collect()
+}
+
+/// Bounding box of points, grown by `pad`
+fn bounds(points: &[(f32, f32)], pad: f32) -> (f32, f32, f32, f32) {
+    points.iter().fold(
+        (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+        |(x0, y0, x1, y1), &(x, y)| (x0.min(x - pad), y0.min(y - pad), x1.max(x + pad), y1.max(y + pad)),
+    )
+}
+
+fn polyline_mask(points: &[(f32, f32)], width: f32, img_w: u32, img_h: u32) -> Option<Mask> {
+    let (x0, y0, x1, y1) = bounds(points, width);
+    let mut mask = Mask::new(x0, y0, x1, y1, img_w, img_h)?;
+    if points.len() == 1 {
+        mask.circle(points[0].0, points[0].1, width / 2.0, true);
+    }
+    for seg in points.windows(2) {
+        mask.line(seg[0], seg[1], width, true);
+    }
+    Some(mask)
+}
+
+/// Rows per outline band; each band gets its own small mask
+const BAND_ROWS: i32 = 32;
+
+/// Paint a hollow shape band by band instead of through one mask over its bounding box
+///
+/// `outer(y0, y1)` is the x-range the shape may reach on rows y0..y1 and `hole(y0, y1)`
+/// the x-range it leaves empty on all of them; `draw` paints the whole shape into a mask.
+/// Only the pixels between the two ranges are supersampled, so a full-screen outline
+/// costs a few stroke-sized masks rather than a full-screen one.
+fn composite_outline(
+    img: &mut RgbaImage,
+    top: f32,
+    bottom: f32,
+    outer: impl Fn(f32, f32) -> Option<(f32, f32)>,
+    hole: impl Fn(f32, f32) -> Option<(f32, f32)>,
+    draw: impl Fn(&mut Mask),
+    color: Rgba<u8>,
+) {
+    let (img_w, img_h) = img.dimensions();
+    let Some((_, top, _, bottom)) = pixel_box(0.0, top, 0.0, bottom, img_w, img_h) else {
+        return;
+    };
+    for band_top in (top..bottom).step_by(BAND_ROWS as usize) {
+        let band_bottom = (band_top + BAND_ROWS).min(bottom);
+        let (y0, y1) = (band_top as f32, band_bottom as f32);
+        let Some((ox0, ox1)) = outer(y0, y1) else {
+            continue;
+        };
+        let Some((left, _, right, _)) = pixel_box(ox0, y0, ox1, y1, img_w, img_h) else {
+            continue;
+        };
+        // One pixel of slack on each side of the hole covers rounding in the drawn shape
+        let (hx0, hx1) = match hole(y0, y1) {
+            Some((h0, h1)) => ((h0.ceil() as i32 + 1).clamp(left, right), (h1.floor() as i32 - 1).clamp(left, right)),
+            None => (right, right),
+        };
+        let spans = if hx1 > hx0 { [(left, hx0), (hx1, right)] } else { [(left, right), (right, right)] };
+        for (x0, x1) in spans.into_iter().filter(|(x0, x1)| x1 > x0) {
+            let mut mask = Mask::with_box(x0, band_top, x1, band_bottom, img_w, img_h);
+            draw(&mut mask);
+            mask.composite(img, color, Blend::Normal);
+        }
+    }
+}
+
+/// Half-width of an ellipse on the row closest to (`nearest`) or farthest from its centre within y0..y1
+fn ellipse_span(cx: f32, cy: f32, rx: f32, ry: f32, y0: f32, y1: f32, nearest: bool) -> Option<(f32, f32)> {
+    if rx <= 0.0 || ry <= 0.0 {
+        return None;
+    }
+    let dy = if nearest {
+        if (y0..=y1).contains(&cy) { 0.0 } else { (y0 - cy).abs().min((y1 - cy).abs()) }
+    } else {
+        (y0 - cy).abs().max((y1 - cy).abs())
+    };
+    let t = dy / ry;
+    (t < 1.0).then(|| {
+        let half = rx * (1.0 - t * t).sqrt();
+        (cx - half, cx + half)
+    })
+}
+
+/// Rectangle outline centred on the edges, dashed like the editor (dash 10, gap 5)
+fn rect_outline(mask: &mut Mask, x: f32, y: f32, w: f32, h: f32, sw: f32, dash: Option<(f32, f32)>) {
+    let Some((on, off)) = dash else {
+        mask.rect(x - sw / 2.0, y - sw / 2.0, w + sw, h + sw, true);
+        mask.rect(x + sw / 2.0, y + sw / 2.0, w - sw, h - sw, false);
+        return;
+    };
+
+    let corners = [(x, y), (x + w, y), (x + w, y + h), (x, y + h), (x, y)];
+    let mut phase = 0.0f32;
+    for edge in corners.windows(2) {
+        let (a, b) = (edge[0], edge[1]);
+        let len = ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+        if len <= f32::EPSILON {
+            continue;
+        }
+        let dir = ((b.0 - a.0) / len, (b.1 - a.1) / len);
+        let mut pos = 0.0f32;
+        while pos < len {
+            let in_dash = phase < on;
+            let step = if in_dash { on - phase } else { on + off - phase }.min(len - pos);
+            if in_dash {
+                // Pieces touching a corner extend by half the stroke to close it
+                let start = if pos == 0.0 { pos - sw / 2.0 } else { pos };
+                let end = if pos + step >= len { len + sw / 2.0 } else { pos + step };
+                let p0 = (a.0 + dir.0 * start, a.1 + dir.1 * start);
+                let p1 = (a.0 + dir.0 * end, a.1 + dir.1 * end);
+                mask.line(p0, p1, sw, false);
+            }
+            pos += step;
+            phase = (phase + step) % (on + off);
+        }
+    }
+}
+
+/// Text drawn into a transparent layer, then blended so alpha stays straight
+fn draw_text_block(img: &mut RgbaImage, font: &FontChain, x: f32, y: f32, size: f32, color: Rgba<u8>, text: &str) {
+    let scale = PxScale::from(size);
+    let lines: Vec<&str> = text.split('\n').collect();
+    let width = lines.iter().map(|l| font.text_width(scale, l)).fold(0.0f32, f32::max).ceil() as u32;
+    let height = (size * lines.len() as f32 * 1.2).ceil() as u32;
+    if width == 0 || height == 0 {
+        return;
+    }
+    let mut layer = RgbaImage::from_pixel(width + 2, height + 2, Rgba([color[0], color[1], color[2], 0]));
+    for (i, line) in lines.iter().enumerate() {
+        font.draw_text(&mut layer, Rgba([color[0], color[1], color[2], 255]), 0, (i as f32 * size) as i32, scale, line);
+    }
+    blend_layer(img, &layer, x.round() as i64, y.round() as i64, color[3] as f32 / 255.0);
+}
+
+/// Number centred on (cx, cy) by its inked bounds, independent of font metrics
+fn draw_centered_label(img: &mut RgbaImage, font: &FontChain, cx: f32, cy: f32, size: f32, color: Rgba<u8>, text: &str) {
+    let scale = PxScale::from(size);
+    let w = font.text_width(scale, text).ceil() as u32 + 4;
+    let h = (size * 2.0).ceil() as u32;
+    let mut layer = RgbaImage::from_pixel(w, h, Rgba([color[0], color[1], color[2], 0]));
+    font.draw_text(&mut layer, color, 2, (size / 2.0) as i32, scale, text);
+
+    let inked: Vec<(u32, u32)> = layer.enumerate_pixels().filter(|(_, _, p)| p[3] > 0).map(|(x, y, _)| (x, y)).collect();
+    if inked.is_empty() {
+        return;
+    }
+    let (min_x, max_x) = inked.iter().fold((u32::MAX, 0), |(a, b), &(x, _)| (a.min(x), b.max(x)));
+    let (min_y, max_y) = inked.iter().fold((u32::MAX, 0), |(a, b), &(_, y)| (a.min(y), b.max(y)));
+    let ox = cx - (min_x + max_x + 1) as f32 / 2.0;
+    let oy = cy - (min_y + max_y + 1) as f32 / 2.0;
+    blend_layer(img, &layer, ox.round() as i64, oy.round() as i64, 1.0);
+}
+
+fn pixelate(img: &mut RgbaImage, source: &RgbaImage, x: u32, y: u32, w: u32, h: u32, block: u32) {
+    for by in (y..y + h).step_by(block as usize) {
+        for bx in (x..x + w).step_by(block as usize) {
+            let bw = block.min(x + w - bx);
+            let bh = block.min(y + h - by);
+            // Centre sample, same as the editor preview
+            let color = *source.get_pixel(bx + bw / 2, by + bh / 2);
+            for py in by..by + bh {
+                for px in bx..bx + bw {
+                    img.put_pixel(px, py, color);
+                }
+            }
+        }
+    }
+}
+
+fn blur_area(img: &mut RgbaImage, source: &RgbaImage, x: u32, y: u32, w: u32, h: u32, sigma: f32) {
+    // Blur a margin around the area too so its edges aren't pulled towards the crop border
+    let margin = (sigma * 3.0).ceil() as u32;
+    let cx = x.saturating_sub(margin);
+    let cy = y.saturating_sub(margin);
+    let cw = (x + w + margin).min(source.width()) - cx;
+    let ch = (y + h + margin).min(source.height()) - cy;
+    let crop = image::imageops::crop_imm(source, cx, cy, cw, ch).to_image();
+    let blurred = imageproc::filter::gaussian_blur_f32(&crop, sigma.max(0.1));
+    for py in y..y + h {
+        for px in x..x + w {
+            img.put_pixel(px, py, *blurred.get_pixel(px - cx, py - cy));
+        }
+    }
+}
+
+/// Area of (x, y, w, h) in image pixels clipped to the image, or None if empty
+fn clip_area(x: f32, y: f32, w: f32, h: f32, img_w: u32, img_h: u32) -> Option<(u32, u32, u32, u32)> {
+    let (x0, x1) = (x.min(x + w).max(0.0) as u32, (x.max(x + w).ceil().max(0.0) as u32).min(img_w));
+    let (y0, y1) = (y.min(y + h).max(0.0) as u32, (y.max(y + h).ceil().max(0.0) as u32).min(img_h));
+    (x1 > x0 && y1 > y0).then(|| (x0, y0, x1 - x0, y1 - y0))
+}
+
+/// Lit area of a spotlight at native resolution; everything outside it gets dimmed
+struct Spot {
+    x: i32,
+    y: i32,
+    lit: GrayImage,
+    opacity: f32,
+}
+
+impl Spot {
+    /// Per-pixel coverage of the rect / ellipse, clipped to the image
+    fn new(area: (f32, f32, f32, f32), ellipse: bool, opacity: f32, img_w: u32, img_h: u32) -> Option<Self> {
+        let (x, y, w, h) = area;
+        let (x0, y0, x1, y1) = (x.min(x + w), y.min(y + h), x.max(x + w), y.max(y + h));
+        let (left, top, right, bottom) = pixel_box(x0, y0, x1, y1, img_w, img_h)?;
+        let (cx, cy, rx, ry) = ((x0 + x1) / 2.0, (y0 + y1) / 2.0, (x1 - x0) / 2.0, (y1 - y0) / 2.0);
+        let lit = GrayImage::from_fn((right - left) as u32, (bottom - top) as u32, |lx, ly| {
+            let (px, py) = ((left + lx as i32) as f32, (top + ly as i32) as f32);
+            let cover = if ellipse {
+                if rx <= 0.0 || ry <= 0.0 {
+                    return Luma([0]);
+                }
+                // SS x SS samples per pixel, evaluated directly instead of drawn into a buffer
+                let mut inside = 0;
+                for sy in 0..SS {
+                    for sx in 0..SS {
+                        let dx = (px + (sx as f32 + 0.5) / SS as f32 - cx) / rx;
+                        let dy = (py + (sy as f32 + 0.5) / SS as f32 - cy) / ry;
+                        if dx * dx + dy * dy <= 1.0 {
+                            inside += 1;
+                        }
+                    }
+                }
+                inside as f32 / (SS * SS) as f32
+            } else {
+                let ox = ((px + 1.0).min(x1) - px.max(x0)).max(0.0);
+                let oy = ((py + 1.0).min(y1) - py.max(y0)).max(0.0);
+                ox * oy
+            };
+            Luma([(cover * 255.0).round() as u8])
+        });
+        Some(Self { x: left, y: top, lit, opacity })
+    }
+
+    fn coverage(&self, px: u32, py: u32) -> f32 {
+        let (lx, ly) = (px as i32 - self.x, py as i32 - self.y);
+        if lx < 0 || ly < 0 || lx >= self.lit.width() as i32 || ly >= self.lit.height() as i32 {
+            return 0.0;
+        }
+        self.lit.get_pixel(lx as u32, ly as u32)[0] as f32 / 255.0
+    }
+}
+
+/// Dim everything outside the spotlight areas in one pass, so they don't darken each other
+fn apply_spotlights(img: &mut RgbaImage, spots: &[Spot]) {
+    let opacity = spots.iter().map(|s| s.opacity.clamp(0.0, 1.0)).fold(0.0f32, f32::max);
+    if opacity <= 0.0 {
+        return;
+    }
+    for (px, py, p) in img.enumerate_pixels_mut() {
+        let lit = spots.iter().map(|s| s.coverage(px, py)).fold(0.0f32, f32::max);
+        let dim = opacity * (1.0 - lit);
+        for c in 0..3 {
+            p[c] = (p[c] as f32 * (1.0 - dim)).round() as u8;
+        }
+    }
+}
+
+fn color_of(hex: &str) -> Result<Rgba<u8>, String> {
+    parse_hex_color(hex).ok_or_else(|| format!("Invalid color: {}", hex))
+}
+
+/// Render a scene onto the original capture
+///
+/// Spotlights are applied first so the other annotations stay at full
+/// brightness; everything else is drawn in order. Mosaic and blur sample the
+/// capture rather than earlier annotations, matching what the editor showed.
+pub fn render_annotations(source: &RgbaImage, scene: &AnnotationScene) -> Result<RgbaImage, String> {
+    let s = if scene.scale > 0.0 { scene.scale } else { 1.0 };
+    let (img_w, img_h) = source.dimensions();
+    let mut img = source.clone();
+
+    let spots: Vec<Spot> = scene
+        .annotations
+        .iter()
+        .filter_map(|a| match a.shape {
+            Shape::Spotlight { x, y, width, height, ellipse, opacity } => {
+                Spot::new((x * s, y * s, width * s, height * s), ellipse, opacity, img_w, img_h)
+            }
+            _ => None,
+        })
+        .collect();
+    if !spots.is_empty() {
+        apply_spotlights(&mut img, &spots);
+    }
+    // Mosaic and blur read from here, so earlier annotations never leak through
+    let base = img.clone();
+
+    let needs_font = scene.annotations.iter().any(|a| matches!(a.shape, Shape::Text { .. } | Shape::Step { .. }));
+    let font = if needs_font {
+        Some(font_chain(FontRole::Text).ok_or("Failed to load font")?)
+    } else {
+        None
+    };
+
+    for annotation in &scene.annotations {
+        match &annotation.shape {
+            Shape::Rect { x, y, width, height, style, color, stroke_width } => {
+                let color = color_of(color)?;
+                let (x, y, w, h, sw) = (x * s, y * s, width * s, height * s, stroke_width * s);
+                let (x, y, w, h) = (x.min(x + w), y.min(y + h), w.abs(), h.abs());
+                if *style == RectStyle::Filled {
+                    if let Some(mut fill) = Mask::new(x, y, x + w, y + h, img_w, img_h) {
+                        fill.rect(x, y, w, h, true);
+                        fill.composite(&mut img, Rgba([color[0], color[1], color[2], 0x40]), Blend::Normal);
+                    }
+                }
+                let dash = (*style == RectStyle::Dashed).then_some((10.0 * s, 5.0 * s));
+                let half = sw / 2.0;
+                composite_outline(
+                    &mut img,
+                    y - sw,
+                    y + h + sw,
+                    |_, _| Some((x - sw, x + w + sw)),
+                    |y0, y1| (y0 >= y + half && y1 <= y + h - half).then_some((x + half, x + w - half)),
+                    |mask| rect_outline(mask, x, y, w, h, sw, dash),
+                    color,
+                );
+            }
+            Shape::Ellipse { x, y, width, height, color, stroke_width, filled } => {
+                let color = color_of(color)?;
+                let (rx, ry, sw) = ((width * s).abs() / 2.0, (height * s).abs() / 2.0, stroke_width * s);
+                let (cx, cy) = (x * s + width * s / 2.0, y * s + height * s / 2.0);
+                if *filled {
+                    if let Some(mut mask) = Mask::new(cx - rx, cy - ry, cx + rx, cy + ry, img_w, img_h) {
+                        mask.ellipse(cx, cy, rx, ry, true);
+                        mask.composite(&mut img, color, Blend::Normal);
+                    }
+                    continue;
+                }
+                let (orx, ory, irx, iry) = (rx + sw / 2.0, ry + sw / 2.0, rx - sw / 2.0, ry - sw / 2.0);
+                composite_outline(
+                    &mut img,
+                    cy - ory,
+                    cy + ory,
+                    |y0, y1| ellipse_span(cx, cy, orx, ory, y0, y1, true),
+                    |y0, y1| ellipse_span(cx, cy, irx, iry, y0, y1, false),
+                    |mask| {
+                        mask.ellipse(cx, cy, orx, ory, true);
+                        mask.ellipse(cx, cy, irx, iry, false);
+                    },
+                    color,
+                );
+            }
+            Shape::Arrow { points, style, color, stroke_width } => {
+                let color = color_of(color)?;
+                let thick = *style == ArrowStyle::Thick;
+                let sw = stroke_width * s * if thick { 2.0 } else { 1.0 };
+                // Head size matches the editor's pointerLength / pointerWidth
+                let head_len = if thick { 15.0 } else { 10.0 } * s;
+                let head_half = if thick { 6.0 } else { 4.0 } * s;
+                let (a, b) = ((points[0] * s, points[1] * s), (points[2] * s, points[3] * s));
+                let pad = sw + head_len + head_half;
+                let Some(mut mask) = Mask::new(a.0.min(b.0) - pad, a.1.min(b.1) - pad, a.0.max(b.0) + pad, a.1.max(b.1) + pad, img_w, img_h) else {
+                    continue;
+                };
+
+                let len = ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt().max(f32::EPSILON);
+                let dir = ((b.0 - a.0) / len, (b.1 - a.1) / len);
+                let mut heads = vec![(b, dir)];
+                if *style == ArrowStyle::Double {
+                    heads.push((a, (-dir.0, -dir.1)));
+                }
+                // Shaft stops at the head bases so round caps don't poke through the tips
+                let trim = |p: (f32, f32), d: (f32, f32)| (p.0 - d.0 * head_len, p.1 - d.1 * head_len);
+                let shaft_end = trim(b, dir);
+                let shaft_start = if *style == ArrowStyle::Double { trim(a, (-dir.0, -dir.1)) } else { a };
+                mask.line(shaft_start, shaft_end, sw, true);
+                for (tip, d) in heads {
+                    let base = trim(tip, d);
+                    let n = (-d.1 * head_half, d.0 * head_half);
+                    let left = (base.0 + n.0, base.1 + n.1);
+                    let right = (base.0 - n.0, base.1 - n.1);
+                    // Filled and stroked like the editor's Konva arrow (round joins)
+                    mask.polygon(&[tip, left, right], true);
+                    mask.line(tip, left, sw, true);
+                    mask.line(left, right, sw, true);
+                    mask.line(right, tip, sw, true);
+                }
+                mask.composite(&mut img, color, Blend::Normal);
+            }
+            Shape::Freehand { points, color, stroke_width } => {
+                let color = color_of(color)?;
+                let points = pairs(points, s);
+                if let Some(mask) = polyline_mask(&points, stroke_width * s, img_w, img_h) {
+                    mask.composite(&mut img, color, Blend::Normal);
+                }
+            }
+            Shape::Highlighter { points, color, stroke_width } => {
+                let color = color_of(color)?;
+                let points = pairs(points, s);
+                if let Some(mask) = polyline_mask(&points, stroke_width * s, img_w, img_h) {
+                    mask.composite(&mut img, color, Blend::Multiply);
+                }
+            }
+            Shape::Text { x, y, text, font_size, color } => {
+                if let Some(ref font) = font {
+                    draw_text_block(&mut img, font, x * s, y * s, font_size * s, color_of(color)?, text);
+                }
+            }
+            Shape::Step { x, y, number, color, radius } => {
+                let color = color_of(color)?;
+                let (cx, cy, r) = (x * s, y * s, radius * s);
+                if let Some(mut mask) = Mask::new(cx - r, cy - r, cx + r, cy + r, img_w, img_h) {
+                    mask.circle(cx, cy, r, true);
+                    mask.composite(&mut img, color, Blend::Normal);
+                }
+                // Dark digits on light badges (e.g. white or yellow), white otherwise
+                let luma = 0.299 * color[0] as f32 + 0.587 * color[1] as f32 + 0.114 * color[2] as f32;
+                let ink = if luma > 170.0 { Rgba([24, 24, 24, 255]) } else { Rgba([255, 255, 255, 255]) };
+                if let Some(ref font) = font {
+                    let label = number.to_string();
+                    let size = if label.len() > 1 { r * 1.0 } else { r * 1.25 };
+                    draw_centered_label(&mut img, font, cx, cy, size, ink, &label);
+                }
+            }
+            Shape::Mosaic { x, y, width, height, style, block_size } => {
+                let Some((ax, ay, aw, ah)) = clip_area(x * s, y * s, width * s, height * s, img_w, img_h) else {
+                    continue;
+                };
+                match style {
+                    MosaicStyle::Pixelate => {
+                        let block = (block_size * s).round().max(1.0) as u32;
+                        pixelate(&mut img, &base, ax, ay, aw, ah, block);
+                    }
+                    // Same strength as the editor's CSS blur(8px)
+                    MosaicStyle::Blur => blur_area(&mut img, &base, ax, ay, aw, ah, 8.0 * s),
+                }
+            }
+            Shape::Spotlight { .. } => {}
+        }
+    }
+
+    Ok(img)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_editor_scene() {
+        let json = r##"{
+            "scale": 2,
+            "annotations": [
+                {"id": "a1", "type": "rect", "x": 1, "y": 2, "width": 30, "height": 20,
+                 "style": "dashed", "color": "#CC785C", "strokeWidth": 3},
+                {"id": "a2", "type": "arrow", "points": [0, 0, 10, 10], "style": "thick", "color": "#D9534F", "strokeWidth": 2},
+                {"type": "mosaic", "x": 0, "y": 0, "width": 8, "height": 8, "style": "blur", "blockSize": 10},
+                {"type": "step", "x": 5, "y": 5, "number": 3, "color": "#5BC0DE"}
+            ]
+        }"##;
+        let scene: AnnotationScene = serde_json::from_str(json).unwrap();
+        assert_eq!(scene.scale, 2.0);
+        assert_eq!(scene.annotations[0].id, "a1");
+        assert!(matches!(
+            scene.annotations[0].shape,
+            Shape::Rect { style: RectStyle::Dashed, stroke_width, .. } if stroke_width == 3.0
+        ));
+        assert!(matches!(scene.annotations[3].shape, Shape::Step { radius, .. } if radius == 14.0));
+
+        // Round-trips with the same field names the editor uses
+        let back = serde_json::to_value(&scene).unwrap();
+        assert_eq!(back["annotations"][0]["strokeWidth"], 3.0);
+        assert_eq!(back["annotations"][0]["type"], "rect");
+    }
+
+    #[test]
+    fn test_rect_outline_and_spotlight() {
+        let source = RgbaImage::from_pixel(40, 40, Rgba([200, 200, 200, 255]));
+        let scene = AnnotationScene {
+            scale: 1.0,
+            annotations: vec![
+                Annotation {
+                    id: String::new(),
+                    shape: Shape::Rect {
+                        x: 10.0,
+                        y: 10.0,
+                        width: 20.0,
+                        height: 20.0,
+                        style: RectStyle::Solid,
+                        color: "#FF0000".to_string(),
+                        stroke_width: 2.0,
+                    },
+                },
+                Annotation {
+                    id: String::new(),
+                    shape: Shape::Spotlight { x: 5.0, y: 5.0, width: 30.0, height: 30.0, ellipse: false, opacity: 0.5 },
+                },
+            ],
+        };
+        let out = render_annotations(&source, &scene).unwrap();
+        assert_eq!(*out.get_pixel(10, 20), Rgba([255, 0, 0, 255])); // on the stroke
+        assert_eq!(*out.get_pixel(20, 20), Rgba([200, 200, 200, 255])); // inside, untouched
+        assert_eq!(*out.get_pixel(1, 1), Rgba([100, 100, 100, 255])); // dimmed outside
+    }
+
+    #[test]
+    fn test_out_of_range_coordinates_are_clipped() {
+        let source = RgbaImage::from_pixel(20, 20, Rgba([200, 200, 200, 255]));
+        let rect = |x: f32, y: f32, width: f32, height: f32| Annotation {
+            id: String::new(),
+            shape: Shape::Rect {
+                x,
+                y,
+                width,
+                height,
+                style: RectStyle::Solid,
+                color: "#FF0000".to_string(),
+                stroke_width: 2.0,
+            },
+        };
+        let scene = AnnotationScene {
+            scale: 1.0,
+            annotations: vec![
+                rect(f32::MAX, f32::MAX, f32::MAX, f32::MAX),
+                rect(-1e30, -1e30, 1e30, 1e30),
+                rect(f32::NAN, 0.0, f32::INFINITY, 5.0),
+                Annotation {
+                    id: String::new(),
+                    shape: Shape::Spotlight { x: -1e30, y: -1e30, width: 2e30, height: 2e30, ellipse: true, opacity: 0.5 },
+                },
+            ],
+        };
+        let out = render_annotations(&source, &scene).unwrap();
+        assert_eq!(out.dimensions(), (20, 20));
+        // The huge ellipse covers the whole canvas, so nothing is dimmed
+        assert_eq!(*out.get_pixel(10, 10), Rgba([200, 200, 200, 255]));
+    }
+
+    #[test]
+    fn test_banded_outlines_match_a_single_mask() {
+        let base = RgbaImage::from_pixel(300, 200, Rgba([200, 200, 200, 255]));
+        let color = Rgba([255, 0, 0, 255]);
+        let whole = |x0: f32, y0: f32, x1: f32, y1: f32, draw: &dyn Fn(&mut Mask)| {
+            let mut img = base.clone();
+            let mut mask = Mask::new(x0, y0, x1, y1, 300, 200).unwrap();
+            draw(&mut mask);
+            mask.composite(&mut img, color, Blend::Normal);
+            img
+        };
+
+        let (cx, cy, orx, ory, irx, iry) = (150.3f32, 99.6f32, 140.0f32, 90.0f32, 134.0f32, 84.0f32);
+        let ellipse = |mask: &mut Mask| {
+            mask.ellipse(cx, cy, orx, ory, true);
+            mask.ellipse(cx, cy, irx, iry, false);
+        };
+        let mut banded = base.clone();
+        composite_outline(
+            &mut banded,
+            cy - ory,
+            cy + ory,
+            |y0, y1| ellipse_span(cx, cy, orx, ory, y0, y1, true),
+            |y0, y1| ellipse_span(cx, cy, irx, iry, y0, y1, false),
+            ellipse,
+            color,
+        );
+        assert!(banded == whole(cx - orx - 6.0, cy - ory - 6.0, cx + orx + 6.0, cy + ory + 6.0, &ellipse));
+
+        let (x, y, w, h, sw) = (10.5f32, 12.0f32, 270.0f32, 170.0f32, 6.0f32);
+        let rect = |mask: &mut Mask| rect_outline(mask, x, y, w, h, sw, Some((10.0, 5.0)));
+        let mut banded = base.clone();
+        composite_outline(
+            &mut banded,
+            y - sw,
+            y + h + sw,
+            |_, _| Some((x - sw, x + w + sw)),
+            |y0, y1| (y0 >= y + sw / 2.0 && y1 <= y + h - sw / 2.0).then_some((x + sw / 2.0, x + w - sw / 2.0)),
+            rect,
+            color,
+        );
+        assert!(banded == whole(x - sw, y - sw, x + w + sw, y + h + sw, &rect));
+    }
+}
@@ -0,0 +1,147 @@
+//! Re-editable annotations stored inside the saved PNG
+//!
+//! Annotated screenshots carry two private chunks next to the rendered
+//! pixels: `lvOR` holds the unannotated capture (a complete PNG) and `lvAN`
+//! the scene as JSON. Both are marked unsafe-to-copy, so other editors that
+//! change the image drop them instead of keeping stale layers. Captions,
+//! moves and trash keep working because everything lives in one file.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::annotate::{render_annotations, AnnotationScene};
+use crate::caption::{get_png_caption, parse_png_chunks, set_png_caption, write_png_chunk, PNG_SIGNATURE};
+use crate::output::write_file_atomic;
+use crate::watermark::add_screenshot_watermark;
+
+const ORIGINAL_CHUNK: &[u8; 4] = b"lvOR";
+const SCENE_CHUNK: &[u8; 4] = b"lvAN";
+
+/// Scene plus what's needed to re-render it identically
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnnotationRecord {
+    pub scene: AnnotationScene,
+    #[serde(default)]
+    pub screenshot_number: Option<u64>, // Corner watermark number, reused on re-render
+}
+
+/// What the editor needs to reopen a saved screenshot
+#[derive(Clone, Debug, Serialize)]
+pub struct EditableAnnotations {
+    pub original: String, // data:image/png;base64,... like capture_region_preview
+    pub scene: AnnotationScene,
+}
+
+pub fn encode_png(img: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut buf = std::io::Cursor::new(Vec::new());
+    img.write_to(&mut buf, image::ImageFormat::Png).map_err(|e| e.to_string())?;
+    Ok(buf.into_inner())
+}
+
+/// Add (or replace) the original and scene chunks in a PNG
+pub fn embed_layers(png: &[u8], original: &RgbaImage, record: &AnnotationRecord) -> Result<Vec<u8>, String> {
+    let original_png = encode_png(original)?;
+    let scene_json = serde_json::to_vec(record).map_err(|e| e.to_string())?;
+
+    let chunks = parse_png_chunks(png)?;
+    let mut out = Vec::with_capacity(png.len() + original_png.len() + scene_json.len() + 64);
+    out.extend_from_slice(&PNG_SIGNATURE);
+    for (chunk_type, body) in chunks {
+        if &chunk_type == ORIGINAL_CHUNK || &chunk_type == SCENE_CHUNK {
+            continue;
+        }
+        if &chunk_type == b"IEND" {
+            write_png_chunk(&mut out, SCENE_CHUNK, &scene_json);
+            write_png_chunk(&mut out, ORIGINAL_CHUNK, &original_png);
+        }
+        write_png_chunk(&mut out, &chunk_type, body);
+    }
+    Ok(out)
+}
+
+/// Embedded original PNG bytes and annotation record, if the file has them
+pub fn read_layers(png: &[u8]) -> Option<(&[u8], AnnotationRecord)> {
+    let chunks = parse_png_chunks(png).ok()?;
+    let original = chunks.iter().find(|(t, _)| t == ORIGINAL_CHUNK).map(|(_, b)| *b)?;
+    let scene = chunks.iter().find(|(t, _)| t == SCENE_CHUNK).map(|(_, b)| *b)?;
+    let record = serde_json::from_slice(scene)
+        .map_err(|e| println!("[annotation_layers] Unreadable scene: {}", e))
+        .ok()?;
+    Some((original, record))
+}
+
+/// Re-render a saved screenshot from its original with a new scene, in place
+pub fn rerender_file(path: &Path, scene: AnnotationScene) -> Result<(), String> {
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    let (original_png, record) = read_layers(&data).ok_or("No editable annotations in this file")?;
+    let original = image::load_from_memory(original_png)
+        .map_err(|e| format!("Image load error: {}", e))?
+        .to_rgba8();
+
+    let mut img = render_annotations(&original, &scene)?;
+    if let Some(number) = record.screenshot_number {
+        add_screenshot_watermark(&mut img, number);
+    }
+
+    let record = AnnotationRecord { scene, ..record };
+    let mut updated = embed_layers(&encode_png(&img)?, &original, &record)?;
+    if let Some(caption) = get_png_caption(&data) {
+        updated = set_png_caption(&updated, &caption)?;
+    }
+    write_file_atomic(path, |tmp| std::fs::write(tmp, &updated).map_err(|e| e.to_string()))
+}
+
+/// Tauri command: original capture and scene of a saved screenshot (None if flattened)
+#[tauri::command]
+pub fn get_annotation_layers(path: String) -> Result<Option<EditableAnnotations>, String> {
+    if !path.to_lowercase().ends_with(".png") {
+        return Ok(None);
+    }
+    let data = std::fs::read(&path).map_err(|e| e.to_string())?;
+    Ok(read_layers(&data).map(|(original, record)| EditableAnnotations {
+        original: format!("data:image/png;base64,{}", STANDARD.encode(original)),
+        scene: record.scene,
+    }))
+}
+
+/// Tauri command: replace the annotations of a saved screenshot and re-render it
+#[tauri::command]
+pub fn update_annotations(path: String, scene: AnnotationScene) -> Result<String, String> {
+    println!("[update_annotations] {} annotations -> {}", scene.annotations.len(), path);
+    rerender_file(Path::new(&path), scene)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn test_layers_roundtrip_keeps_caption() {
+        let original = RgbaImage::from_pixel(8, 8, Rgba([10, 20, 30, 255]));
+        let rendered = RgbaImage::from_pixel(8, 8, Rgba([200, 0, 0, 255]));
+        let scene: AnnotationScene =
+            serde_json::from_str(r##"{"annotations": [{"id": "r", "type": "rect", "x": 1, "y": 1, "width": 4, "height": 4, "color": "#FF0000"}]}"##)
+                .unwrap();
+        let record = AnnotationRecord { scene, screenshot_number: Some(7) };
+
+        let png = set_png_caption(&encode_png(&rendered).unwrap(), "hello").unwrap();
+        let embedded = embed_layers(&png, &original, &record).unwrap();
+
+        // Rendered pixels and caption are untouched
+        assert_eq!(image::load_from_memory(&embedded).unwrap().to_rgba8(), rendered);
+        assert_eq!(get_png_caption(&embedded).as_deref(), Some("hello"));
+
+        let (original_png, back) = read_layers(&embedded).unwrap();
+        assert_eq!(image::load_from_memory(original_png).unwrap().to_rgba8(), original);
+        assert_eq!(back.screenshot_number, Some(7));
+        assert_eq!(back.scene.annotations[0].id, "r");
+
+        // Embedding again replaces rather than duplicates
+        let twice = embed_layers(&embedded, &original, &back).unwrap();
+        assert_eq!(parse_png_chunks(&twice).unwrap().iter().filter(|(t, _)| t == SCENE_CHUNK).count(), 1);
+    }
+}
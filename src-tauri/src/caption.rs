@@ -0,0 +1,733 @@
+//! Portable caption storage
+//!
+//! Captions live inside the image file where the format allows it:
+//! - PNG: iTXt chunk with keyword "Description"
+//! - JPEG: XMP packet (dc:description) in an APP1 segment
+//! - WebP: `XMP ` chunk (simple files are upgraded to the VP8X container)
+//! - GIF: "XMP DataXMP" application extension
+//! - Anything else: XMP sidecar next to the file (`name.bmp.xmp`)
+//!
+//! On macOS the Finder comment is still written as an extra, and read as a
+//! fallback for captions saved by older versions.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::output::write_file_atomic;
+
+pub(crate) const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+const PNG_CAPTION_KEYWORD: &[u8] = b"Description";
+const XMP_NAMESPACE: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+
+/// Save caption for an image (empty caption removes it)
+pub fn write_caption(path: &str, caption: &str) -> Result<(), String> {
+    let file_path = Path::new(path);
+    let caption = caption.trim();
+
+    let embedded = match extension_of(file_path).as_str() {
+        "png" => rewrite_file(file_path, |data| set_png_caption(data, caption)),
+        "jpg" | "jpeg" => rewrite_file(file_path, |data| set_jpeg_caption(data, caption)),
+        "webp" => rewrite_file(file_path, |data| set_webp_caption(data, caption)),
+        "gif" => rewrite_file(file_path, |data| set_gif_caption(data, caption)),
+        _ => Err("No embedded caption support for this format".to_string()),
+    };
+
+    match embedded {
+        Ok(()) => {
+            // Drop a stale sidecar so it can't shadow the embedded caption
+            let _ = fs::remove_file(sidecar_path(file_path));
+        }
+        Err(e) => {
+            println!("[caption] Embedded write skipped ({}), using sidecar", e);
+            write_sidecar(file_path, caption)?;
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    if let Err(e) = write_finder_comment(path, caption) {
+        println!("[caption] Finder comment error: {}", e);
+    }
+
+    Ok(())
+}
+
+/// Read caption for an image: embedded metadata, then sidecar, then Finder comment
+pub fn read_caption(path: &str) -> Option<String> {
+    let file_path = Path::new(path);
+
+    let embedded = match extension_of(file_path).as_str() {
+        "png" => fs::read(file_path).ok().and_then(|d| get_png_caption(&d)),
+        "jpg" | "jpeg" => fs::read(file_path).ok().and_then(|d| get_jpeg_caption(&d)),
+        "webp" => fs::read(file_path).ok().and_then(|d| get_webp_caption(&d)),
+        "gif" => fs::read(file_path).ok().and_then(|d| get_gif_caption(&d)),
+        _ => None,
+    };
+
+    embedded
+        .or_else(|| read_sidecar(file_path))
+        .or_else(|| read_finder_comment(path))
+        .filter(|c| !c.is_empty())
+}
+
+/// Sidecar path for an image: `shot.gif` -> `shot.gif.xmp`
+pub fn sidecar_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".xmp");
+    path.with_file_name(name)
+}
+
+fn extension_of(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+fn rewrite_file<F>(path: &Path, edit: F) -> Result<(), String>
+where
+    F: FnOnce(&[u8]) -> Result<Vec<u8>, String>,
+{
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+    let updated = edit(&data)?;
+    write_file_atomic(path, |tmp| fs::write(tmp, &updated).map_err(|e| e.to_string()))?;
+
+    // Keep the original timestamp so history order doesn't change on caption edits
+    if let Some(modified) = modified {
+        if let Ok(file) = fs::OpenOptions::new().write(true).open(path) {
+            let _ = file.set_modified(modified);
+        }
+    }
+    Ok(())
+}
+
+fn write_sidecar(path: &Path, caption: &str) -> Result<(), String> {
+    let sidecar = sidecar_path(path);
+    if caption.is_empty() {
+        if sidecar.exists() {
+            fs::remove_file(&sidecar).map_err(|e| e.to_string())?;
+        }
+        return Ok(());
+    }
+    let packet = build_xmp_packet(caption);
+    write_file_atomic(&sidecar, |tmp| fs::write(tmp, &packet).map_err(|e| e.to_string()))
+}
+
+fn read_sidecar(path: &Path) -> Option<String> {
+    let data = fs::read_to_string(sidecar_path(path)).ok()?;
+    parse_xmp_description(&data)
+}
+
+// ============ PNG iTXt ============
+
+/// Replace (or remove, if empty) the Description iTXt chunk
+pub fn set_png_caption(data: &[u8], caption: &str) -> Result<Vec<u8>, String> {
+    let chunks = parse_png_chunks(data)?;
+    let mut out = Vec::with_capacity(data.len() + caption.len() + 64);
+    out.extend_from_slice(&PNG_SIGNATURE);
+
+    for (chunk_type, body) in chunks {
+        if is_png_caption_chunk(&chunk_type, body) {
+            continue;
+        }
+        if &chunk_type == b"IEND" && !caption.is_empty() {
+            let mut itxt = Vec::with_capacity(caption.len() + 16);
+            itxt.extend_from_slice(PNG_CAPTION_KEYWORD);
+            itxt.push(0); // keyword terminator
+            itxt.push(0); // compression flag: uncompressed
+            itxt.push(0); // compression method
+            itxt.push(0); // empty language tag
+            itxt.push(0); // empty translated keyword
+            itxt.extend_from_slice(caption.as_bytes());
+            write_png_chunk(&mut out, b"iTXt", &itxt);
+        }
+        write_png_chunk(&mut out, &chunk_type, body);
+    }
+
+    Ok(out)
+}
+
+/// Read the Description iTXt (or legacy tEXt) chunk
+pub fn get_png_caption(data: &[u8]) -> Option<String> {
+    let chunks = parse_png_chunks(data).ok()?;
+    for (chunk_type, body) in chunks {
+        if !is_png_caption_chunk(&chunk_type, body) {
+            continue;
+        }
+        let text = &body[PNG_CAPTION_KEYWORD.len() + 1..];
+        if &chunk_type == b"tEXt" {
+            // Latin-1 text
+            return Some(text.iter().map(|&b| b as char).collect());
+        }
+        // iTXt: compression flag, method, language\0, translated keyword\0, text
+        if text.len() < 2 || text[0] != 0 {
+            continue; // compressed iTXt is never written by us
+        }
+        let rest = &text[2..];
+        let lang_end = rest.iter().position(|&b| b == 0)?;
+        let rest = &rest[lang_end + 1..];
+        let kw_end = rest.iter().position(|&b| b == 0)?;
+        return String::from_utf8(rest[kw_end + 1..].to_vec()).ok();
+    }
+    None
+}
+
+fn is_png_caption_chunk(chunk_type: &[u8; 4], body: &[u8]) -> bool {
+    (chunk_type == b"iTXt" || chunk_type == b"tEXt")
+        && body.len() > PNG_CAPTION_KEYWORD.len()
+        && body.starts_with(PNG_CAPTION_KEYWORD)
+        && body[PNG_CAPTION_KEYWORD.len()] == 0
+}
+
+/// (chunk type, chunk body) borrowed from the file data
+pub(crate) type PngChunk<'a> = ([u8; 4], &'a [u8]);
+
+pub(crate) fn parse_png_chunks(data: &[u8]) -> Result<Vec<PngChunk<'_>>, String> {
+    if data.len() < 8 || data[..8] != PNG_SIGNATURE {
+        return Err("Not a PNG file".to_string());
+    }
+
+    let mut chunks = Vec::new();
+    let mut pos = 8;
+    while pos + 12 <= data.len() {
+        let len = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+        let chunk_type = [data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]];
+        let body_start = pos + 8;
+        let body_end = body_start.checked_add(len).ok_or("Corrupt PNG chunk")?;
+        if body_end + 4 > data.len() {
+            return Err("Truncated PNG chunk".to_string());
+        }
+        chunks.push((chunk_type, &data[body_start..body_end]));
+        pos = body_end + 4; // skip CRC
+        if &chunk_type == b"IEND" {
+            return Ok(chunks);
+        }
+    }
+
+    Err("PNG has no IEND chunk".to_string())
+}
+
+pub(crate) fn write_png_chunk(out: &mut Vec<u8>, chunk_type: &[u8; 4], body: &[u8]) {
+    out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    out.extend_from_slice(chunk_type);
+    out.extend_from_slice(body);
+    let mut crc = crc32_update(0xFFFF_FFFF, chunk_type);
+    crc = crc32_update(crc, body);
+    out.extend_from_slice(&(crc ^ 0xFFFF_FFFF).to_be_bytes());
+}
+
+/// CRC-32 (ISO-HDLC) as used by PNG
+fn crc32_update(mut crc: u32, bytes: &[u8]) -> u32 {
+    for &b in bytes {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    crc
+}
+
+// ============ JPEG XMP ============
+
+/// Replace (or remove, if empty) the XMP APP1 segment
+pub fn set_jpeg_caption(data: &[u8], caption: &str) -> Result<Vec<u8>, String> {
+    let segments = parse_jpeg_header(data)?;
+
+    let xmp_segment = if caption.is_empty() {
+        None
+    } else {
+        let packet = build_xmp_packet(caption);
+        let payload_len = XMP_NAMESPACE.len() + packet.len();
+        if payload_len + 2 > u16::MAX as usize {
+            return Err("Caption too long for JPEG XMP segment".to_string());
+        }
+        let mut seg = Vec::with_capacity(payload_len + 4);
+        seg.extend_from_slice(&[0xFF, 0xE1]);
+        seg.extend_from_slice(&((payload_len + 2) as u16).to_be_bytes());
+        seg.extend_from_slice(XMP_NAMESPACE);
+        seg.extend_from_slice(packet.as_bytes());
+        Some(seg)
+    };
+
+    let mut out = Vec::with_capacity(data.len() + 1024);
+    out.extend_from_slice(&data[..2]); // SOI
+
+    // Keep JFIF (APP0) first, then our XMP, then everything else
+    let mut inserted = false;
+    let mut header_end = 2;
+    for (marker, start, end) in &segments {
+        header_end = *end;
+        if *marker == 0xE1 && data[start + 4..*end].starts_with(XMP_NAMESPACE) {
+            continue;
+        }
+        if !inserted && *marker != 0xE0 {
+            if let Some(ref seg) = xmp_segment {
+                out.extend_from_slice(seg);
+            }
+            inserted = true;
+        }
+        out.extend_from_slice(&data[*start..*end]);
+    }
+    if !inserted {
+        if let Some(ref seg) = xmp_segment {
+            out.extend_from_slice(seg);
+        }
+    }
+    out.extend_from_slice(&data[header_end..]);
+
+    Ok(out)
+}
+
+/// Read dc:description from the XMP APP1 segment
+pub fn get_jpeg_caption(data: &[u8]) -> Option<String> {
+    let segments = parse_jpeg_header(data).ok()?;
+    segments.iter().find_map(|(marker, start, end)| {
+        let payload = &data[start + 4..*end];
+        if *marker == 0xE1 && payload.starts_with(XMP_NAMESPACE) {
+            let xml = String::from_utf8_lossy(&payload[XMP_NAMESPACE.len()..]);
+            parse_xmp_description(&xml)
+        } else {
+            None
+        }
+    })
+}
+
+/// Header segments up to (not including) SOS: (marker, start, end) byte ranges
+fn parse_jpeg_header(data: &[u8]) -> Result<Vec<(u8, usize, usize)>, String> {
+    if data.len() < 4 || data[0] != 0xFF || data[1] != 0xD8 {
+        return Err("Not a JPEG file".to_string());
+    }
+
+    let mut segments = Vec::new();
+    let mut pos = 2;
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xFF {
+            return Err("Corrupt JPEG marker".to_string());
+        }
+        let marker = data[pos + 1];
+        if marker == 0xFF {
+            pos += 1; // fill byte
+            continue;
+        }
+        if marker == 0xDA || marker == 0xD9 {
+            return Ok(segments); // start of scan / end of image
+        }
+        let len = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let end = pos + 2 + len;
+        if len < 2 || end > data.len() {
+            return Err("Truncated JPEG segment".to_string());
+        }
+        segments.push((marker, pos, end));
+        pos = end;
+    }
+
+    Err("JPEG has no scan data".to_string())
+}
+
+// ============ WebP XMP ============
+
+const VP8X_FLAG_ALPHA: u8 = 0x10;
+const VP8X_FLAG_XMP: u8 = 0x04;
+
+/// Replace (or remove, if empty) the `XMP ` chunk
+pub fn set_webp_caption(data: &[u8], caption: &str) -> Result<Vec<u8>, String> {
+    let chunks = parse_webp_chunks(data)?;
+
+    // Metadata needs the extended format; describe the canvas from the bitstream
+    let vp8x = match chunks.iter().find(|(fourcc, _)| fourcc == b"VP8X") {
+        Some((_, body)) if body.len() >= 10 => body.to_vec(),
+        Some(_) => return Err("Corrupt VP8X chunk".to_string()),
+        None => {
+            let (width, height, alpha) = webp_canvas(&chunks)?;
+            let mut body = vec![if alpha { VP8X_FLAG_ALPHA } else { 0 }, 0, 0, 0];
+            body.extend_from_slice(&(width - 1).to_le_bytes()[..3]);
+            body.extend_from_slice(&(height - 1).to_le_bytes()[..3]);
+            body
+        }
+    };
+    let mut vp8x = vp8x;
+    if caption.is_empty() {
+        vp8x[0] &= !VP8X_FLAG_XMP;
+    } else {
+        vp8x[0] |= VP8X_FLAG_XMP;
+    }
+
+    let mut body = Vec::with_capacity(data.len() + caption.len() + 1024);
+    write_riff_chunk(&mut body, b"VP8X", &vp8x);
+    for (fourcc, chunk) in &chunks {
+        if fourcc != b"VP8X" && fourcc != b"XMP " {
+            write_riff_chunk(&mut body, fourcc, chunk);
+        }
+    }
+    // The spec puts XMP last, after the image data
+    if !caption.is_empty() {
+        write_riff_chunk(&mut body, b"XMP ", build_xmp_packet(caption).as_bytes());
+    }
+
+    let riff_size = u32::try_from(body.len() + 4).map_err(|_| "WebP file too large".to_string())?;
+    let mut out = Vec::with_capacity(body.len() + 12);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&riff_size.to_le_bytes());
+    out.extend_from_slice(b"WEBP");
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+/// Read dc:description from the `XMP ` chunk
+pub fn get_webp_caption(data: &[u8]) -> Option<String> {
+    let chunks = parse_webp_chunks(data).ok()?;
+    let (_, xmp) = chunks.iter().find(|(fourcc, _)| fourcc == b"XMP ")?;
+    parse_xmp_description(&String::from_utf8_lossy(xmp))
+}
+
+/// Canvas size and alpha of a simple (VP8 / VP8L) WebP
+fn webp_canvas(chunks: &[RiffChunk]) -> Result<(u32, u32, bool), String> {
+    for (fourcc, body) in chunks {
+        match fourcc {
+            // Lossless: signature 0x2f, then 14-bit width-1, 14-bit height-1, alpha bit
+            b"VP8L" if body.len() >= 5 && body[0] == 0x2f => {
+                let bits = u32::from_le_bytes([body[1], body[2], body[3], body[4]]);
+                return Ok(((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1, (bits >> 28) & 1 == 1));
+            }
+            // Lossy: 3-byte frame tag, start code 9d 01 2a, then 14-bit width and height
+            b"VP8 " if body.len() >= 10 && body[3..6] == [0x9d, 0x01, 0x2a] => {
+                let width = u16::from_le_bytes([body[6], body[7]]) as u32 & 0x3fff;
+                let height = u16::from_le_bytes([body[8], body[9]]) as u32 & 0x3fff;
+                return Ok((width, height, false));
+            }
+            _ => {}
+        }
+    }
+    Err("WebP has no image data".to_string())
+}
+
+/// (fourcc, chunk body) borrowed from the file data
+type RiffChunk<'a> = ([u8; 4], &'a [u8]);
+
+fn parse_webp_chunks(data: &[u8]) -> Result<Vec<RiffChunk<'_>>, String> {
+    if data.len() < 12 || &data[..4] != b"RIFF" || &data[8..12] != b"WEBP" {
+        return Err("Not a WebP file".to_string());
+    }
+
+    let mut chunks = Vec::new();
+    let mut pos = 12;
+    while pos + 8 <= data.len() {
+        let fourcc = [data[pos], data[pos + 1], data[pos + 2], data[pos + 3]];
+        let len = u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]]) as usize;
+        let body_end = (pos + 8).checked_add(len).ok_or("Corrupt WebP chunk")?;
+        if body_end > data.len() {
+            return Err("Truncated WebP chunk".to_string());
+        }
+        chunks.push((fourcc, &data[pos + 8..body_end]));
+        pos = body_end + len % 2; // chunks are padded to even length
+    }
+    Ok(chunks)
+}
+
+fn write_riff_chunk(out: &mut Vec<u8>, fourcc: &[u8; 4], body: &[u8]) {
+    out.extend_from_slice(fourcc);
+    out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    out.extend_from_slice(body);
+    if body.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+// ============ GIF XMP ============
+
+const GIF_XMP_APP_ID: &[u8] = b"XMP DataXMP";
+
+/// Replace (or remove, if empty) the XMP application extension
+///
+/// XMP is stored raw rather than in sub-blocks; the 258-byte "magic trailer"
+/// lets decoders that walk sub-blocks skip over it.
+pub fn set_gif_caption(data: &[u8], caption: &str) -> Result<Vec<u8>, String> {
+    let blocks = parse_gif_blocks(data)?;
+    let header_end = blocks.first().map(|&(start, _)| start).unwrap_or(data.len());
+
+    let mut out = Vec::with_capacity(data.len() + caption.len() + 1024);
+    out.extend_from_slice(&data[..header_end]);
+    for &(start, end) in &blocks {
+        if is_gif_xmp_block(&data[start..end]) {
+            continue;
+        }
+        if data[start] == 0x3B && !caption.is_empty() {
+            out.extend_from_slice(&[0x21, 0xFF, GIF_XMP_APP_ID.len() as u8]);
+            out.extend_from_slice(GIF_XMP_APP_ID);
+            out.extend_from_slice(build_xmp_packet(caption).as_bytes());
+            out.push(0x01);
+            out.extend((0..=0xFFu8).rev());
+            out.push(0x00);
+        }
+        out.extend_from_slice(&data[start..end]);
+    }
+    Ok(out)
+}
+
+/// Read dc:description from the XMP application extension
+pub fn get_gif_caption(data: &[u8]) -> Option<String> {
+    let blocks = parse_gif_blocks(data).ok()?;
+    blocks.iter().find_map(|&(start, end)| {
+        let block = &data[start..end];
+        if is_gif_xmp_block(block) {
+            let xmp = &block[3 + GIF_XMP_APP_ID.len()..];
+            parse_xmp_description(&String::from_utf8_lossy(xmp))
+        } else {
+            None
+        }
+    })
+}
+
+fn is_gif_xmp_block(block: &[u8]) -> bool {
+    block.len() > 3 + GIF_XMP_APP_ID.len()
+        && block[..3] == [0x21, 0xFF, GIF_XMP_APP_ID.len() as u8]
+        && &block[3..3 + GIF_XMP_APP_ID.len()] == GIF_XMP_APP_ID
+}
+
+/// Byte ranges of the blocks after the header and global colour table, ending with the trailer
+fn parse_gif_blocks(data: &[u8]) -> Result<Vec<(usize, usize)>, String> {
+    if data.len() < 13 || !(data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a")) {
+        return Err("Not a GIF file".to_string());
+    }
+    let color_table = |flags: u8| if flags & 0x80 != 0 { 3 << ((flags & 0x07) + 1) } else { 0 };
+    let truncated = || "Truncated GIF".to_string();
+
+    // Sub-blocks: length byte + data, until a zero length
+    let skip_sub_blocks = |mut pos: usize| -> Result<usize, String> {
+        loop {
+            let len = *data.get(pos).ok_or_else(truncated)? as usize;
+            pos += 1 + len;
+            if len == 0 {
+                return Ok(pos);
+            }
+        }
+    };
+
+    let mut blocks = Vec::new();
+    let mut pos = 13 + color_table(data[10]);
+    loop {
+        let start = pos;
+        match *data.get(pos).ok_or_else(truncated)? {
+            0x21 => {
+                // Extension: introducer, label, sub-blocks
+                pos = skip_sub_blocks(pos + 2)?;
+            }
+            0x2C => {
+                // Image descriptor, local colour table, LZW code size, image data
+                let flags = *data.get(pos + 9).ok_or_else(truncated)?;
+                pos = skip_sub_blocks(pos + 10 + color_table(flags) + 1)?;
+            }
+            0x3B => {
+                blocks.push((start, start + 1));
+                return Ok(blocks);
+            }
+            b => return Err(format!("Unknown GIF block 0x{:02x}", b)),
+        }
+        if pos > data.len() {
+            return Err(truncated());
+        }
+        blocks.push((start, pos));
+    }
+}
+
+// ============ XMP packet ============
+
+fn build_xmp_packet(caption: &str) -> String {
+    format!(
+        concat!(
+            "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n",
+            "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n",
+            " <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n",
+            "  <rdf:Description rdf:about=\"\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n",
+            "   <dc:description><rdf:Alt><rdf:li xml:lang=\"x-default\">{}</rdf:li></rdf:Alt></dc:description>\n",
+            "  </rdf:Description>\n",
+            " </rdf:RDF>\n",
+            "</x:xmpmeta>\n",
+            "<?xpacket end=\"w\"?>"
+        ),
+        xml_escape(caption)
+    )
+}
+
+fn parse_xmp_description(xml: &str) -> Option<String> {
+    let desc_start = xml.find("<dc:description")?;
+    let desc = &xml[desc_start..];
+    let desc_end = desc.find("</dc:description>")?;
+    let desc = &desc[..desc_end];
+
+    let li_start = desc.find("<rdf:li")?;
+    let li = &desc[li_start..];
+    let text_start = li.find('>')? + 1;
+    let text_end = li.find("</rdf:li>")?;
+    if text_end < text_start {
+        return None;
+    }
+    Some(xml_unescape(&li[text_start..text_end]))
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#10;", "\n")
+        .replace("&amp;", "&")
+}
+
+// ============ macOS Finder comment ============
+
+#[cfg(target_os = "macos")]
+fn write_finder_comment(path: &str, comment: &str) -> Result<(), String> {
+    // Set Finder comment using AppleScript via stdin (handles unicode properly)
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    // AppleScript to set Finder comment
+    let script = format!(
+        r#"set theFile to POSIX file "{}" as alias
+tell application "Finder" to set comment of theFile to "{}""#,
+        path.replace("\\", "\\\\").replace("\"", "\\\""),
+        comment.replace("\\", "\\\\").replace("\"", "\\\"")
+    );
+
+    let mut child = Command::new("osascript")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("osascript spawn error: {}", e))?;
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(script.as_bytes())
+        .map_err(|e| format!("osascript write error: {}", e))?;
+
+    let output = child.wait_with_output().map_err(|e| format!("osascript error: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("AppleScript error: {}", stderr));
+    }
+
+    println!("[caption] Finder comment set successfully");
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn read_finder_comment(path: &str) -> Option<String> {
+    // Read directly from extended attribute (much faster than osascript)
+    let xattr_name = "com.apple.metadata:kMDItemFinderComment";
+    let path = Path::new(path);
+
+    // Get xattr value as bytes
+    let value = xattr::get(path, xattr_name).ok()??;
+
+    // Parse plist to extract the string
+    let plist: plist::Value = plist::from_bytes(&value).ok()?;
+    let comment = plist.as_string()?.to_string();
+
+    if comment.is_empty() {
+        None
+    } else {
+        Some(comment)
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_finder_comment(_path: &str) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(img: &image::RgbaImage, format: image::ImageFormat) -> Vec<u8> {
+        let mut buf = std::io::Cursor::new(Vec::new());
+        match format {
+            image::ImageFormat::Jpeg => image::DynamicImage::ImageRgba8(img.clone())
+                .to_rgb8()
+                .write_to(&mut buf, format)
+                .unwrap(),
+            _ => img.write_to(&mut buf, format).unwrap(),
+        }
+        buf.into_inner()
+    }
+
+    #[test]
+    fn test_png_caption_roundtrip() {
+        let img = image::RgbaImage::from_pixel(8, 8, image::Rgba([10, 20, 30, 255]));
+        let png = encode(&img, image::ImageFormat::Png);
+
+        let with_caption = set_png_caption(&png, "你好 <world> & co").unwrap();
+        assert_eq!(get_png_caption(&with_caption).as_deref(), Some("你好 <world> & co"));
+        // Pixels must survive the rewrite
+        assert_eq!(image::load_from_memory(&with_caption).unwrap().to_rgba8(), img);
+
+        let replaced = set_png_caption(&with_caption, "second").unwrap();
+        assert_eq!(get_png_caption(&replaced).as_deref(), Some("second"));
+
+        let removed = set_png_caption(&replaced, "").unwrap();
+        assert_eq!(get_png_caption(&removed), None);
+    }
+
+    #[test]
+    fn test_jpeg_caption_roundtrip() {
+        let img = image::RgbaImage::from_pixel(8, 8, image::Rgba([200, 100, 50, 255]));
+        let jpeg = encode(&img, image::ImageFormat::Jpeg);
+
+        let with_caption = set_jpeg_caption(&jpeg, "line one\n\"quoted\"").unwrap();
+        assert_eq!(get_jpeg_caption(&with_caption).as_deref(), Some("line one\n\"quoted\""));
+        assert!(image::load_from_memory(&with_caption).is_ok());
+
+        let replaced = set_jpeg_caption(&with_caption, "again").unwrap();
+        assert_eq!(get_jpeg_caption(&replaced).as_deref(), Some("again"));
+        assert_eq!(replaced.windows(XMP_NAMESPACE.len()).filter(|w| *w == XMP_NAMESPACE).count(), 1);
+    }
+
+    #[test]
+    fn test_webp_caption_roundtrip() {
+        let img = image::RgbaImage::from_pixel(9, 7, image::Rgba([30, 60, 90, 128]));
+        let webp = encode(&img, image::ImageFormat::WebP);
+
+        // A simple (VP8L-only) file gets upgraded to VP8X with the XMP flag
+        let with_caption = set_webp_caption(&webp, "webp <caption>").unwrap();
+        assert_eq!(&with_caption[12..16], b"VP8X");
+        assert_eq!(with_caption[20] & VP8X_FLAG_XMP, VP8X_FLAG_XMP);
+        assert_eq!(get_webp_caption(&with_caption).as_deref(), Some("webp <caption>"));
+        assert_eq!(image::load_from_memory(&with_caption).unwrap().to_rgba8(), img);
+
+        let replaced = set_webp_caption(&with_caption, "second").unwrap();
+        assert_eq!(get_webp_caption(&replaced).as_deref(), Some("second"));
+
+        let removed = set_webp_caption(&replaced, "").unwrap();
+        assert_eq!(get_webp_caption(&removed), None);
+        assert_eq!(removed[20] & VP8X_FLAG_XMP, 0);
+    }
+
+    #[test]
+    fn test_gif_caption_roundtrip() {
+        let img = image::RgbaImage::from_pixel(8, 8, image::Rgba([255, 0, 0, 255]));
+        let gif = encode(&img, image::ImageFormat::Gif);
+
+        let with_caption = set_gif_caption(&gif, "动图 & more").unwrap();
+        assert_eq!(get_gif_caption(&with_caption).as_deref(), Some("动图 & more"));
+        // Decoders must still skip the extension and find the frame
+        assert_eq!(image::load_from_memory(&with_caption).unwrap().to_rgba8(), img);
+
+        let replaced = set_gif_caption(&with_caption, "again").unwrap();
+        assert_eq!(get_gif_caption(&replaced).as_deref(), Some("again"));
+        assert_eq!(replaced.windows(GIF_XMP_APP_ID.len()).filter(|w| *w == GIF_XMP_APP_ID).count(), 1);
+
+        let removed = set_gif_caption(&replaced, "").unwrap();
+        assert_eq!(removed, gif);
+    }
+}
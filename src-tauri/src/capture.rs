@@ -2,8 +2,9 @@
 //!
 //! Provides a unified API for screen capture operations.
 
+use image::imageops::FilterType;
 use image::RgbaImage;
-use xcap::Monitor;
+use xcap::{Monitor, Window};
 
 /// Display information matching the old screenshots API
 #[derive(Debug, Clone)]
@@ -35,10 +36,9 @@ impl Screen {
                 let width = monitor.width().map_err(|e| e.to_string())?;
                 let height = monitor.height().map_err(|e| e.to_string())?;
 
-                // xcap may not expose x/y directly, default to 0 for primary
-                // For multi-monitor setups, this would need platform-specific code
                 let (x, y) = get_monitor_position(&monitor, idx);
                 let scale_factor = get_scale_factor(&monitor, width);
+                let (width, height) = get_logical_size(width, height, scale_factor);
 
                 Ok(Screen {
                     display_info: DisplayInfo {
@@ -55,12 +55,6 @@ impl Screen {
             .collect()
     }
 
-    /// Capture entire screen
-    pub fn capture(&self) -> Result<RgbaImage, String> {
-        let img = self.monitor.capture_image().map_err(|e| e.to_string())?;
-        Ok(img)
-    }
-
     /// Capture a specific area of the screen
     /// Note: x, y, width, height are in logical pixels (CSS pixels)
     /// xcap returns physical pixels, so we scale by scale_factor
@@ -99,6 +93,160 @@ impl Screen {
     }
 }
 
+/// Part of a logical-pixel area that lies on a display, as (x, y, width, height)
+pub fn intersect(d: &DisplayInfo, x: i32, y: i32, width: u32, height: u32) -> Option<(i32, i32, u32, u32)> {
+    let x0 = x.max(d.x);
+    let y0 = y.max(d.y);
+    let x1 = (x + width as i32).min(d.x + d.width as i32);
+    let y1 = (y + height as i32).min(d.y + d.height as i32);
+    (x1 > x0 && y1 > y0).then(|| (x0, y0, (x1 - x0) as u32, (y1 - y0) as u32))
+}
+
+/// One display's share of a captured region
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionPart {
+    /// Index into the display list
+    pub display: usize,
+    /// Logical area captured from the display
+    pub area: (i32, i32, u32, u32),
+    /// Top-left of the part in the output image, in output pixels
+    pub offset: (i64, i64),
+    /// Size of the part in the output image
+    pub size: (u32, u32),
+}
+
+/// Output scale and per-display parts for a logical-pixel region
+///
+/// A region on a single display keeps that display's scale. A region
+/// spanning displays is stitched at the highest scale among them; parts not
+/// covered by any display stay empty.
+pub fn plan_region(displays: &[DisplayInfo], x: i32, y: i32, width: u32, height: u32) -> (f32, Vec<RegionPart>) {
+    let hits: Vec<_> = displays
+        .iter()
+        .enumerate()
+        .filter_map(|(i, d)| intersect(d, x, y, width, height).map(|area| (i, area)))
+        .collect();
+    let scale = match hits.as_slice() {
+        [(i, _)] => displays[*i].scale_factor,
+        _ => hits.iter().map(|(i, _)| displays[*i].scale_factor).fold(1.0, f32::max),
+    };
+    let parts = hits
+        .into_iter()
+        .map(|(display, (px, py, pw, ph))| RegionPart {
+            display,
+            area: (px, py, pw, ph),
+            offset: (((px - x) as f32 * scale).round() as i64, ((py - y) as f32 * scale).round() as i64),
+            size: ((pw as f32 * scale).round() as u32, (ph as f32 * scale).round() as u32),
+        })
+        .collect();
+    (scale, parts)
+}
+
+/// Pixel size of a `width` x `height` image resized by `scale`
+pub fn scaled_size(width: u32, height: u32, scale: f32) -> (u32, u32) {
+    let resize = |v: u32| ((v as f32 * scale).round() as u32).max(1);
+    (resize(width), resize(height))
+}
+
+/// Index of the display containing a logical point
+pub fn display_at(displays: &[DisplayInfo], x: f64, y: f64) -> Option<usize> {
+    displays.iter().position(|d| {
+        x >= d.x as f64 && x < (d.x + d.width as i32) as f64 && y >= d.y as f64 && y < (d.y + d.height as i32) as f64
+    })
+}
+
+/// Display info of every screen, in the same order
+pub fn display_infos(screens: &[Screen]) -> Vec<DisplayInfo> {
+    screens.iter().map(|s| s.display_info.clone()).collect()
+}
+
+/// Capture a logical-pixel area from whichever screens it covers, laid out by [`plan_region`]
+pub fn capture_region(
+    screens: &[Screen],
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+) -> Result<RgbaImage, String> {
+    let (scale, parts) = plan_region(&display_infos(screens), x, y, width, height);
+
+    match parts.as_slice() {
+        [] => Err("Capture area is outside every screen".to_string()),
+        [part] => {
+            let (px, py, pw, ph) = part.area;
+            screens[part.display].capture_area(px, py, pw, ph)
+        }
+        _ => {
+            let mut canvas = RgbaImage::new(
+                (width as f32 * scale).round() as u32,
+                (height as f32 * scale).round() as u32,
+            );
+            for part in &parts {
+                let (px, py, pw, ph) = part.area;
+                let mut img = screens[part.display].capture_area(px, py, pw, ph)?;
+                // Lower-density screens are upscaled to match the densest one
+                if img.dimensions() != part.size {
+                    img = image::imageops::resize(&img, part.size.0, part.size.1, FilterType::Lanczos3);
+                }
+                image::imageops::overlay(&mut canvas, &img, part.offset.0, part.offset.1);
+            }
+            Ok(canvas)
+        }
+    }
+}
+
+/// Logical bounding box of all displays, as (x, y, width, height)
+pub fn desktop_bounds(displays: &[DisplayInfo]) -> Option<(i32, i32, u32, u32)> {
+    let x0 = displays.iter().map(|d| d.x).min()?;
+    let y0 = displays.iter().map(|d| d.y).min()?;
+    let x1 = displays.iter().map(|d| d.x + d.width as i32).max()?;
+    let y1 = displays.iter().map(|d| d.y + d.height as i32).max()?;
+    Some((x0, y0, (x1 - x0) as u32, (y1 - y0) as u32))
+}
+
+/// Capture every screen into one image, laid out by monitor position
+pub fn capture_desktop() -> Result<RgbaImage, String> {
+    let screens = Screen::all()?;
+    let (x, y, width, height) = desktop_bounds(&display_infos(&screens)).ok_or("No screens found")?;
+    capture_region(&screens, x, y, width, height)
+}
+
+/// Convert a physical screen point (as mouse_position reports it off macOS) to logical pixels
+#[cfg(not(target_os = "macos"))]
+pub fn physical_to_logical(x: f64, y: f64) -> (f64, f64) {
+    physical_to_logical_in(&display_infos(&Screen::all().unwrap_or_default()), x, y)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn physical_to_logical_in(displays: &[DisplayInfo], x: f64, y: f64) -> (f64, f64) {
+    for d in displays {
+        let scale = d.scale_factor as f64;
+        let (left, top) = (d.x as f64 * scale, d.y as f64 * scale);
+        let (right, bottom) = (left + d.width as f64 * scale, top + d.height as f64 * scale);
+        if x >= left && x < right && y >= top && y < bottom {
+            return (x / scale, y / scale);
+        }
+    }
+    (x, y)
+}
+
+/// Window bounds in logical pixels, as (x, y, width, height)
+#[cfg(target_os = "macos")]
+pub fn window_bounds(window: &Window) -> Option<(i32, i32, u32, u32)> {
+    // xcap already reports points on macOS
+    Some((window.x().ok()?, window.y().ok()?, window.width().ok()?, window.height().ok()?))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn window_bounds(window: &Window) -> Option<(i32, i32, u32, u32)> {
+    let scale = window
+        .current_monitor()
+        .map(|m| get_scale_factor(&m, 0))
+        .unwrap_or(1.0);
+    let (width, height) = get_logical_size(window.width().ok()?, window.height().ok()?, scale);
+    Some((to_logical(window.x().ok()?, scale), to_logical(window.y().ok()?, scale), width, height))
+}
+
 /// Get monitor position (platform-specific)
 #[cfg(target_os = "macos")]
 fn get_monitor_position(monitor: &Monitor, _idx: usize) -> (i32, i32) {
@@ -116,9 +264,14 @@ fn get_monitor_position(monitor: &Monitor, _idx: usize) -> (i32, i32) {
     (0, 0)
 }
 
+/// xcap reports X11/Windows monitor origins in physical pixels; convert with
+/// the monitor's own scale, the same way Tauri converts monitor positions
 #[cfg(not(target_os = "macos"))]
-fn get_monitor_position(_monitor: &Monitor, _idx: usize) -> (i32, i32) {
-    (0, 0)
+fn get_monitor_position(monitor: &Monitor, _idx: usize) -> (i32, i32) {
+    let scale = get_scale_factor(monitor, 0);
+    let x = monitor.x().unwrap_or(0);
+    let y = monitor.y().unwrap_or(0);
+    (to_logical(x, scale), to_logical(y, scale))
 }
 
 /// Get scale factor (platform-specific)
@@ -140,6 +293,166 @@ fn get_scale_factor(_monitor: &Monitor, logical_width: u32) -> f32 {
 }
 
 #[cfg(not(target_os = "macos"))]
-fn get_scale_factor(_monitor: &Monitor, _logical_width: u32) -> f32 {
-    1.0
+fn get_scale_factor(monitor: &Monitor, _logical_width: u32) -> f32 {
+    monitor
+        .scale_factor()
+        .ok()
+        .filter(|s| s.is_finite() && *s > 0.0)
+        .unwrap_or(1.0)
+}
+
+/// Monitor size in logical pixels (xcap already reports points on macOS)
+#[cfg(target_os = "macos")]
+fn get_logical_size(width: u32, height: u32, _scale: f32) -> (u32, u32) {
+    (width, height)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn get_logical_size(width: u32, height: u32, scale: f32) -> (u32, u32) {
+    (to_logical(width as i32, scale) as u32, to_logical(height as i32, scale) as u32)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn to_logical(physical: i32, scale: f32) -> i32 {
+    (physical as f32 / scale).round() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn display(id: u32, x: i32, y: i32, width: u32, height: u32, scale_factor: f32) -> DisplayInfo {
+        DisplayInfo { id, x, y, width, height, scale_factor }
+    }
+
+    #[test]
+    fn test_plan_region_single_display() {
+        let displays = [display(0, 0, 0, 1440, 900, 2.0), display(1, 1440, 0, 1920, 1080, 1.0)];
+        let (scale, parts) = plan_region(&displays, 1500, 100, 300, 200);
+        assert_eq!(scale, 1.0);
+        assert_eq!(parts, vec![RegionPart { display: 1, area: (1500, 100, 300, 200), offset: (0, 0), size: (300, 200) }]);
+
+        // Cut to the display, at its own scale
+        let (scale, parts) = plan_region(&displays, -50, 800, 200, 200);
+        assert_eq!(scale, 2.0);
+        assert_eq!(parts[0].area, (0, 800, 150, 100));
+        assert!(plan_region(&displays, 5000, 0, 10, 10).1.is_empty());
+    }
+
+    #[test]
+    fn test_plan_region_spanning_mixed_scales() {
+        // Retina laptop on the left, 1x external display on the right, lower down
+        let displays = [display(0, 0, 0, 1440, 900, 2.0), display(1, 1440, 200, 1920, 1080, 1.0)];
+        let (scale, parts) = plan_region(&displays, 1340, 300, 200, 100);
+        assert_eq!(scale, 2.0);
+        assert_eq!(
+            parts,
+            vec![
+                RegionPart { display: 0, area: (1340, 300, 100, 100), offset: (0, 0), size: (200, 200) },
+                // The 1x part is placed and upscaled in 2x output pixels
+                RegionPart { display: 1, area: (1440, 300, 100, 100), offset: (200, 0), size: (200, 200) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_region_across_a_gap() {
+        // Displays that don't touch; the gap between them stays uncovered
+        let displays = [display(0, 0, 0, 1000, 800, 1.0), display(1, 1200, 100, 1000, 800, 1.5)];
+        let (x, y, w, h) = desktop_bounds(&displays).unwrap();
+        assert_eq!((x, y, w, h), (0, 0, 2200, 900));
+        let (scale, parts) = plan_region(&displays, x, y, w, h);
+        assert_eq!(scale, 1.5);
+        assert_eq!(parts.len(), 2);
+        assert_eq!((parts[0].offset, parts[0].size), ((0, 0), (1500, 1200)));
+        assert_eq!((parts[1].offset, parts[1].size), ((1800, 150), (1500, 1200)));
+
+        assert_eq!(display_at(&displays, 1100.0, 400.0), None);
+        assert_eq!(display_at(&displays, 1200.0, 100.0), Some(1));
+        assert_eq!(display_at(&displays, 999.5, 0.0), Some(0));
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn test_physical_to_logical_uses_the_display_under_the_point() {
+        let displays = [display(0, 0, 0, 1280, 800, 2.0), display(1, 2560, 0, 1920, 1080, 1.0)];
+        assert_eq!(physical_to_logical_in(&displays, 1000.0, 600.0), (500.0, 300.0));
+        assert_eq!(physical_to_logical_in(&displays, 3000.0, 10.0), (3000.0, 10.0));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_to_logical() {
+        assert_eq!(to_logical(3840, 2.0), 1920);
+        assert_eq!(to_logical(-1280, 1.25), -1024);
+        assert_eq!(get_logical_size(2560, 1440, 1.5), (1707, 960));
+    }
+
+    /// Monitors the Test workflow sets up on one Xvfb screen, in physical pixels
+    ///
+    /// Run locally with the same layout:
+    ///   xvfb-run -a -s "-screen 0 2880x1200x24" sh -c 'xrandr --setmonitor left 1280/338x1024/270+0+0 screen &&
+    ///     xrandr --setmonitor right 1600/423x900/238+1280+120 none && cargo test capture -- --ignored'
+    #[cfg(target_os = "linux")]
+    const XVFB_MONITORS: [(i32, i32, u32, u32); 2] = [(0, 0, 1280, 1024), (1280, 120, 1600, 900)];
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    #[ignore = "needs an X server"]
+    fn test_monitor_geometry_matches_capture() {
+        let screens = Screen::all().unwrap();
+        assert!(!screens.is_empty());
+        for screen in &screens {
+            let d = &screen.display_info;
+            assert!(d.width > 0 && d.height > 0 && d.scale_factor > 0.0);
+
+            // A full-screen logical capture must come back at physical size
+            let img = screen.capture_area(d.x, d.y, d.width, d.height).unwrap();
+            let expected_w = (d.width as f32 * d.scale_factor) as i64;
+            let expected_h = (d.height as f32 * d.scale_factor) as i64;
+            assert!((img.width() as i64 - expected_w).abs() <= 1);
+            assert!((img.height() as i64 - expected_h).abs() <= 1);
+        }
+
+        // The desktop bounds cover every monitor at its reported position
+        let (x, y, w, h) = desktop_bounds(&display_infos(&screens)).unwrap();
+        for screen in &screens {
+            let d = &screen.display_info;
+            assert!(d.x >= x && d.y >= y);
+            assert!(d.x + d.width as i32 <= x + w as i32 && d.y + d.height as i32 <= y + h as i32);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    #[ignore = "needs an X server"]
+    fn test_xvfb_monitor_offsets() {
+        let screens = Screen::all().unwrap();
+        let mut seen = Vec::new();
+        for screen in &screens {
+            let m = &screen.monitor;
+            let physical = (m.x().unwrap(), m.y().unwrap(), m.width().unwrap(), m.height().unwrap());
+            assert!(XVFB_MONITORS.contains(&physical), "unexpected monitor {:?}", physical);
+            seen.push(physical);
+
+            // Position and size come through converted with the monitor's own scale
+            let d = &screen.display_info;
+            let (x, y, w, h) = physical;
+            let s = d.scale_factor;
+            assert_eq!(
+                (d.x, d.y, d.width, d.height),
+                (to_logical(x, s), to_logical(y, s), to_logical(w as i32, s) as u32, to_logical(h as i32, s) as u32)
+            );
+        }
+        seen.sort();
+        assert_eq!(seen, XVFB_MONITORS);
+
+        // A region across the seam is stitched from both monitors
+        let right = screens.iter().find(|s| s.display_info.x > 0).unwrap();
+        let (rx, ry) = (right.display_info.x, right.display_info.y);
+        let (scale, parts) = plan_region(&display_infos(&screens), rx - 40, ry, 80, 60);
+        assert_eq!(parts.len(), 2);
+        let img = capture_region(&screens, rx - 40, ry, 80, 60).unwrap();
+        assert_eq!(img.dimensions(), ((80.0 * scale).round() as u32, (60.0 * scale).round() as u32));
+    }
 }
@@ -0,0 +1,284 @@
+//! Multi-image collages (row, column, grid, before/after)
+//!
+//! The collage is laid out first, then optionally run through a share
+//! template so the caption is rendered exactly like `compose_share`.
+
+use ab_glyph::PxScale;
+use image::imageops::FilterType;
+use image::{Rgba, RgbaImage};
+
+use crate::fonts::{font_chain, FontChain, FontRole};
+use crate::share_compose::{
+    compose_share_rgba, deliver_share, fill_circle_aa, fill_rounded_rect, ShareTemplate, ACCENT, BG_WARM,
+    TEXT_MUTED,
+};
+use crate::text_layout::{text_width, truncate_with_ellipsis};
+
+/// Largest edge a single image is normalised to, to keep collages shareable
+const MAX_CELL: u32 = 1600;
+const LABEL_SIZE: f32 = 18.0;
+
+/// Collage layouts
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollageLayout {
+    Row,         // Same height, left to right
+    Column,      // Same width, top to bottom
+    Grid,        // ceil(sqrt(n)) columns, uniform cells
+    BeforeAfter, // Exactly two images split by a divider
+}
+
+/// Options for compose_collage
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct CollageOptions {
+    pub layout: CollageLayout,
+    #[serde(default)]
+    pub labels: Vec<String>, // Per-image labels; before/after defaults to "Before"/"After"
+    #[serde(default = "default_gap")]
+    pub gap: u32,
+    #[serde(default)]
+    pub caption: String,
+    #[serde(default)]
+    pub template: Option<String>, // Share template id for the caption (default caption_below)
+}
+
+fn default_gap() -> u32 {
+    16
+}
+
+fn scale_to_height(img: &RgbaImage, h: u32) -> RgbaImage {
+    let w = ((img.width() as f32 * h as f32 / img.height() as f32).round() as u32).max(1);
+    image::imageops::resize(img, w, h, FilterType::Lanczos3)
+}
+
+fn scale_to_width(img: &RgbaImage, w: u32) -> RgbaImage {
+    let h = ((img.height() as f32 * w as f32 / img.width() as f32).round() as u32).max(1);
+    image::imageops::resize(img, w, h, FilterType::Lanczos3)
+}
+
+/// Scale down (never up) to fit inside w x h
+fn fit_within(img: &RgbaImage, w: u32, h: u32) -> RgbaImage {
+    let scale = (w as f32 / img.width() as f32).min(h as f32 / img.height() as f32).min(1.0);
+    if (scale - 1.0).abs() < f32::EPSILON {
+        return img.clone();
+    }
+    let new_w = ((img.width() as f32 * scale).round() as u32).max(1);
+    let new_h = ((img.height() as f32 * scale).round() as u32).max(1);
+    image::imageops::resize(img, new_w, new_h, FilterType::Lanczos3)
+}
+
+/// Centered single-line label under a cell image
+fn draw_label(canvas: &mut RgbaImage, font: &FontChain, text: &str, x: u32, y: u32, cell_w: u32) {
+    let scale = PxScale::from(LABEL_SIZE);
+    let text = truncate_with_ellipsis(font, scale, text, cell_w);
+    let w = text_width(font, scale, &text).ceil() as u32;
+    let tx = x + cell_w.saturating_sub(w) / 2;
+    font.draw_text(canvas, TEXT_MUTED, tx as i32, y as i32, scale, &text);
+}
+
+/// Lay out images in a row, column or grid with optional labels under each
+fn compose_cells(images: &[RgbaImage], layout: CollageLayout, labels: &[String], gap: u32, font: &FontChain) -> RgbaImage {
+    let n = images.len() as u32;
+    let label_h = if labels.iter().any(|l| !l.trim().is_empty()) {
+        (LABEL_SIZE * 1.8) as u32
+    } else {
+        0
+    };
+
+    // Normalised images and the number of columns
+    let (cells, cols): (Vec<RgbaImage>, u32) = match layout {
+        CollageLayout::Row => {
+            let h = images.iter().map(|i| i.height()).min().unwrap_or(1).min(MAX_CELL);
+            (images.iter().map(|i| scale_to_height(i, h)).collect(), n)
+        }
+        CollageLayout::Column => {
+            let w = images.iter().map(|i| i.width()).min().unwrap_or(1).min(MAX_CELL);
+            (images.iter().map(|i| scale_to_width(i, w)).collect(), 1)
+        }
+        _ => {
+            let cols = (n as f32).sqrt().ceil() as u32;
+            let w = images.iter().map(|i| i.width()).min().unwrap_or(1).min(MAX_CELL / 2);
+            (images.iter().map(|i| fit_within(&scale_to_width(i, w), w, w * 2)).collect(), cols)
+        }
+    };
+    let rows = n.div_ceil(cols);
+
+    // Column widths / row heights from the largest cell in each
+    let mut col_w = vec![0u32; cols as usize];
+    let mut row_h = vec![0u32; rows as usize];
+    for (i, cell) in cells.iter().enumerate() {
+        let (c, r) = (i % cols as usize, i / cols as usize);
+        col_w[c] = col_w[c].max(cell.width());
+        row_h[r] = row_h[r].max(cell.height() + label_h);
+    }
+    if layout == CollageLayout::Grid {
+        let w = col_w.iter().copied().max().unwrap_or(1);
+        let h = row_h.iter().copied().max().unwrap_or(1);
+        col_w.iter_mut().for_each(|c| *c = w);
+        row_h.iter_mut().for_each(|r| *r = h);
+    }
+
+    let canvas_w = col_w.iter().sum::<u32>() + gap * (cols + 1);
+    let canvas_h = row_h.iter().sum::<u32>() + gap * (rows + 1);
+    let mut canvas = RgbaImage::from_pixel(canvas_w, canvas_h, BG_WARM);
+
+    for (i, cell) in cells.iter().enumerate() {
+        let (c, r) = (i % cols as usize, i / cols as usize);
+        let x0 = gap + col_w[..c].iter().sum::<u32>() + gap * c as u32;
+        let y0 = gap + row_h[..r].iter().sum::<u32>() + gap * r as u32;
+        let x = x0 + (col_w[c] - cell.width()) / 2;
+        let y = y0 + (row_h[r] - label_h - cell.height()) / 2;
+        image::imageops::overlay(&mut canvas, cell, x as i64, y as i64);
+
+        if let Some(label) = labels.get(i).map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            let label_y = y + cell.height() + (label_h - LABEL_SIZE as u32) / 2;
+            draw_label(&mut canvas, font, label, x0, label_y, col_w[c]);
+        }
+    }
+    canvas
+}
+
+/// Two images side by side at the same height, split by an accent divider with a handle
+fn compose_before_after(before: &RgbaImage, after: &RgbaImage, labels: &[String], font: &FontChain) -> RgbaImage {
+    let h = before.height().min(after.height()).min(MAX_CELL);
+    let left = scale_to_height(before, h);
+    let right = scale_to_height(after, h);
+    let divider = (h / 200).max(4);
+
+    let canvas_w = left.width() + divider + right.width();
+    let mut canvas = RgbaImage::from_pixel(canvas_w, h, BG_WARM);
+    image::imageops::overlay(&mut canvas, &left, 0, 0);
+    image::imageops::overlay(&mut canvas, &right, (left.width() + divider) as i64, 0);
+
+    for y in 0..h {
+        for x in left.width()..left.width() + divider {
+            canvas.put_pixel(x, y, ACCENT);
+        }
+    }
+    let cx = left.width() as f32 + divider as f32 / 2.0;
+    let r = (h as f32 * 0.035).clamp(12.0, 40.0);
+    fill_circle_aa(&mut canvas, cx, h as f32 / 2.0, r, ACCENT);
+    fill_circle_aa(&mut canvas, cx, h as f32 / 2.0, r * 0.45, Rgba([255, 255, 255, 255]));
+
+    // Pill labels in the top corners of each side
+    let defaults = ["Before".to_string(), "After".to_string()];
+    let scale = PxScale::from((h as f32 * 0.03).clamp(14.0, 32.0));
+    let pad = scale.y * 0.6;
+    for (i, label) in labels.iter().chain(defaults.iter().skip(labels.len())).take(2).enumerate() {
+        let label = label.trim();
+        if label.is_empty() {
+            continue;
+        }
+        let text_w = text_width(font, scale, label).ceil();
+        let pill_w = (text_w + pad * 2.0) as u32;
+        let pill_h = (scale.y + pad) as u32;
+        let margin = pad as i64 * 2;
+        let x = if i == 0 {
+            margin
+        } else {
+            canvas_w as i64 - margin - pill_w as i64
+        };
+        fill_rounded_rect(&mut canvas, x, margin, pill_w, pill_h, pill_h as f32 / 2.0, Rgba([0, 0, 0, 150]));
+        let text_y = margin as f32 + (pill_h as f32 - scale.y) / 2.0;
+        font.draw_text(&mut canvas, Rgba([255, 255, 255, 255]), (x as f32 + pad) as i32, text_y as i32, scale, label);
+    }
+    canvas
+}
+
+/// Compose several images into one collage, then apply the caption template
+pub fn compose_collage_image(source_paths: &[String], options: &CollageOptions) -> Result<RgbaImage, String> {
+    if source_paths.is_empty() {
+        return Err("No images selected".to_string());
+    }
+    if options.layout == CollageLayout::BeforeAfter && source_paths.len() != 2 {
+        return Err("Before/after needs exactly two images".to_string());
+    }
+
+    let images = source_paths
+        .iter()
+        .map(|p| {
+            image::open(p)
+                .map(|img| img.to_rgba8())
+                .map_err(|e| format!("Failed to open {}: {}", p, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let font = font_chain(FontRole::Text).ok_or("Failed to load font")?;
+
+    println!("[collage] {} images, layout {:?}", images.len(), options.layout);
+    let collage = match options.layout {
+        CollageLayout::BeforeAfter => compose_before_after(&images[0], &images[1], &options.labels, &font),
+        layout => compose_cells(&images, layout, &options.labels, options.gap, &font),
+    };
+
+    if options.caption.trim().is_empty() && options.template.is_none() {
+        return Ok(collage);
+    }
+    let template = options
+        .template
+        .as_deref()
+        .and_then(ShareTemplate::from_id)
+        .unwrap_or(ShareTemplate::CaptionBelow);
+    compose_share_rgba(&collage, &options.caption, template)
+}
+
+/// Tauri command: compose N library images and save like compose_share
+#[tauri::command]
+pub fn compose_collage(
+    app: tauri::AppHandle,
+    source_paths: Vec<String>,
+    options: CollageOptions,
+) -> Result<String, String> {
+    let composed = compose_collage_image(&source_paths, &options)?;
+    deliver_share(&app, &composed, "collage")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLORS: [Rgba<u8>; 4] = [
+        Rgba([220, 40, 40, 255]),
+        Rgba([40, 160, 60, 255]),
+        Rgba([40, 80, 220, 255]),
+        Rgba([230, 180, 30, 255]),
+    ];
+
+    #[test]
+    fn test_grid_cell_placement() {
+        let Some(font) = font_chain(FontRole::Text) else {
+            println!("no fonts, skipping");
+            return;
+        };
+        let images: Vec<RgbaImage> = COLORS[..3].iter().map(|&c| RgbaImage::from_pixel(100, 80, c)).collect();
+        let out = compose_cells(&images, CollageLayout::Grid, &[], 10, &font);
+
+        // Three images -> 2x2 grid of 100x80 cells with 10px gaps; the last cell stays empty
+        assert_eq!(out.dimensions(), (10 + 100 + 10 + 100 + 10, 10 + 80 + 10 + 80 + 10));
+        for (i, color) in COLORS[..3].iter().enumerate() {
+            let (x0, y0) = (10 + (i as u32 % 2) * 110, 10 + (i as u32 / 2) * 90);
+            assert_eq!(out.get_pixel(x0, y0), color);
+            assert_eq!(out.get_pixel(x0 + 99, y0 + 79), color);
+        }
+        assert_eq!(*out.get_pixel(120 + 50, 100 + 40), BG_WARM);
+        assert_eq!(*out.get_pixel(115, 50), BG_WARM); // Gap between columns
+    }
+
+    #[test]
+    fn test_before_after_placement() {
+        let Some(font) = font_chain(FontRole::Text) else {
+            println!("no fonts, skipping");
+            return;
+        };
+        let before = RgbaImage::from_pixel(200, 100, COLORS[0]);
+        let after = RgbaImage::from_pixel(300, 150, COLORS[2]);
+        let out = compose_before_after(&before, &after, &[], &font);
+
+        // Both sides at the smaller height, with a 4px divider between them
+        assert_eq!(out.dimensions(), (200 + 4 + 200, 100));
+        assert_eq!(*out.get_pixel(10, 90), COLORS[0]);
+        assert_eq!(*out.get_pixel(199, 90), COLORS[0]);
+        assert_eq!(*out.get_pixel(202, 5), ACCENT);
+        assert_eq!(*out.get_pixel(204, 90), COLORS[2]);
+        assert_eq!(*out.get_pixel(403, 90), COLORS[2]);
+    }
+}
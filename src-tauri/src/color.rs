@@ -0,0 +1,174 @@
+//! Colour picker formats (HEX, RGB, HSL, OKLCH) and WCAG contrast
+
+use serde::Serialize;
+
+use crate::watermark::parse_hex_color;
+
+/// Picked colours kept in config, most recent first
+pub const HISTORY_LIMIT: usize = 24;
+
+/// One colour in every format the picker offers, as CSS strings
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct ColorInfo {
+    pub hex: String,   // "#CC785C"
+    pub rgb: String,   // "rgb(204, 120, 92)"
+    pub hsl: String,   // "hsl(15, 50%, 58%)"
+    pub oklch: String, // "oklch(65.8% 0.105 40.9)"
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+/// WCAG 2.x contrast ratio and which levels it passes
+#[derive(Clone, Debug, Serialize)]
+pub struct ContrastInfo {
+    pub ratio: f32,
+    pub aa: bool,        // Normal text, >= 4.5
+    pub aa_large: bool,  // Large text, >= 3
+    pub aaa: bool,       // Normal text, >= 7
+    pub aaa_large: bool, // Large text, >= 4.5
+}
+
+pub fn color_info(rgb: [u8; 3]) -> ColorInfo {
+    let [r, g, b] = rgb;
+    let (h, s, l) = rgb_to_hsl(rgb);
+    let (ok_l, ok_c, ok_h) = rgb_to_oklch(rgb);
+    ColorInfo {
+        hex: format!("#{:02X}{:02X}{:02X}", r, g, b),
+        rgb: format!("rgb({}, {}, {})", r, g, b),
+        hsl: format!("hsl({}, {}%, {}%)", h.round() as i32 % 360, (s * 100.0).round(), (l * 100.0).round()),
+        oklch: format!("oklch({:.1}% {:.3} {:.1})", ok_l * 100.0, ok_c, ok_h),
+        r,
+        g,
+        b,
+    }
+}
+
+/// "#RRGGBB" / "#RRGGBBAA" to RGB (alpha ignored)
+pub fn parse_rgb(hex: &str) -> Result<[u8; 3], String> {
+    let c = parse_hex_color(hex).ok_or_else(|| format!("Invalid color: {}", hex))?;
+    Ok([c[0], c[1], c[2]])
+}
+
+/// Hue in degrees, saturation and lightness in 0..1
+fn rgb_to_hsl(rgb: [u8; 3]) -> (f32, f32, f32) {
+    let [r, g, b] = rgb.map(|c| c as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d == 0.0 {
+        return (0.0, 0.0, l);
+    }
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        60.0 * ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / d + 2.0)
+    } else {
+        60.0 * ((r - g) / d + 4.0)
+    };
+    (h, s, l)
+}
+
+fn srgb_to_linear(c: u8) -> f32 {
+    let c = c as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// OKLCH via OKLab (Björn Ottosson's matrices); hue in degrees
+fn rgb_to_oklch(rgb: [u8; 3]) -> (f32, f32, f32) {
+    let [r, g, b] = rgb.map(srgb_to_linear);
+    let l = (0.412_221_47 * r + 0.536_332_54 * g + 0.051_445_993 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+
+    let ok_l = 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s;
+    let ok_a = 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s;
+    let ok_b = 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s;
+
+    let c = (ok_a * ok_a + ok_b * ok_b).sqrt();
+    // Greys have no meaningful hue
+    let h = if c < 1e-4 { 0.0 } else { ok_b.atan2(ok_a).to_degrees().rem_euclid(360.0) };
+    (ok_l, c, h)
+}
+
+/// WCAG relative luminance
+pub fn relative_luminance(rgb: [u8; 3]) -> f32 {
+    let [r, g, b] = rgb.map(srgb_to_linear);
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+pub fn contrast_info(a: [u8; 3], b: [u8; 3]) -> ContrastInfo {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    let ratio = (la.max(lb) + 0.05) / (la.min(lb) + 0.05);
+    // Levels are checked on the exact ratio; only the reported value is rounded
+    ContrastInfo {
+        ratio: (ratio * 100.0).round() / 100.0,
+        aa: ratio >= 4.5,
+        aa_large: ratio >= 3.0,
+        aaa: ratio >= 7.0,
+        aaa_large: ratio >= 4.5,
+    }
+}
+
+/// Move `hex` to the front of the history, dropping duplicates and the oldest entries
+pub fn push_history(history: &mut Vec<String>, hex: &str) {
+    history.retain(|h| !h.eq_ignore_ascii_case(hex));
+    history.insert(0, hex.to_string());
+    history.truncate(HISTORY_LIMIT);
+}
+
+/// Tauri command: all formats for a hex colour (e.g. a history entry)
+#[tauri::command]
+pub fn convert_color(color: String) -> Result<ColorInfo, String> {
+    Ok(color_info(parse_rgb(&color)?))
+}
+
+/// Tauri command: WCAG contrast between two hex colours
+#[tauri::command]
+pub fn color_contrast(foreground: String, background: String) -> Result<ContrastInfo, String> {
+    Ok(contrast_info(parse_rgb(&foreground)?, parse_rgb(&background)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_formats() {
+        let red = color_info([255, 0, 0]);
+        assert_eq!(red.hex, "#FF0000");
+        assert_eq!(red.rgb, "rgb(255, 0, 0)");
+        assert_eq!(red.hsl, "hsl(0, 100%, 50%)");
+        assert_eq!(red.oklch, "oklch(62.8% 0.258 29.2)");
+
+        let grey = color_info([128, 128, 128]);
+        assert_eq!(grey.hsl, "hsl(0, 0%, 50%)");
+        assert_eq!(grey.oklch, "oklch(60.0% 0.000 0.0)");
+    }
+
+    #[test]
+    fn test_contrast_and_history() {
+        let bw = contrast_info([0, 0, 0], [255, 255, 255]);
+        assert_eq!(bw.ratio, 21.0);
+        assert!(bw.aaa);
+
+        // #777 on white is the classic just-fails-AA grey
+        let grey = contrast_info([0x77, 0x77, 0x77], [255, 255, 255]);
+        assert!(!grey.aa && grey.aa_large);
+
+        // 4.498:1 reports as 4.5 but still fails AA
+        let near = contrast_info([53, 135, 53], [255, 255, 255]);
+        assert_eq!(near.ratio, 4.5);
+        assert!(!near.aa && !near.aaa_large);
+
+        let mut history = vec!["#000000".to_string(), "#FFFFFF".to_string()];
+        push_history(&mut history, "#ffffff");
+        assert_eq!(history, vec!["#ffffff", "#000000"]);
+    }
+}
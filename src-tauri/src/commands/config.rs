@@ -2,7 +2,8 @@ use tauri::AppHandle;
 use tauri_plugin_autostart::ManagerExt;
 use tauri_plugin_global_shortcut::GlobalShortcutExt;
 
-use crate::config::{self, AppConfig, ShortcutConfig, WatermarkPosition};
+use crate::config::{self, AppConfig, ShortcutConfig, WatermarkConfig, WatermarkPosition};
+use crate::presets::{parse_aspect_ratio, RegionPreset, PRESET_ACTION_PREFIX};
 use crate::shortcuts::register_shortcuts_from_config;
 use crate::state::SharedState;
 use crate::tray::update_tray_menu;
@@ -164,7 +165,7 @@ pub fn set_image_export_format(format: String) -> Result<AppConfig, String> {
 
 #[tauri::command]
 pub fn get_screenshot_count() -> u64 {
-    config::count_screenshots()
+    config::last_screenshot_number()
 }
 
 #[tauri::command]
@@ -197,6 +198,149 @@ pub fn set_watermark_position(position: String) -> Result<AppConfig, String> {
     Ok(cfg)
 }
 
+#[tauri::command]
+pub fn get_fonts() -> Vec<String> {
+    config::load_config().fonts
+}
+
+/// Font files or file names (e.g. "NotoSansCJK-Regular.ttc#2") tried before the built-in chain
+#[tauri::command]
+pub fn set_fonts(fonts: Vec<String>) -> Result<AppConfig, String> {
+    let fonts: Vec<String> = fonts
+        .into_iter()
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty())
+        .collect();
+    crate::fonts::validate_font_entries(&fonts)?;
+    let mut cfg = config::load_config();
+    cfg.fonts = fonts;
+    config::save_config(&cfg)?;
+    Ok(cfg)
+}
+
+#[tauri::command]
+pub fn get_color_history() -> Vec<String> {
+    config::load_config().color_history
+}
+
+#[tauri::command]
+pub fn clear_color_history() -> Result<AppConfig, String> {
+    let mut cfg = config::load_config();
+    cfg.color_history.clear();
+    config::save_config(&cfg)?;
+    Ok(cfg)
+}
+
+#[tauri::command]
+pub fn get_region_presets() -> Vec<RegionPreset> {
+    config::load_config().region_presets
+}
+
+/// Add a region preset, or replace the one with the same id
+#[tauri::command]
+pub fn save_region_preset(app: AppHandle, preset: RegionPreset) -> Result<AppConfig, String> {
+    if preset.id.is_empty() || preset.name.is_empty() {
+        return Err("Preset needs an id and a name".to_string());
+    }
+    if preset.width == 0 || preset.height == 0 {
+        return Err("Preset size must be non-zero".to_string());
+    }
+    let mut cfg = config::load_config();
+    match cfg.region_presets.iter_mut().find(|p| p.id == preset.id) {
+        Some(existing) => *existing = preset,
+        None => cfg.region_presets.push(preset),
+    }
+    config::save_config(&cfg)?;
+    update_tray_menu(&app);
+    Ok(cfg)
+}
+
+/// Remove a region preset together with its shortcuts
+#[tauri::command]
+pub fn remove_region_preset(app: AppHandle, id: String) -> Result<AppConfig, String> {
+    let mut cfg = config::load_config();
+    cfg.region_presets.retain(|p| p.id != id);
+    cfg.shortcuts.remove(&format!("{}{}", PRESET_ACTION_PREFIX, id));
+    config::save_config(&cfg)?;
+    register_shortcuts_from_config(&app)?;
+    update_tray_menu(&app);
+    Ok(cfg)
+}
+
+/// Locked selection aspect ratio (width / height), if any
+#[tauri::command]
+pub fn get_aspect_ratio_lock() -> Option<f32> {
+    config::load_config()
+        .aspect_ratio_lock
+        .as_deref()
+        .and_then(parse_aspect_ratio)
+}
+
+/// Set the selection aspect ratio ("16:9", "1.5"), or clear it with None
+#[tauri::command]
+pub fn set_aspect_ratio_lock(ratio: Option<String>) -> Result<AppConfig, String> {
+    if let Some(ref r) = ratio {
+        parse_aspect_ratio(r).ok_or_else(|| format!("Invalid aspect ratio: {}", r))?;
+    }
+    let mut cfg = config::load_config();
+    cfg.aspect_ratio_lock = ratio;
+    config::save_config(&cfg)?;
+    Ok(cfg)
+}
+
+/// Default countdown for delayed screenshots, in seconds (1-60)
+#[tauri::command]
+pub fn set_capture_delay(app: AppHandle, seconds: u32) -> Result<AppConfig, String> {
+    if !(1..=60).contains(&seconds) {
+        return Err("Delay must be between 1 and 60 seconds".to_string());
+    }
+    let mut cfg = config::load_config();
+    cfg.capture_delay_secs = seconds;
+    config::save_config(&cfg)?;
+    update_tray_menu(&app);
+    Ok(cfg)
+}
+
+/// Defaults for timelapse capture: seconds between frames and total minutes (0 = until stopped)
+#[tauri::command]
+pub fn set_timelapse_defaults(interval_secs: u32, duration_mins: u32) -> Result<AppConfig, String> {
+    if interval_secs == 0 {
+        return Err("Interval must be at least 1 second".to_string());
+    }
+    let mut cfg = config::load_config();
+    cfg.timelapse_interval_secs = interval_secs;
+    cfg.timelapse_duration_mins = duration_mins;
+    config::save_config(&cfg)?;
+    Ok(cfg)
+}
+
+#[tauri::command]
+pub fn get_watermark_config() -> WatermarkConfig {
+    config::load_config().watermark
+}
+
+#[tauri::command]
+pub fn set_watermark_config(watermark: WatermarkConfig) -> Result<AppConfig, String> {
+    if !(0.0..=1.0).contains(&watermark.opacity) {
+        return Err("Opacity must be between 0 and 1".to_string());
+    }
+    if watermark.size <= 0.0 || watermark.margin < 0.0 {
+        return Err("Invalid watermark size or margin".to_string());
+    }
+    if crate::watermark::parse_hex_color(&watermark.color).is_none() {
+        return Err(format!("Invalid color: {}", watermark.color));
+    }
+    if let Some(ref path) = watermark.image_path {
+        if !std::path::Path::new(path).is_file() {
+            return Err(format!("Logo not found: {}", path));
+        }
+    }
+    let mut cfg = config::load_config();
+    cfg.watermark = watermark;
+    config::save_config(&cfg)?;
+    Ok(cfg)
+}
+
 #[tauri::command]
 pub fn get_show_caption_editor() -> bool {
     config::load_config().show_caption_editor
@@ -209,3 +353,45 @@ pub fn set_show_caption_editor(enabled: bool) -> Result<AppConfig, String> {
     config::save_config(&cfg)?;
     Ok(cfg)
 }
+
+/// Resolved library root (custom dir or ~/Pictures/lovshot)
+#[tauri::command]
+pub fn get_output_dir() -> String {
+    crate::output::lovshot_dir().to_string_lossy().to_string()
+}
+
+/// Set library root; None or empty resets to ~/Pictures/lovshot
+#[tauri::command]
+pub fn set_output_dir(path: Option<String>) -> Result<AppConfig, String> {
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    if let Some(ref p) = path {
+        let resolved = crate::output::resolve_output_dir(Some(p));
+        std::fs::create_dir_all(&resolved)
+            .map_err(|e| format!("Cannot use {:?} as output folder: {}", resolved, e))?;
+    }
+    let mut cfg = config::load_config();
+    cfg.output_dir = path;
+    config::save_config(&cfg)?;
+    Ok(cfg)
+}
+
+/// Set filename template for "screenshot", "recording" or "share"
+#[tauri::command]
+pub fn set_filename_template(kind: String, template: String) -> Result<AppConfig, String> {
+    let template = template.trim().to_string();
+    if template.is_empty() {
+        return Err("Template cannot be empty".to_string());
+    }
+    if template.contains('/') || template.contains('\\') {
+        return Err("Template cannot contain path separators".to_string());
+    }
+    let mut cfg = config::load_config();
+    match kind.as_str() {
+        "screenshot" => cfg.filename_templates.screenshot = template,
+        "recording" => cfg.filename_templates.recording = template,
+        "share" => cfg.filename_templates.share = template,
+        _ => return Err("Invalid kind. Must be 'screenshot', 'recording', or 'share'".to_string()),
+    }
+    config::save_config(&cfg)?;
+    Ok(cfg)
+}
@@ -3,107 +3,23 @@ use std::io::{BufReader, BufWriter};
 use std::path::PathBuf;
 use std::thread;
 
-use ab_glyph::{FontRef, PxScale};
-use crate::capture::Screen;
+use crate::capture::{capture_region, desktop_bounds, display_infos, scaled_size, Screen};
 use base64::{engine::general_purpose::STANDARD, Engine};
 use gif::{Encoder, Frame, Repeat};
-use image::{Rgba, RgbaImage};
-use imageproc::drawing::draw_text_mut;
+use image::RgbaImage;
 use tauri::{AppHandle, Emitter};
 use tauri_plugin_clipboard_manager::ClipboardExt;
 
-use crate::config::WatermarkPosition;
+use crate::annotate::{render_annotations, AnnotationScene};
+use crate::annotation_layers::{embed_layers, encode_png, AnnotationRecord};
+use crate::caption::{read_caption, sidecar_path, write_caption};
+use crate::output::{
+    app_name_for_region, build_output_path, create_unique_file, save_image_unique,
+    write_file_atomic, FilenameContext, OutputKind,
+};
 use crate::state::SharedState;
-use crate::types::{ExportConfig, ExportProgress, GifLoopMode, SaveResult, SizeEstimate};
-
-// ============ Screenshot Watermark ============
-
-/// Load system font for watermark
-fn load_watermark_font() -> Option<FontRef<'static>> {
-    #[cfg(target_os = "macos")]
-    {
-        let font_paths = [
-            "/System/Library/Fonts/Menlo.ttc",
-            "/System/Library/Fonts/Monaco.ttf",
-            "/System/Library/Fonts/Helvetica.ttc",
-        ];
-        for path in font_paths {
-            if let Ok(data) = std::fs::read(path) {
-                let leaked: &'static [u8] = Box::leak(data.into_boxed_slice());
-                if let Ok(font) = FontRef::try_from_slice(leaked) {
-                    return Some(font);
-                }
-            }
-        }
-    }
-    #[cfg(not(target_os = "macos"))]
-    {
-        let font_paths = [
-            "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf",
-            "C:\\Windows\\Fonts\\consola.ttf",
-        ];
-        for path in font_paths {
-            if let Ok(data) = std::fs::read(path) {
-                let leaked: &'static [u8] = Box::leak(data.into_boxed_slice());
-                if let Ok(font) = FontRef::try_from_slice(leaked) {
-                    return Some(font);
-                }
-            }
-        }
-    }
-    None
-}
-
-/// Add #{number} watermark based on config position
-fn add_screenshot_watermark(img: &mut RgbaImage, number: u64) {
-    let cfg = crate::config::load_config();
-    let position = cfg.watermark_position;
-
-    // Skip if position is None or Brand (Brand is handled in share_compose)
-    if matches!(position, WatermarkPosition::None | WatermarkPosition::Brand) {
-        println!("[watermark] Position {:?}, skipping corner watermark", position);
-        return;
-    }
-
-    let font = match load_watermark_font() {
-        Some(f) => f,
-        None => {
-            println!("[watermark] Font not found, skipping");
-            return;
-        }
-    };
-
-    let text = format!("#{}", number);
-    let (img_w, img_h) = img.dimensions();
-
-    // Scale font size based on image dimensions
-    let base_size = (img_w.min(img_h) as f32 * 0.04).clamp(14.0, 48.0);
-    let scale = PxScale::from(base_size);
-
-    // Estimate text dimensions
-    let char_width = base_size * 0.6;
-    let text_width = (text.len() as f32 * char_width) as u32;
-    let text_height = base_size as u32;
-    let padding = (base_size * 0.4) as u32;
-
-    // Calculate position based on config
-    let (x, y) = match position {
-        WatermarkPosition::TopLeft => (padding as i32, padding as i32),
-        WatermarkPosition::TopRight => ((img_w.saturating_sub(text_width + padding)) as i32, padding as i32),
-        WatermarkPosition::BottomLeft => (padding as i32, (img_h.saturating_sub(text_height + padding)) as i32),
-        WatermarkPosition::BottomRight => (
-            (img_w.saturating_sub(text_width + padding)) as i32,
-            (img_h.saturating_sub(text_height + padding)) as i32,
-        ),
-        _ => return,
-    };
-
-    // Draw with semi-transparent dark color
-    let color = Rgba([40, 40, 40, 180]);
-    draw_text_mut(img, color, x, y, scale, &font, &text);
-
-    println!("[watermark] Added #{} at ({}, {}) position {:?}", number, x, y, position);
-}
+use crate::watermark::{add_screenshot_watermark, corner_watermark_enabled, WatermarkLayer};
+use crate::types::{CaptureMode, ExportConfig, ExportProgress, GifLoopMode, SaveResult, SizeEstimate};
 
 #[tauri::command]
 pub fn estimate_export_size(
@@ -293,6 +209,12 @@ pub fn save_screenshot(
     let screen_x = s.screen_x;
     let screen_y = s.screen_y;
     let is_static_mode = use_cached.unwrap_or(false) && cached_snapshot.is_some();
+    // A repeated static capture is taken live, but should stay static for the next repeat
+    let remembered_mode = if is_static_mode || s.pending_mode == Some(CaptureMode::StaticImage) {
+        CaptureMode::StaticImage
+    } else {
+        CaptureMode::Image
+    };
     println!(
         "[DEBUG][save_screenshot] region: x={}, y={}, w={}, h={}, scale={}, static={}, caption_mode={:?}",
         region.x, region.y, region.width, region.height, output_scale, is_static_mode, caption_mode
@@ -338,19 +260,17 @@ pub fn save_screenshot(
         }
         println!("[DEBUG][save_screenshot] 找到 {} 个屏幕", screens.len());
 
-        let screen = &screens[0];
         println!(
-            "[DEBUG][save_screenshot] 调用 capture_area: x={}, y={}, w={}, h={}",
+            "[DEBUG][save_screenshot] 调用 capture_region: x={}, y={}, w={}, h={}",
             region.x, region.y, region.width, region.height
         );
-        let captured = screen
-            .capture_area(region.x, region.y, region.width, region.height)
+        let captured = capture_region(&screens, region.x, region.y, region.width, region.height)
             .map_err(|e| {
-                println!("[DEBUG][save_screenshot] capture_area 错误: {}", e);
+                println!("[DEBUG][save_screenshot] capture_region 错误: {}", e);
                 e.to_string()
             })?;
         println!(
-            "[DEBUG][save_screenshot] capture_area 成功, 图像尺寸: {}x{}",
+            "[DEBUG][save_screenshot] capture_region 成功, 图像尺寸: {}x{}",
             captured.width(),
             captured.height()
         );
@@ -359,9 +279,23 @@ pub fn save_screenshot(
             .ok_or("Failed to convert image")?
     };
 
+    crate::config::remember_last_capture(&region, remembered_mode);
+    let mode = if is_static_mode { "static" } else { "image" };
+    let app_name = app_name_for_region(&region);
+    finish_screenshot(&app, captured_rgba, app_name, output_scale, mode, caption_mode.unwrap_or(false))
+}
+
+/// Scale, watermark, copy and save a captured screenshot, then open the preview
+pub(crate) fn finish_screenshot(
+    app: &AppHandle,
+    captured_rgba: RgbaImage,
+    app_name: Option<String>,
+    output_scale: f32,
+    mode: &str,
+    is_caption_mode: bool,
+) -> Result<String, String> {
     let mut img = if (output_scale - 1.0).abs() > 0.01 {
-        let new_w = (captured_rgba.width() as f32 * output_scale) as u32;
-        let new_h = (captured_rgba.height() as f32 * output_scale) as u32;
+        let (new_w, new_h) = scaled_size(captured_rgba.width(), captured_rgba.height(), output_scale);
         println!("[DEBUG][save_screenshot] 缩放到: {}x{}", new_w, new_h);
         image::imageops::resize(
             &captured_rgba,
@@ -374,7 +308,7 @@ pub fn save_screenshot(
     };
 
     // Add watermark with screenshot number
-    let screenshot_number = crate::config::count_screenshots();
+    let screenshot_number = crate::config::next_screenshot_number();
     add_screenshot_watermark(&mut img, screenshot_number);
 
     let tauri_image =
@@ -385,39 +319,32 @@ pub fn save_screenshot(
     })?;
     println!("[DEBUG][save_screenshot] 已复制到剪切板");
 
-    let output_dir = dirs::picture_dir()
-        .or_else(|| dirs::home_dir())
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("lovshot");
-    println!("[DEBUG][save_screenshot] 输出目录: {:?}", output_dir);
-
-    std::fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
-
-    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-    let filename = output_dir.join(format!("screenshot_{}.png", timestamp));
+    let ctx = FilenameContext::new(mode)
+        .with_counter(screenshot_number)
+        .with_app_name(app_name);
+    let filename = build_output_path(OutputKind::Screenshot, ctx, "png")?;
     println!("[DEBUG][save_screenshot] 保存文件: {:?}", filename);
 
-    img.save(&filename).map_err(|e| {
+    let filename = save_image_unique(&img, &filename).map_err(|e| {
         println!("[DEBUG][save_screenshot] 保存文件错误: {}", e);
-        e.to_string()
+        e
     })?;
-    println!("[DEBUG][save_screenshot] 文件保存成功");
+    println!("[DEBUG][save_screenshot] 文件保存成功: {:?}", filename);
 
     let path_str = filename.to_string_lossy().to_string();
     let _ = app.emit("screenshot-saved", &path_str);
 
     // Show preview window: caption mode takes priority, then normal preview
-    let is_caption_mode = caption_mode.unwrap_or(false);
     if is_caption_mode {
         println!("[save_screenshot] Opening caption preview window");
-        if let Err(e) = crate::windows::open_caption_window(&app, &path_str, None) {
+        if let Err(e) = crate::windows::open_caption_window(app, &path_str, None) {
             println!("[save_screenshot] Failed to open caption window: {}", e);
         }
     } else {
         let cfg = crate::config::load_config();
         println!("[save_screenshot] screenshot_preview_enabled: {}", cfg.screenshot_preview_enabled);
         if cfg.screenshot_preview_enabled {
-            if let Err(e) = crate::windows::open_preview_window(&app, &path_str) {
+            if let Err(e) = crate::windows::open_preview_window(app, &path_str) {
                 println!("[save_screenshot] Failed to open preview: {}", e);
             }
         }
@@ -426,6 +353,19 @@ pub fn save_screenshot(
     Ok(path_str)
 }
 
+/// Tauri command: screenshot of every monitor composited into one image
+#[tauri::command(rename_all = "camelCase")]
+pub fn save_desktop_screenshot(app: AppHandle, caption_mode: Option<bool>) -> Result<String, String> {
+    let screens = Screen::all()?;
+    let (x, y, width, height) = desktop_bounds(&display_infos(&screens)).ok_or("No screens found")?;
+    println!(
+        "[save_desktop_screenshot] {} screens, bounds: x={}, y={}, w={}, h={}",
+        screens.len(), x, y, width, height
+    );
+    let captured = capture_region(&screens, x, y, width, height)?;
+    finish_screenshot(&app, captured, None, 1.0, "desktop", caption_mode.unwrap_or(false))
+}
+
 #[tauri::command]
 pub fn export_gif(
     app: AppHandle,
@@ -568,41 +508,50 @@ pub fn export_gif(
             _ => scaled_frames,
         };
 
-        let output_dir = dirs::picture_dir()
-            .or_else(|| dirs::home_dir())
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("lovshot");
-
-        if let Err(e) = std::fs::create_dir_all(&output_dir) {
-            let _ = app.emit(
-                "export-complete",
-                SaveResult {
-                    success: false,
-                    path: None,
-                    error: Some(e.to_string()),
-                },
-            );
-            return;
-        }
+        // Render the watermark once and stamp the same layer on every frame
+        let (width, height) = final_frames[0].dimensions();
+        let watermark_number = if corner_watermark_enabled() {
+            Some(crate::config::next_screenshot_number())
+        } else {
+            None
+        };
+        let watermark = watermark_number.and_then(|n| WatermarkLayer::for_corner(width, height, n));
 
         // Use custom path or default
         let filename = if let Some(ref custom_path) = config.output_path {
             PathBuf::from(custom_path)
         } else {
-            let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-            output_dir.join(format!("recording_{}.gif", timestamp))
+            let mut ctx = FilenameContext::new("gif");
+            if let Some(n) = watermark_number {
+                ctx = ctx.with_counter(n);
+            }
+            match build_output_path(OutputKind::Recording, ctx, "gif")
+                .and_then(|path| create_unique_file(&path))
+            {
+                Ok(path) => path,
+                Err(e) => {
+                    let _ = app.emit(
+                        "export-complete",
+                        SaveResult {
+                            success: false,
+                            path: None,
+                            error: Some(e),
+                        },
+                    );
+                    return;
+                }
+            }
         };
         println!("[DEBUG][export_gif] 保存路径: {:?}", filename);
 
-        let (width, height) = final_frames[0].dimensions();
         let frame_count = final_frames.len();
         println!(
             "[DEBUG][export_gif] 开始编码: {}x{}, {} 帧",
             width, height, frame_count
         );
 
-        let result = (|| -> Result<String, String> {
-            let mut file = File::create(&filename).map_err(|e| e.to_string())?;
+        let result = write_file_atomic(&filename, |tmp_path| {
+            let mut file = File::create(tmp_path).map_err(|e| e.to_string())?;
             let mut encoder = Encoder::new(&mut file, width as u16, height as u16, &[])
                 .map_err(|e| e.to_string())?;
 
@@ -620,7 +569,10 @@ pub fn export_gif(
                 10
             };
 
-            for (i, rgba_img) in final_frames.into_iter().enumerate() {
+            for (i, mut rgba_img) in final_frames.into_iter().enumerate() {
+                if let Some(ref wm) = watermark {
+                    wm.apply(&mut rgba_img);
+                }
                 let mut pixels: Vec<u8> = Vec::with_capacity((width * height * 4) as usize);
                 for pixel in rgba_img.pixels() {
                     pixels.push(pixel[0]);
@@ -654,8 +606,15 @@ pub fn export_gif(
                 }
             }
 
-            Ok(filename.to_string_lossy().to_string())
-        })();
+            // Dropping the encoder writes the GIF trailer before the rename
+            drop(encoder);
+            Ok(())
+        })
+        .map(|_| filename.to_string_lossy().to_string());
+
+        if result.is_err() && config.output_path.is_none() {
+            let _ = std::fs::remove_file(&filename);
+        }
 
         match result {
             Ok(path) => {
@@ -749,7 +708,7 @@ pub struct HistoryItem {
     pub modified: u64,     // unix timestamp
     pub size: u64,         // file size in bytes
     pub thumbnail: String, // base64 data URL
-    pub description: Option<String>, // Finder comment / caption
+    pub description: Option<String>, // caption (image metadata / sidecar)
 }
 
 #[derive(serde::Serialize)]
@@ -820,7 +779,7 @@ pub async fn get_history(
 
             let file_type = match ext.to_lowercase().as_str() {
                 "png" | "jpg" | "jpeg" => "screenshot",
-                "gif" => "gif",
+                "gif" | "webp" => "gif", // WebP only comes from animated exports
                 _ => continue,
             };
 
@@ -872,7 +831,7 @@ pub async fn get_history(
             .into_iter()
             .map(|f| {
                 let path_str = f.path.to_string_lossy().to_string();
-                let description = read_finder_comment(&path_str);
+                let description = read_caption(&path_str);
                 HistoryItem {
                     path: path_str,
                     filename: f.filename,
@@ -898,10 +857,7 @@ pub async fn get_history(
 #[tauri::command]
 pub async fn get_stats() -> Result<StatsResponse, String> {
     tokio::task::spawn_blocking(|| {
-        let output_dir = dirs::picture_dir()
-            .or_else(|| dirs::home_dir())
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("lovshot");
+        let output_dir = get_lovshot_dir();
 
         if !output_dir.exists() {
             return Ok(StatsResponse {
@@ -937,7 +893,7 @@ pub async fn get_stats() -> Result<StatsResponse, String> {
 
             let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
             let is_screenshot = matches!(ext.to_lowercase().as_str(), "png" | "jpg" | "jpeg");
-            let is_gif = ext.to_lowercase() == "gif";
+            let is_gif = matches!(ext.to_lowercase().as_str(), "gif" | "webp");
 
             if !is_screenshot && !is_gif {
                 continue;
@@ -988,13 +944,13 @@ pub fn save_caption(app: AppHandle, path: String, caption: String, close_window:
         return Err(format!("File not found: {}", path));
     }
 
-    // Write Finder comment in background, emit event when done
+    // Write caption metadata in background, emit event when done
     let path_clone = path.clone();
     let caption_clone = caption.clone();
     let app_clone = app.clone();
     std::thread::spawn(move || {
-        if let Err(e) = write_png_comment(&path_clone, &caption_clone) {
-            println!("[save_caption] PNG comment error: {}", e);
+        if let Err(e) = write_caption(&path_clone, &caption_clone) {
+            println!("[save_caption] Caption write error: {}", e);
         } else {
             // Emit event to notify frontend
             let _ = app_clone.emit("caption-saved", serde_json::json!({
@@ -1021,76 +977,9 @@ pub fn save_caption(app: AppHandle, path: String, caption: String, close_window:
     Ok(())
 }
 
-fn write_png_comment(path: &str, comment: &str) -> Result<(), String> {
-    // Set Finder comment using AppleScript via stdin (handles unicode properly)
-    #[cfg(target_os = "macos")]
-    {
-        use std::io::Write;
-        use std::process::{Command, Stdio};
-
-        // AppleScript to set Finder comment
-        let script = format!(
-            r#"set theFile to POSIX file "{}" as alias
-tell application "Finder" to set comment of theFile to "{}""#,
-            path.replace("\\", "\\\\").replace("\"", "\\\""),
-            comment.replace("\\", "\\\\").replace("\"", "\\\"")
-        );
-
-        let mut child = Command::new("osascript")
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| format!("osascript spawn error: {}", e))?;
-
-        child
-            .stdin
-            .take()
-            .unwrap()
-            .write_all(script.as_bytes())
-            .map_err(|e| format!("osascript write error: {}", e))?;
-
-        let output = child.wait_with_output().map_err(|e| format!("osascript error: {}", e))?;
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("AppleScript error: {}", stderr));
-        }
-    }
-
-    println!("[save_caption] Finder comment set successfully");
-    Ok(())
-}
-
-#[cfg(target_os = "macos")]
-fn read_finder_comment(path: &str) -> Option<String> {
-    use std::path::Path;
-
-    // Read directly from extended attribute (much faster than osascript)
-    let xattr_name = "com.apple.metadata:kMDItemFinderComment";
-    let path = Path::new(path);
-
-    // Get xattr value as bytes
-    let value = xattr::get(path, xattr_name).ok()??;
-
-    // Parse plist to extract the string
-    let plist: plist::Value = plist::from_bytes(&value).ok()?;
-    let comment = plist.as_string()?.to_string();
-
-    if comment.is_empty() {
-        None
-    } else {
-        Some(comment)
-    }
-}
-
-#[cfg(not(target_os = "macos"))]
-fn read_finder_comment(_path: &str) -> Option<String> {
-    None
-}
-
 #[tauri::command]
 pub fn get_image_description(path: String) -> Option<String> {
-    read_finder_comment(&path)
+    read_caption(&path)
 }
 
 #[tauri::command]
@@ -1098,6 +987,12 @@ pub fn delete_file(path: String) -> Result<(), String> {
     // Move to trash instead of permanent delete
     trash::delete(&path).map_err(|e| format!("Failed to delete file: {}", e))?;
     println!("[delete_file] Moved to trash: {}", path);
+
+    // Caption sidecar goes along with its image
+    let sidecar = sidecar_path(std::path::Path::new(&path));
+    if sidecar.exists() {
+        let _ = trash::delete(&sidecar);
+    }
     Ok(())
 }
 
@@ -1111,10 +1006,7 @@ pub struct FolderInfo {
 }
 
 fn get_lovshot_dir() -> PathBuf {
-    dirs::picture_dir()
-        .or_else(|| dirs::home_dir())
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("lovshot")
+    crate::output::lovshot_dir()
 }
 
 #[tauri::command]
@@ -1317,6 +1209,11 @@ pub fn move_to_folder(file_paths: Vec<String>, folder_path: Option<String>) -> R
 
         std::fs::rename(&src, &final_dest).map_err(|e| e.to_string())?;
         println!("[move_to_folder] Moved {:?} -> {:?}", src, final_dest);
+
+        let src_sidecar = sidecar_path(&src);
+        if src_sidecar.exists() {
+            let _ = std::fs::rename(&src_sidecar, sidecar_path(&final_dest));
+        }
         new_paths.push(final_dest.to_string_lossy().to_string());
     }
 
@@ -1367,7 +1264,7 @@ pub fn preview_folder_export(folder_path: Option<String>, format: String) -> Res
     let mut lines: Vec<String> = vec![];
 
     for (path, filename, _) in &files {
-        let caption = read_finder_comment(&path.to_string_lossy());
+        let caption = read_caption(&path.to_string_lossy());
         let alt_text = caption.as_deref().unwrap_or(filename);
         let path_str = path.to_string_lossy();
 
@@ -1451,6 +1348,34 @@ pub fn save_rgba_to_file(data: Vec<u8>, width: u32, height: u32, path: String) -
     Ok(())
 }
 
+/// Crop a logical region (screen coordinates) out of the frozen screen snapshot
+pub(crate) fn crop_snapshot(
+    snapshot: &RgbaImage,
+    region: &crate::types::Region,
+    screen_x: i32,
+    screen_y: i32,
+    screen_scale: f32,
+) -> Result<RgbaImage, String> {
+    // Convert logical pixels to physical pixels
+    let rel_x = ((region.x - screen_x) as f32 * screen_scale).max(0.0) as u32;
+    let rel_y = ((region.y - screen_y) as f32 * screen_scale).max(0.0) as u32;
+    let phys_w = (region.width as f32 * screen_scale) as u32;
+    let phys_h = (region.height as f32 * screen_scale) as u32;
+
+    let max_x = snapshot.width().saturating_sub(1);
+    let max_y = snapshot.height().saturating_sub(1);
+    let crop_x = rel_x.min(max_x);
+    let crop_y = rel_y.min(max_y);
+    let crop_w = phys_w.min(snapshot.width().saturating_sub(crop_x));
+    let crop_h = phys_h.min(snapshot.height().saturating_sub(crop_y));
+
+    if crop_w == 0 || crop_h == 0 {
+        return Err("Invalid capture area".to_string());
+    }
+
+    Ok(image::imageops::crop_imm(snapshot, crop_x, crop_y, crop_w, crop_h).to_image())
+}
+
 /// Capture a region and return base64 PNG for annotation editing
 #[tauri::command(rename_all = "camelCase")]
 pub fn capture_region_preview(
@@ -1470,24 +1395,7 @@ pub fn capture_region_preview(
     // Try to use cached snapshot first (for static mode)
     let captured_rgba = if let Some(ref snapshot) = cached_snapshot {
         println!("[capture_region_preview] Using cached snapshot");
-        // Convert logical pixels to physical pixels
-        let rel_x = ((region.x - screen_x) as f32 * screen_scale).max(0.0) as u32;
-        let rel_y = ((region.y - screen_y) as f32 * screen_scale).max(0.0) as u32;
-        let phys_w = (region.width as f32 * screen_scale) as u32;
-        let phys_h = (region.height as f32 * screen_scale) as u32;
-
-        let max_x = snapshot.width().saturating_sub(1);
-        let max_y = snapshot.height().saturating_sub(1);
-        let crop_x = rel_x.min(max_x);
-        let crop_y = rel_y.min(max_y);
-        let crop_w = phys_w.min(snapshot.width().saturating_sub(crop_x));
-        let crop_h = phys_h.min(snapshot.height().saturating_sub(crop_y));
-
-        if crop_w == 0 || crop_h == 0 {
-            return Err("Invalid capture area".to_string());
-        }
-
-        image::imageops::crop_imm(snapshot, crop_x, crop_y, crop_w, crop_h).to_image()
+        crop_snapshot(snapshot, &region, screen_x, screen_y, screen_scale)?
     } else {
         // Capture live screen
         println!("[capture_region_preview] Capturing live screen");
@@ -1496,10 +1404,7 @@ pub fn capture_region_preview(
             return Err("No screens found".to_string());
         }
 
-        let screen = &screens[0];
-        let captured = screen
-            .capture_area(region.x, region.y, region.width, region.height)
-            .map_err(|e| e.to_string())?;
+        let captured = capture_region(&screens, region.x, region.y, region.width, region.height)?;
 
         RgbaImage::from_raw(captured.width(), captured.height(), captured.into_raw())
             .ok_or("Failed to convert image")?
@@ -1521,27 +1426,67 @@ pub fn capture_region_preview(
 }
 
 /// Save an annotated screenshot from base64 PNG data
+///
+/// When the editor also sends the unannotated capture and its scene, both are
+/// embedded so the screenshot can be reopened with editable annotations.
 #[tauri::command(rename_all = "camelCase")]
 pub fn save_annotated_screenshot(
     app: AppHandle,
     image_data: String,
     caption_mode: Option<bool>,
+    original_data: Option<String>,
+    scene: Option<AnnotationScene>,
 ) -> Result<String, String> {
     println!("[save_annotated_screenshot] Saving annotated screenshot, caption_mode={:?}", caption_mode);
 
-    // Decode base64
-    let decoded = STANDARD.decode(&image_data)
-        .map_err(|e| format!("Base64 decode error: {}", e))?;
+    let img = decode_png_data(&image_data)?;
+    println!("[save_annotated_screenshot] Image size: {}x{}", img.width(), img.height());
 
-    // Load as image
-    let mut img = image::load_from_memory(&decoded)
-        .map_err(|e| format!("Image load error: {}", e))?
-        .to_rgba8();
+    let layers = match (original_data, scene) {
+        (Some(data), Some(scene)) => Some((decode_png_data(&data)?, scene)),
+        _ => None,
+    };
+    finish_annotated_save(&app, img, layers, caption_mode)
+}
 
-    println!("[save_annotated_screenshot] Image size: {}x{}", img.width(), img.height());
+/// Render an annotation scene onto the original capture in Rust and save it
+///
+/// `image_data` is the unannotated capture (as returned by capture_region_preview).
+#[tauri::command(rename_all = "camelCase")]
+pub fn render_annotated_screenshot(
+    app: AppHandle,
+    image_data: String,
+    scene: AnnotationScene,
+    caption_mode: Option<bool>,
+) -> Result<String, String> {
+    println!("[render_annotated_screenshot] {} annotations, scale {}", scene.annotations.len(), scene.scale);
 
+    let original = decode_png_data(&image_data)?;
+    let img = render_annotations(&original, &scene)?;
+    finish_annotated_save(&app, img, Some((original, scene)), caption_mode)
+}
+
+/// Base64 image, with or without a data URL prefix
+fn decode_png_data(data: &str) -> Result<RgbaImage, String> {
+    let data = data.split_once(";base64,").map(|(_, d)| d).unwrap_or(data);
+    let decoded = STANDARD.decode(data)
+        .map_err(|e| format!("Base64 decode error: {}", e))?;
+    Ok(image::load_from_memory(&decoded)
+        .map_err(|e| format!("Image load error: {}", e))?
+        .to_rgba8())
+}
+
+/// Watermark, copy, save and preview an annotated screenshot
+///
+/// `layers` (original capture and scene) are embedded in the PNG when given.
+fn finish_annotated_save(
+    app: &AppHandle,
+    mut img: RgbaImage,
+    layers: Option<(RgbaImage, AnnotationScene)>,
+    caption_mode: Option<bool>,
+) -> Result<String, String> {
     // Add watermark with screenshot number
-    let screenshot_number = crate::config::count_screenshots();
+    let screenshot_number = crate::config::next_screenshot_number();
     add_screenshot_watermark(&mut img, screenshot_number);
 
     // Copy to clipboard
@@ -1555,17 +1500,22 @@ pub fn save_annotated_screenshot(
     println!("[save_annotated_screenshot] Copied to clipboard");
 
     // Save to file
-    let output_dir = dirs::picture_dir()
-        .or_else(|| dirs::home_dir())
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("lovshot");
-
-    std::fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
-
-    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-    let filename = output_dir.join(format!("screenshot_{}.png", timestamp));
-
-    img.save(&filename).map_err(|e| format!("Save error: {}", e))?;
+    let ctx = FilenameContext::new("annotated").with_counter(screenshot_number);
+    let filename = build_output_path(OutputKind::Screenshot, ctx, "png")?;
+
+    let filename = match layers {
+        Some((original, scene)) => {
+            let record = AnnotationRecord { scene, screenshot_number: Some(screenshot_number) };
+            let png = embed_layers(&encode_png(&img)?, &original, &record)?;
+            let path = create_unique_file(&filename)?;
+            if let Err(e) = write_file_atomic(&path, |tmp| std::fs::write(tmp, &png).map_err(|e| e.to_string())) {
+                let _ = std::fs::remove_file(&path);
+                return Err(format!("Save error: {}", e));
+            }
+            path
+        }
+        None => save_image_unique(&img, &filename).map_err(|e| format!("Save error: {}", e))?,
+    };
     println!("[save_annotated_screenshot] Saved to {:?}", filename);
 
     let path_str = filename.to_string_lossy().to_string();
@@ -1574,13 +1524,13 @@ pub fn save_annotated_screenshot(
     // Show preview window
     let is_caption_mode = caption_mode.unwrap_or(false);
     if is_caption_mode {
-        if let Err(e) = crate::windows::open_caption_window(&app, &path_str, None) {
+        if let Err(e) = crate::windows::open_caption_window(app, &path_str, None) {
             println!("[save_annotated_screenshot] Failed to open caption window: {}", e);
         }
     } else {
         let cfg = crate::config::load_config();
         if cfg.screenshot_preview_enabled {
-            if let Err(e) = crate::windows::open_preview_window(&app, &path_str) {
+            if let Err(e) = crate::windows::open_preview_window(app, &path_str) {
                 println!("[save_annotated_screenshot] Failed to open preview: {}", e);
             }
         }
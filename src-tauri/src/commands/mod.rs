@@ -2,16 +2,20 @@ mod config;
 mod export;
 mod mouse;
 mod permission;
+mod quick_capture;
 mod recording;
 mod screen;
 mod scroll;
 mod selector;
+mod timelapse;
 
 pub use config::*;
 pub use export::*;
 pub use mouse::*;
 pub use permission::*;
+pub use quick_capture::*;
 pub use recording::*;
 pub use screen::*;
 pub use scroll::*;
 pub use selector::*;
+pub use timelapse::*;
@@ -0,0 +1,194 @@
+//! Captures that skip the selector: repeating the last region, region presets,
+//! and the countdown half of a delayed screenshot
+
+use std::time::{Duration, Instant};
+
+use mouse_position::mouse_position::Mouse;
+use tauri::{AppHandle, Manager};
+
+use crate::capture::{display_at, display_infos, plan_region, Screen};
+#[cfg(not(target_os = "macos"))]
+use crate::capture::physical_to_logical;
+use crate::commands::{save_screenshot, start_recording, stop_recording};
+use crate::config;
+use crate::shortcuts::{register_stop_shortcuts, unregister_stop_shortcuts};
+use crate::state::SharedState;
+use crate::tray::create_countdown_overlay;
+use crate::types::{CaptureMode, Region};
+
+/// Capture `region` right away through the normal screenshot / recording flows
+///
+/// Screenshots are always taken live, since there is no frozen screen without the selector.
+/// `output_scale` resizes the saved screenshot, as the selector's scale option does.
+pub(crate) fn capture_region_now(
+    app: &AppHandle,
+    region: Region,
+    mode: CaptureMode,
+    output_scale: Option<f32>,
+) -> Result<(), String> {
+    let state = app.state::<SharedState>();
+    {
+        let mut s = state.lock().unwrap();
+        s.region = Some(region);
+        s.pending_mode = Some(mode);
+    }
+
+    match mode {
+        CaptureMode::Image | CaptureMode::StaticImage => {
+            save_screenshot(app.clone(), state, output_scale, Some(false), None).map(|_| ())
+        }
+        CaptureMode::Gif | CaptureMode::Video => start_recording(app.clone(), state),
+        CaptureMode::Scroll => Err("Scroll capture needs the selector".to_string()),
+    }
+}
+
+/// Cursor position in logical pixels
+pub(crate) fn cursor_position() -> Option<(f64, f64)> {
+    let Mouse::Position { x, y } = Mouse::get_mouse_position() else {
+        return None;
+    };
+    #[cfg(not(target_os = "macos"))]
+    return Some(physical_to_logical(x as f64, y as f64));
+    #[cfg(target_os = "macos")]
+    Some((x as f64, y as f64))
+}
+
+/// Logical bounds of the screen containing a point (first screen as fallback)
+pub(crate) fn screen_bounds_at(x: f64, y: f64) -> Result<(i32, i32, u32, u32), String> {
+    let displays = display_infos(&Screen::all()?);
+    let d = display_at(&displays, x, y)
+        .and_then(|i| displays.get(i))
+        .or_else(|| displays.first())
+        .ok_or("No screens found")?;
+    Ok((d.x, d.y, d.width, d.height))
+}
+
+/// Tauri command: capture the last used region again, in its mode
+///
+/// While a recording is running this stops it instead, like the other capture shortcuts.
+#[tauri::command]
+pub fn repeat_last_capture(app: AppHandle) -> Result<(), String> {
+    let state = app.state::<SharedState>();
+    if state.lock().unwrap().recording {
+        stop_recording(app.clone(), state);
+        return Ok(());
+    }
+
+    let last = config::load_config()
+        .last_capture
+        .ok_or("Nothing captured yet")?;
+    println!(
+        "[repeat_last_capture] {:?} x={}, y={}, w={}, h={}",
+        last.mode, last.region.x, last.region.y, last.region.width, last.region.height
+    );
+    capture_region_now(&app, last.region, last.mode, None)
+}
+
+/// Tauri command: capture a region preset; sized presets are centred on the cursor
+#[tauri::command]
+pub fn capture_preset(app: AppHandle, id: String) -> Result<(), String> {
+    if app.state::<SharedState>().lock().unwrap().recording {
+        return Err("Already recording".to_string());
+    }
+    let preset = config::load_config()
+        .region_presets
+        .into_iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| format!("Unknown preset: {}", id))?;
+
+    let cursor = cursor_position().unwrap_or((0.0, 0.0));
+    let region = preset.resolve(cursor, screen_bounds_at(cursor.0, cursor.1)?);
+    // The region is in points; scale the capture back down to the preset's pixel size
+    let (capture_scale, _) = plan_region(
+        &display_infos(&Screen::all()?),
+        region.x,
+        region.y,
+        region.width,
+        region.height,
+    );
+    println!(
+        "[capture_preset] {} -> x={}, y={}, w={}, h={}, scale={}",
+        preset.name, region.x, region.y, region.width, region.height, capture_scale
+    );
+    capture_region_now(&app, region, preset.mode, Some(preset.output_scale(capture_scale)))
+}
+
+/// Longest countdown accepted for a delayed screenshot
+const MAX_CAPTURE_DELAY: u32 = 60;
+
+/// Tauri command: screenshot `region` (or the screen under the cursor) after a countdown
+///
+/// The countdown overlay leaves the screen alone, so open menus and hover states survive
+/// until the capture. The stop shortcut (ESC) cancels it.
+#[tauri::command]
+pub fn start_delayed_capture(app: AppHandle, seconds: Option<u32>, region: Option<Region>) -> Result<(), String> {
+    let seconds = seconds
+        .unwrap_or_else(|| config::load_config().capture_delay_secs)
+        .clamp(1, MAX_CAPTURE_DELAY);
+    let region = match region {
+        Some(r) => r,
+        None => {
+            let cursor = cursor_position().unwrap_or((0.0, 0.0));
+            let (x, y, width, height) = screen_bounds_at(cursor.0, cursor.1)?;
+            Region { x, y, width, height }
+        }
+    };
+
+    let state = app.state::<SharedState>().inner().clone();
+    {
+        let mut s = state.lock().unwrap();
+        if s.recording {
+            return Err("Already recording".to_string());
+        }
+        if s.countdown_active {
+            return Err("A delayed capture is already counting down".to_string());
+        }
+        s.countdown_active = true;
+    }
+    println!(
+        "[start_delayed_capture] {}s x={}, y={}, w={}, h={}",
+        seconds, region.x, region.y, region.width, region.height
+    );
+
+    register_stop_shortcuts(&app);
+    create_countdown_overlay(&app, &region, seconds);
+
+    std::thread::spawn(move || {
+        let deadline = Instant::now() + Duration::from_secs(seconds as u64);
+        while Instant::now() < deadline {
+            if !state.lock().unwrap().countdown_active {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        if let Some(win) = app.get_webview_window("recording-overlay") {
+            let _ = win.destroy();
+        }
+        let cancelled = !std::mem::replace(&mut state.lock().unwrap().countdown_active, false);
+        unregister_stop_shortcuts(&app);
+        if cancelled {
+            println!("[start_delayed_capture] 已取消");
+            return;
+        }
+
+        // Give the compositor a moment to drop the overlay before grabbing pixels
+        std::thread::sleep(Duration::from_millis(150));
+        if let Err(e) = capture_region_now(&app, region, CaptureMode::Image, None) {
+            eprintln!("[start_delayed_capture] Capture failed: {}", e);
+        }
+    });
+    Ok(())
+}
+
+/// Tauri command: stop a running countdown without capturing
+#[tauri::command]
+pub fn cancel_delayed_capture(state: tauri::State<SharedState>) {
+    state.lock().unwrap().countdown_active = false;
+}
+
+/// Tauri command: delay requested for the selector that is opening (consumed on read)
+#[tauri::command]
+pub fn take_pending_delay(state: tauri::State<SharedState>) -> Option<u32> {
+    state.lock().unwrap().pending_delay.take()
+}
@@ -1,14 +1,14 @@
 use std::thread;
 use std::time::{Duration, Instant};
 
-use crate::capture::Screen;
+use crate::capture::{capture_region, Screen};
 use image::RgbaImage;
 use tauri::{AppHandle, Emitter, Manager};
 
 use crate::shortcuts::{register_stop_shortcuts, unregister_stop_shortcuts};
 use crate::state::SharedState;
 use crate::tray::{create_recording_overlay, update_tray_icon};
-use crate::types::{RecordingInfo, RecordingState};
+use crate::types::{CaptureMode, RecordingInfo, RecordingState};
 use crate::windows::{open_editor_window, set_activation_policy};
 
 #[tauri::command]
@@ -29,7 +29,12 @@ pub fn start_recording(app: AppHandle, state: tauri::State<SharedState>) -> Resu
     s.frames.clear();
 
     let recording_fps = s.recording_fps;
+    let mode = match s.pending_mode {
+        Some(CaptureMode::Video) => CaptureMode::Video,
+        _ => CaptureMode::Gif,
+    };
     drop(s);
+    crate::config::remember_last_capture(&region, mode);
 
     // Register stop shortcuts (ESC, etc.) now that we're recording
     register_stop_shortcuts(&app);
@@ -47,12 +52,9 @@ pub fn start_recording(app: AppHandle, state: tauri::State<SharedState>) -> Resu
             println!("[DEBUG][recording_thread] 错误: 没有找到屏幕");
             return;
         }
-        let screen = &screens[0];
         println!(
-            "[DEBUG][recording_thread] 屏幕: {}x{}, scale={}, fps={}",
-            screen.display_info.width,
-            screen.display_info.height,
-            screen.display_info.scale_factor,
+            "[DEBUG][recording_thread] 屏幕数: {}, fps={}",
+            screens.len(),
             recording_fps
         );
         let frame_duration = Duration::from_millis(1000 / recording_fps as u64);
@@ -95,7 +97,7 @@ pub fn start_recording(app: AppHandle, state: tauri::State<SharedState>) -> Resu
                 }
             }
 
-            match screen.capture_area(region.x, region.y, region.width, region.height) {
+            match capture_region(&screens, region.x, region.y, region.width, region.height) {
                 Ok(img) => {
                     let rgba =
                         RgbaImage::from_raw(img.width(), img.height(), img.into_raw()).unwrap();
@@ -118,7 +120,7 @@ pub fn start_recording(app: AppHandle, state: tauri::State<SharedState>) -> Resu
                 }
                 Err(e) => {
                     if frame_idx == 0 {
-                        println!("[DEBUG][recording_thread] capture_area 失败: {:?}", e);
+                        println!("[DEBUG][recording_thread] capture_region 失败: {:?}", e);
                         println!(
                             "[DEBUG][recording_thread] 参数: x={}, y={}, w={}, h={}",
                             region.x, region.y, region.width, region.height
@@ -1,4 +1,4 @@
-use crate::capture::Screen;
+use crate::capture::{capture_desktop, Screen};
 use base64::{engine::general_purpose::STANDARD, Engine};
 
 #[tauri::command]
@@ -19,15 +19,10 @@ pub fn get_screens() -> Vec<serde_json::Value> {
         .collect()
 }
 
+/// Whole desktop (all monitors composited) as a PNG data URL
 #[tauri::command]
 pub fn capture_screenshot() -> Result<String, String> {
-    let screens = Screen::all().map_err(|e| e.to_string())?;
-    if screens.is_empty() {
-        return Err("No screens found".to_string());
-    }
-
-    let screen = &screens[0];
-    let img = screen.capture().map_err(|e| e.to_string())?;
+    let img = capture_desktop()?;
 
     use image::ImageEncoder;
     let mut png_data = Vec::new();
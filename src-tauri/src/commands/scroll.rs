@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use crate::capture::Screen;
+use crate::capture::{capture_region, Screen};
 use base64::{engine::general_purpose::STANDARD, Engine};
 use image::codecs::jpeg::JpegEncoder;
 use image::ExtendedColorType;
@@ -42,12 +42,10 @@ fn capture_initial_scroll_frame(
         return Err("No screens found".to_string());
     }
 
-    let screen = &screens[0];
-    let captured = screen
-        .capture_area(region.x, region.y, region.width, region.height)
+    let captured = capture_region(&screens, region.x, region.y, region.width, region.height)
         .map_err(|e| {
-            println!("[DEBUG][capture_initial_scroll_frame] capture_area 错误: {}", e);
-            e.to_string()
+            println!("[DEBUG][capture_initial_scroll_frame] capture_region 错误: {}", e);
+            e
         })?;
 
     let frame = RgbaImage::from_raw(captured.width(), captured.height(), captured.into_raw())
@@ -126,10 +124,7 @@ pub fn capture_scroll_frame_auto(
         return Err("No screens found".to_string());
     }
 
-    let screen = &screens[0];
-    let captured = screen
-        .capture_area(region.x, region.y, region.width, region.height)
-        .map_err(|e| e.to_string())?;
+    let captured = capture_region(&screens, region.x, region.y, region.width, region.height)?;
 
     let new_frame = RgbaImage::from_raw(captured.width(), captured.height(), captured.into_raw())
         .ok_or("Failed to convert image")?;
@@ -1,7 +1,11 @@
-use crate::capture::Screen;
+use crate::capture::{display_at, display_infos, Screen};
 use mouse_position::mouse_position::Mouse;
 use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindowBuilder};
 
+use crate::color::{color_info, push_history, ColorInfo};
+use crate::commands::cursor_position;
+use crate::config;
+use crate::presets::{fit_aspect, parse_aspect_ratio};
 use crate::state::SharedState;
 use crate::types::{CaptureMode, Region, WindowInfo};
 use crate::windows::{open_permission_window, set_activation_policy};
@@ -12,6 +16,8 @@ use crate::native_screenshot;
 use crate::permission;
 #[cfg(target_os = "macos")]
 use crate::window_detect;
+#[cfg(not(target_os = "macos"))]
+use crate::window_capture::{hit_test, window_entries};
 
 #[tauri::command]
 pub fn open_selector(app: AppHandle, state: tauri::State<SharedState>) -> Result<(), String> {
@@ -57,22 +63,26 @@ pub fn open_selector(app: AppHandle, state: tauri::State<SharedState>) -> Result
     }
 
     let screens = Screen::all().map_err(|e| e.to_string())?;
-    if screens.is_empty() {
-        return Err("No screens found".to_string());
-    }
-
-    let screen = &screens[0];
+    let screen = selector_screen(&screens).ok_or("No screens found")?;
     let screen_x = screen.display_info.x;
     let screen_y = screen.display_info.y;
     let width = screen.display_info.width;
     let height = screen.display_info.height;
     let scale = screen.display_info.scale_factor;
 
+    // Window snapping hit-tests this list on every mouse move instead of re-listing windows
+    #[cfg(not(target_os = "macos"))]
+    let windows = window_entries();
+
     {
         let mut s = state.lock().unwrap();
         s.screen_x = screen_x;
         s.screen_y = screen_y;
         s.screen_scale = scale;
+        #[cfg(not(target_os = "macos"))]
+        {
+            s.window_cache = windows;
+        }
     }
 
     println!("[DEBUG][open_selector] 准备创建 selector 窗口");
@@ -126,6 +136,17 @@ pub fn set_region(state: tauri::State<SharedState>, region: Region) {
         "[DEBUG][set_region] ====== 被调用 ====== x={}, y={}, w={}, h={}",
         region.x, region.y, region.width, region.height
     );
+    // The selector applies the aspect-ratio lock; only report a mismatch here,
+    // since rewriting the region would desync it from what the editor exported
+    if let Some(ratio) = config::load_config().aspect_ratio_lock.as_deref().and_then(parse_aspect_ratio) {
+        let fitted = fit_aspect(&region, ratio);
+        if region.width.abs_diff(fitted.width) > 1 || region.height.abs_diff(fitted.height) > 1 {
+            println!(
+                "[set_region] 选区 {}x{} 与锁定比例 {:.3} 不符",
+                region.width, region.height, ratio
+            );
+        }
+    }
     let mut s = state.lock().unwrap();
     println!("[DEBUG][set_region] 直接使用逻辑像素坐标（不缩放）");
     s.region = Some(region);
@@ -194,22 +215,87 @@ pub fn get_magnifier_pixels(
     Some(pixels)
 }
 
+/// Pick the colour at (x, y) on the frozen screen, averaged over a `sample` x `sample`
+/// square of physical pixels, and remember it in the colour history
+#[tauri::command]
+pub fn pick_color(
+    state: tauri::State<SharedState>,
+    x: i32,
+    y: i32,
+    sample: Option<u32>,
+) -> Result<ColorInfo, String> {
+    let s = state.lock().unwrap();
+    let img = s.cached_snapshot.as_ref().ok_or("No screen snapshot")?;
+    let scale = s.screen_scale;
+
+    // Same logical -> physical mapping as get_magnifier_pixels
+    let px = ((x as f32 * scale) as i32).clamp(0, img.width() as i32 - 1);
+    let py = ((y as f32 * scale) as i32).clamp(0, img.height() as i32 - 1);
+    let half = (sample.unwrap_or(1).clamp(1, 15) / 2) as i32;
+
+    let mut sum = [0u32; 3];
+    let mut count = 0u32;
+    for row in (py - half).max(0)..=(py + half).min(img.height() as i32 - 1) {
+        for col in (px - half).max(0)..=(px + half).min(img.width() as i32 - 1) {
+            let p = img.get_pixel(col as u32, row as u32);
+            for c in 0..3 {
+                sum[c] += p[c] as u32;
+            }
+            count += 1;
+        }
+    }
+    drop(s);
+
+    let info = color_info(sum.map(|v| ((v as f32 / count as f32).round()) as u8));
+    println!("[pick_color] ({}, {}) -> {}", x, y, info.hex);
+
+    let mut cfg = config::load_config();
+    push_history(&mut cfg.color_history, &info.hex);
+    config::save_config(&cfg)?;
+    Ok(info)
+}
+
+/// Cursor in logical pixels while it's over the selector
+///
+/// The selector covers one screen, so its scale converts the physical
+/// position without looking the monitors up again.
+#[cfg(not(target_os = "macos"))]
+fn selector_cursor(scale: f32) -> Option<(f64, f64)> {
+    let Mouse::Position { x, y } = Mouse::get_mouse_position() else {
+        return None;
+    };
+    Some((x as f64 / scale as f64, y as f64 / scale as f64))
+}
+
 #[tauri::command]
-pub fn get_window_at_cursor() -> Option<Region> {
+pub fn get_window_at_cursor(state: tauri::State<SharedState>) -> Option<Region> {
     #[cfg(target_os = "macos")]
     {
+        let _ = state;
         if let Mouse::Position { x, y } = Mouse::get_mouse_position() {
             return window_detect::get_window_at_position(x as f64, y as f64);
         }
+        None
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let s = state.lock().unwrap();
+        let (x, y) = selector_cursor(s.screen_scale)?;
+        hit_test(&s.window_cache, x, y, std::process::id()).map(|w| Region {
+            x: w.x,
+            y: w.y,
+            width: w.width,
+            height: w.height,
+        })
     }
-    None
 }
 
 /// Get window info at cursor including titlebar height (for exclude-titlebar feature)
 #[tauri::command]
-pub fn get_window_info_at_cursor() -> Option<WindowInfo> {
+pub fn get_window_info_at_cursor(state: tauri::State<SharedState>) -> Option<WindowInfo> {
     #[cfg(target_os = "macos")]
     {
+        let _ = state;
         if let Mouse::Position { x, y } = Mouse::get_mouse_position() {
             if let Some(info) = window_detect::get_window_info_at_position(x as f64, y as f64) {
                 return Some(WindowInfo {
@@ -224,7 +310,18 @@ pub fn get_window_info_at_cursor() -> Option<WindowInfo> {
         None
     }
     #[cfg(not(target_os = "macos"))]
-    None
+    {
+        // xcap reports client-area bounds, so there is no separate titlebar to exclude
+        let s = state.lock().unwrap();
+        let (x, y) = selector_cursor(s.screen_scale)?;
+        hit_test(&s.window_cache, x, y, std::process::id()).map(|w| WindowInfo {
+            x: w.x,
+            y: w.y,
+            width: w.width,
+            height: w.height,
+            titlebar_height: 0,
+        })
+    }
 }
 
 #[tauri::command]
@@ -245,7 +342,11 @@ pub fn capture_screen_now(app: AppHandle, state: tauri::State<SharedState>) -> b
         };
 
         let start = std::time::Instant::now();
-        let cg_image = match native_screenshot::capture_cgimage() {
+        let (screen_x, screen_y) = {
+            let s = state.lock().unwrap();
+            (s.screen_x, s.screen_y)
+        };
+        let cg_image = match native_screenshot::capture_cgimage_at(screen_x as f64, screen_y as f64) {
             Some(img) => img,
             None => return false,
         };
@@ -308,6 +409,14 @@ pub fn activate_window_under_cursor() -> bool {
     false
 }
 
+/// Screen the selector opens on: the one under the cursor, else the first
+fn selector_screen(screens: &[Screen]) -> Option<&Screen> {
+    let index = cursor_position()
+        .and_then(|(x, y)| display_at(&display_infos(screens), x, y))
+        .unwrap_or(0);
+    screens.get(index)
+}
+
 /// Internal function to open selector (called from shortcut handler)
 pub fn open_selector_internal(app: AppHandle) -> Result<(), String> {
     println!("[DEBUG][open_selector_internal] 入口");
@@ -355,11 +464,7 @@ pub fn open_selector_internal(app: AppHandle) -> Result<(), String> {
     }
 
     let screens = Screen::all().map_err(|e| e.to_string())?;
-    if screens.is_empty() {
-        return Err("No screens found".to_string());
-    }
-
-    let screen = &screens[0];
+    let screen = selector_screen(&screens).ok_or("No screens found")?;
     let screen_x = screen.display_info.x;
     let screen_y = screen.display_info.y;
     let width = screen.display_info.width;
@@ -373,7 +478,7 @@ pub fn open_selector_internal(app: AppHandle) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     let cg_image = {
         let start = std::time::Instant::now();
-        let img = native_screenshot::capture_cgimage();
+        let img = native_screenshot::capture_cgimage_at(screen_x as f64, screen_y as f64);
         println!("[DEBUG][open_selector_internal] 原生截屏 {}ms", start.elapsed().as_millis());
 
         // Cache RGBA for magnifier (no base64 encoding needed - use get_magnifier_pixels instead)
@@ -392,12 +497,20 @@ pub fn open_selector_internal(app: AppHandle) -> Result<(), String> {
     #[cfg(not(target_os = "macos"))]
     let cg_image: Option<()> = None;
 
+    // Snapshot the windows for snapping, as open_selector does
+    #[cfg(not(target_os = "macos"))]
+    let windows = window_entries();
+
     {
         let state = app.state::<SharedState>();
         let mut s = state.lock().unwrap();
         s.screen_x = screen_x;
         s.screen_y = screen_y;
         s.screen_scale = scale;
+        #[cfg(not(target_os = "macos"))]
+        {
+            s.window_cache = windows;
+        }
     }
 
     let win = WebviewWindowBuilder::new(&app, "selector", WebviewUrl::App("/selector.html".into()))
@@ -0,0 +1,242 @@
+//! Timelapse: capture a region every few seconds, straight to a numbered
+//! image sequence in a library folder, then optionally export it as an animation
+
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::animation::{write_animation, AnimationFormat};
+use crate::capture::{capture_region, Screen};
+use crate::commands::{cursor_position, screen_bounds_at};
+use crate::config;
+use crate::output::{
+    build_output_path, create_unique_file, lovshot_dir, save_image_atomic, write_file_atomic,
+    FilenameContext, OutputKind,
+};
+use crate::state::SharedState;
+use crate::tray::{update_tray_icon, update_tray_menu};
+use crate::types::{Region, SaveResult};
+
+/// Interval between frames is clamped to this range (seconds)
+const MIN_INTERVAL: u32 = 1;
+const MAX_INTERVAL: u32 = 3600;
+
+#[derive(Clone, serde::Serialize)]
+pub struct TimelapseProgress {
+    pub folder: String,
+    pub frame_count: u32,
+}
+
+/// New library folder for a sequence: timelapse_YYYYMMDD_HHMMSS[_n]
+fn create_sequence_dir() -> Result<PathBuf, String> {
+    let base = lovshot_dir();
+    let stem = format!("timelapse_{}", Local::now().format("%Y%m%d_%H%M%S"));
+    let mut dir = base.join(&stem);
+    let mut n = 1;
+    while dir.exists() {
+        dir = base.join(format!("{}_{}", stem, n));
+        n += 1;
+    }
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+/// Numbered frames (00001.png, ...) of a sequence folder, in order
+fn sequence_frames(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut frames: Vec<(u64, PathBuf)> = std::fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("png"))
+        .filter_map(|p| {
+            let index = p.file_stem()?.to_str()?.parse::<u64>().ok()?;
+            Some((index, p))
+        })
+        .collect();
+    frames.sort_by_key(|(index, _)| *index);
+    Ok(frames.into_iter().map(|(_, p)| p).collect())
+}
+
+/// Tauri command: capture a region every `interval_secs` for `duration_mins` (0 = until stopped)
+///
+/// Without a region, the last captured region is reused, else the screen under the cursor.
+/// Returns the folder the frames are written to.
+#[tauri::command(rename_all = "camelCase")]
+pub fn start_timelapse(
+    app: AppHandle,
+    region: Option<Region>,
+    interval_secs: Option<u32>,
+    duration_mins: Option<u32>,
+) -> Result<String, String> {
+    let cfg = config::load_config();
+    let interval = interval_secs
+        .unwrap_or(cfg.timelapse_interval_secs)
+        .clamp(MIN_INTERVAL, MAX_INTERVAL);
+    let duration_mins = duration_mins.unwrap_or(cfg.timelapse_duration_mins);
+    let region = match region.or_else(|| cfg.last_capture.map(|last| last.region)) {
+        Some(r) => r,
+        None => {
+            let cursor = cursor_position().unwrap_or((0.0, 0.0));
+            let (x, y, width, height) = screen_bounds_at(cursor.0, cursor.1)?;
+            Region { x, y, width, height }
+        }
+    };
+
+    let state = app.state::<SharedState>().inner().clone();
+    {
+        let mut s = state.lock().unwrap();
+        if s.recording {
+            return Err("Already recording".to_string());
+        }
+        if s.timelapse_active {
+            return Err("A timelapse is already running".to_string());
+        }
+        s.timelapse_active = true;
+    }
+
+    let dir = match create_sequence_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            state.lock().unwrap().timelapse_active = false;
+            return Err(e);
+        }
+    };
+    let folder = dir.to_string_lossy().to_string();
+    println!(
+        "[start_timelapse] every {}s for {} min, x={}, y={}, w={}, h={} -> {}",
+        interval, duration_mins, region.x, region.y, region.width, region.height, folder
+    );
+
+    update_tray_icon(&app, true);
+    update_tray_menu(&app);
+
+    let folder_for_thread = folder.clone();
+    thread::spawn(move || {
+        let folder = folder_for_thread;
+        let screens = Screen::all().unwrap_or_default();
+        let interval = Duration::from_secs(interval as u64);
+        let deadline = (duration_mins > 0)
+            .then(|| Instant::now() + Duration::from_secs(duration_mins as u64 * 60));
+
+        let mut frame_count = 0u32;
+        let mut next_tick = Instant::now();
+        'capture: loop {
+            match capture_region(&screens, region.x, region.y, region.width, region.height) {
+                Ok(img) => {
+                    let path = dir.join(format!("{:05}.png", frame_count + 1));
+                    match save_image_atomic(&img, &path) {
+                        Ok(()) => {
+                            frame_count += 1;
+                            let _ = app.emit(
+                                "timelapse-progress",
+                                TimelapseProgress { folder: folder.clone(), frame_count },
+                            );
+                        }
+                        Err(e) => eprintln!("[timelapse] Failed to save frame: {}", e),
+                    }
+                }
+                // A missed frame only leaves a gap; keep going
+                Err(e) => eprintln!("[timelapse] Capture failed: {}", e),
+            }
+
+            next_tick += interval;
+            if deadline.is_some_and(|d| next_tick > d) {
+                break;
+            }
+            // Sleep in short steps so stopping takes effect right away
+            while Instant::now() < next_tick {
+                if !state.lock().unwrap().timelapse_active {
+                    break 'capture;
+                }
+                thread::sleep(Duration::from_millis(200));
+            }
+            if !state.lock().unwrap().timelapse_active {
+                break;
+            }
+        }
+
+        state.lock().unwrap().timelapse_active = false;
+        println!("[timelapse] 结束，共 {} 帧 -> {}", frame_count, folder);
+        update_tray_icon(&app, false);
+        update_tray_menu(&app);
+        let _ = app.emit("timelapse-stopped", TimelapseProgress { folder, frame_count });
+    });
+
+    Ok(folder)
+}
+
+/// Tauri command: stop a running timelapse (frames captured so far are kept)
+#[tauri::command]
+pub fn stop_timelapse(state: tauri::State<SharedState>) {
+    state.lock().unwrap().timelapse_active = false;
+}
+
+#[tauri::command]
+pub fn is_timelapse_active(state: tauri::State<SharedState>) -> bool {
+    state.lock().unwrap().timelapse_active
+}
+
+/// Tauri command: encode a sequence folder as an animated GIF / WebP in the library root
+///
+/// Runs in the background and reports through `timelapse-export-complete`.
+/// With `remove_frames`, the sequence folder is moved to the trash afterwards.
+#[tauri::command(rename_all = "camelCase")]
+pub fn export_timelapse(
+    app: AppHandle,
+    folder: String,
+    format: AnimationFormat,
+    fps: Option<u32>,
+    remove_frames: Option<bool>,
+) -> Result<(), String> {
+    let frames = sequence_frames(Path::new(&folder))?;
+    if frames.is_empty() {
+        return Err("No frames in this folder".to_string());
+    }
+    let delay_ms = 1000 / fps.unwrap_or(10).clamp(1, 50);
+
+    thread::spawn(move || {
+        println!("[export_timelapse] {} 帧, {:?}, {}ms/帧", frames.len(), format, delay_ms);
+        let result = build_output_path(
+            OutputKind::Recording,
+            FilenameContext::new("timelapse"),
+            format.extension(),
+        )
+        .and_then(|p| create_unique_file(&p))
+        .and_then(|path| {
+            let written = write_file_atomic(&path, |tmp| {
+                // Frames are decoded one at a time so long sequences stay out of memory
+                let images = frames.iter().map(|p| {
+                    image::open(p)
+                        .map(|img| img.to_rgba8())
+                        .map_err(|e| format!("Failed to open {}: {}", p.display(), e))
+                });
+                write_animation(tmp, format, images, delay_ms).map(|_| ())
+            });
+            if let Err(e) = written {
+                let _ = std::fs::remove_file(&path);
+                return Err(e);
+            }
+            Ok(path)
+        });
+
+        let saved = match result {
+            Ok(path) => {
+                if remove_frames.unwrap_or(false) {
+                    if let Err(e) = trash::delete(&folder) {
+                        eprintln!("[export_timelapse] Failed to remove frames: {}", e);
+                    }
+                }
+                SaveResult { success: true, path: Some(path.to_string_lossy().to_string()), error: None }
+            }
+            Err(e) => {
+                eprintln!("[export_timelapse] {}", e);
+                SaveResult { success: false, path: None, error: Some(e) }
+            }
+        };
+        let _ = app.emit("timelapse-export-complete", saved);
+    });
+    Ok(())
+}
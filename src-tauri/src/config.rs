@@ -2,7 +2,11 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::presets::{default_region_presets, RegionPreset};
+use crate::types::{CaptureMode, Region};
 
 /// Shortcut configuration for a single shortcut binding
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -63,6 +67,98 @@ fn default_watermark_position() -> WatermarkPosition {
     WatermarkPosition::BottomRight
 }
 
+/// Watermark content and style
+/// Text placeholders: {number}, {date}, {user}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct WatermarkConfig {
+    #[serde(default = "default_watermark_text")]
+    pub text: String,
+    #[serde(default)]
+    pub image_path: Option<String>, // Optional logo PNG drawn before the text
+    #[serde(default = "default_watermark_opacity")]
+    pub opacity: f32, // 0.0 - 1.0
+    #[serde(default = "default_watermark_size")]
+    pub size: f32, // Font size as a fraction of the image's shorter side
+    #[serde(default = "default_watermark_color")]
+    pub color: String, // "#RRGGBB" or "#RRGGBBAA"
+    #[serde(default = "default_watermark_margin")]
+    pub margin: f32, // Distance from the edge, in multiples of font size
+}
+
+impl Default for WatermarkConfig {
+    fn default() -> Self {
+        Self {
+            text: default_watermark_text(),
+            image_path: None,
+            opacity: default_watermark_opacity(),
+            size: default_watermark_size(),
+            color: default_watermark_color(),
+            margin: default_watermark_margin(),
+        }
+    }
+}
+
+fn default_watermark_text() -> String {
+    "#{number}".to_string()
+}
+
+fn default_watermark_opacity() -> f32 {
+    0.7
+}
+
+fn default_watermark_size() -> f32 {
+    0.04
+}
+
+fn default_watermark_color() -> String {
+    "#282828".to_string()
+}
+
+fn default_watermark_margin() -> f32 {
+    0.4
+}
+
+/// Filename templates (without extension) for each kind of output
+/// Placeholders: {date}, {time}, {counter}, {mode}, {app}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FilenameTemplates {
+    #[serde(default = "default_screenshot_template")]
+    pub screenshot: String,
+    #[serde(default = "default_recording_template")]
+    pub recording: String,
+    #[serde(default = "default_share_template")]
+    pub share: String,
+}
+
+impl Default for FilenameTemplates {
+    fn default() -> Self {
+        Self {
+            screenshot: default_screenshot_template(),
+            recording: default_recording_template(),
+            share: default_share_template(),
+        }
+    }
+}
+
+/// Region and mode of the most recent capture, for "repeat last capture"
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LastCapture {
+    pub region: Region,
+    pub mode: CaptureMode,
+}
+
+fn default_screenshot_template() -> String {
+    "screenshot_{date}_{time}".to_string()
+}
+
+fn default_recording_template() -> String {
+    "recording_{date}_{time}".to_string()
+}
+
+fn default_share_template() -> String {
+    "share_{date}_{time}".to_string()
+}
+
 /// Application configuration (v2 - supports multiple shortcuts per action)
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -80,8 +176,42 @@ pub struct AppConfig {
     pub image_export_format: String, // "markdown", "html", "url_only"
     #[serde(default = "default_watermark_position")]
     pub watermark_position: WatermarkPosition,
+    #[serde(default)]
+    pub watermark: WatermarkConfig,
     #[serde(default = "default_show_caption_editor")]
     pub show_caption_editor: bool,
+    #[serde(default)]
+    pub output_dir: Option<String>, // None = ~/Pictures/lovshot
+    #[serde(default)]
+    pub filename_templates: FilenameTemplates,
+    #[serde(default)]
+    pub fonts: Vec<String>, // Font paths or file names tried before the built-in fallbacks
+    #[serde(default)]
+    pub color_history: Vec<String>, // Picked colours as "#RRGGBB", most recent first
+    #[serde(default)]
+    pub last_capture: Option<LastCapture>,
+    #[serde(default = "default_region_presets")]
+    pub region_presets: Vec<RegionPreset>,
+    #[serde(default)]
+    pub aspect_ratio_lock: Option<String>, // e.g. "16:9"; the selector keeps this ratio
+    #[serde(default = "default_capture_delay")]
+    pub capture_delay_secs: u32,
+    #[serde(default = "default_timelapse_interval")]
+    pub timelapse_interval_secs: u32,
+    #[serde(default = "default_timelapse_duration")]
+    pub timelapse_duration_mins: u32, // 0 = until stopped
+}
+
+fn default_capture_delay() -> u32 {
+    3
+}
+
+fn default_timelapse_interval() -> u32 {
+    10
+}
+
+fn default_timelapse_duration() -> u32 {
+    60
 }
 
 fn default_show_caption_editor() -> bool {
@@ -127,7 +257,18 @@ impl From<OldAppConfig> for AppConfig {
             screenshot_preview_enabled: old.screenshot_preview_enabled,
             image_export_format: default_image_export_format(),
             watermark_position: default_watermark_position(),
+            watermark: WatermarkConfig::default(),
             show_caption_editor: default_show_caption_editor(),
+            output_dir: None,
+            filename_templates: FilenameTemplates::default(),
+            fonts: Vec::new(),
+            color_history: Vec::new(),
+            last_capture: None,
+            region_presets: default_region_presets(),
+            aspect_ratio_lock: None,
+            capture_delay_secs: default_capture_delay(),
+            timelapse_interval_secs: default_timelapse_interval(),
+            timelapse_duration_mins: default_timelapse_duration(),
         }
     }
 }
@@ -216,6 +357,10 @@ impl Default for AppConfig {
             }],
         );
 
+        // Newer actions ship unbound, so upgrading never claims keys other apps may use
+        shortcuts.insert("delayed_screenshot".to_string(), Vec::new());
+        shortcuts.insert("repeat_last".to_string(), Vec::new());
+
         Self {
             version: "2.0.0".to_string(),
             shortcuts,
@@ -225,7 +370,18 @@ impl Default for AppConfig {
             screenshot_preview_enabled: true,
             image_export_format: default_image_export_format(),
             watermark_position: default_watermark_position(),
+            watermark: WatermarkConfig::default(),
             show_caption_editor: default_show_caption_editor(),
+            output_dir: None,
+            filename_templates: FilenameTemplates::default(),
+            fonts: Vec::new(),
+            color_history: Vec::new(),
+            last_capture: None,
+            region_presets: default_region_presets(),
+            aspect_ratio_lock: None,
+            capture_delay_secs: default_capture_delay(),
+            timelapse_interval_secs: default_timelapse_interval(),
+            timelapse_duration_mins: default_timelapse_duration(),
         }
     }
 }
@@ -399,20 +555,120 @@ pub fn remove_shortcut(action: &str, index: usize) -> Result<AppConfig, String>
     Ok(config)
 }
 
+/// Remember the region and mode of a capture (failures are only logged)
+pub fn remember_last_capture(region: &Region, mode: CaptureMode) {
+    let mut config = load_config();
+    config.last_capture = Some(LastCapture {
+        region: region.clone(),
+        mode,
+    });
+    if let Err(e) = save_config(&config) {
+        eprintln!("[config] Failed to save last capture: {}", e);
+    }
+}
+
 // ============ Screenshot Counter ============
 
+/// Persisted screenshot counter (stored next to config.json)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct CounterState {
+    last: u64, // last number handed out
+}
+
+/// Serialises counter access within this process (the lock file covers other processes)
+static COUNTER_LOCK: Mutex<()> = Mutex::new(());
+
+/// Get the counter file path
+pub fn get_counter_path() -> PathBuf {
+    get_config_path().with_file_name("counter.json")
+}
+
+/// Last number handed out, without reserving one (share and branding text)
+pub fn last_screenshot_number() -> u64 {
+    with_counter(|state| state.last, false)
+}
+
+/// Reserve the next screenshot number (monotonic, survives file deletion)
+pub fn next_screenshot_number() -> u64 {
+    with_counter(
+        |state| {
+            state.last += 1;
+            state.last
+        },
+        true,
+    )
+}
+
+/// Run `f` on the counter state while holding both the in-process and the
+/// cross-process lock; persist the state afterwards if `write` is set
+fn with_counter<F>(f: F, write: bool) -> u64
+where
+    F: FnOnce(&mut CounterState) -> u64,
+{
+    let _guard = COUNTER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = get_counter_path();
+
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let lock_file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_extension("lock"));
+    let lock_file = match lock_file {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("[config] Failed to open counter lock: {}", e);
+            let mut state = CounterState { last: scan_library_image_count() };
+            return f(&mut state);
+        }
+    };
+    if let Err(e) = lock_file.lock() {
+        eprintln!("[config] Failed to lock counter: {}", e);
+    }
+
+    let mut state = match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str::<CounterState>(&content).unwrap_or_else(|e| {
+            eprintln!("[config] Corrupt counter file, rescanning: {}", e);
+            CounterState { last: scan_library_image_count() }
+        }),
+        Err(_) => {
+            // First run: seed from the library so numbering continues where the scan left off
+            let last = scan_library_image_count();
+            println!("[config] Migrating screenshot counter from library scan: {}", last);
+            let state = CounterState { last };
+            let _ = save_counter(&path, &state);
+            state
+        }
+    };
+
+    let value = f(&mut state);
+    if write {
+        if let Err(e) = save_counter(&path, &state) {
+            eprintln!("[config] Failed to save counter: {}", e);
+        }
+    }
+
+    let _ = lock_file.unlock();
+    value
+}
+
+fn save_counter(path: &Path, state: &CounterState) -> Result<(), String> {
+    let content = serde_json::to_string(state).map_err(|e| e.to_string())?;
+    crate::output::write_file_atomic(path, |tmp| fs::write(tmp, &content).map_err(|e| e.to_string()))
+}
+
 /// Count all image files in lovshot folder (including subfolders)
-pub fn count_screenshots() -> u64 {
-    let output_dir = dirs::picture_dir()
-        .or_else(dirs::home_dir)
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("lovshot");
+/// Only used to seed the persistent counter
+fn scan_library_image_count() -> u64 {
+    let output_dir = crate::output::lovshot_dir();
 
     if !output_dir.exists() {
-        return 1; // First screenshot
+        return 0;
     }
 
-    fn count_images_recursive(dir: &std::path::Path) -> u64 {
+    fn count_images_recursive(dir: &Path) -> u64 {
         let mut count = 0u64;
         if let Ok(entries) = fs::read_dir(dir) {
             for entry in entries.flatten() {
@@ -430,6 +686,5 @@ pub fn count_screenshots() -> u64 {
         count
     }
 
-    // Return current count + 1 (for the new screenshot being saved)
-    count_images_recursive(&output_dir) + 1
+    count_images_recursive(&output_dir)
 }
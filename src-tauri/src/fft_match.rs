@@ -227,9 +227,60 @@ fn compute_strip_diff(
     diff
 }
 
+/// Estimate the translation (dx, dy) such that `a(x, y)` ≈ `b(x + dx, y + dy)`
+///
+/// Correlates the column and row brightness profiles of both images, so it
+/// works for different sizes and is cheap even for full-screen captures.
+pub fn estimate_translation(a: &RgbaImage, b: &RgbaImage, max_shift: u32) -> (i32, i32) {
+    let (wa, ha) = a.dimensions();
+    let (wb, hb) = b.dimensions();
+    let gray_a = to_grayscale(a);
+    let gray_b = to_grayscale(b);
+
+    let dx = estimate_shift_1d(
+        &profile(&gray_a, wa, ha, true),
+        &profile(&gray_b, wb, hb, true),
+        max_shift as usize,
+    );
+    let dy = estimate_shift_1d(
+        &profile(&gray_a, wa, ha, false),
+        &profile(&gray_b, wb, hb, false),
+        max_shift as usize,
+    );
+    (dx, dy)
+}
+
+/// Mean brightness per column (`columns`) or per row, minus the overall mean
+fn profile(gray: &[f32], width: u32, height: u32, columns: bool) -> Vec<f32> {
+    let (w, h) = (width as usize, height as usize);
+    let mut values: Vec<f32> = if columns {
+        (0..w).map(|x| (0..h).map(|y| gray[y * w + x]).sum::<f32>() / h as f32).collect()
+    } else {
+        (0..h).map(|y| gray[y * w..(y + 1) * w].iter().sum::<f32>() / w as f32).collect()
+    };
+    let mean = values.iter().sum::<f32>() / values.len().max(1) as f32;
+    values.iter_mut().for_each(|v| *v -= mean);
+    values
+}
+
+/// Shift of `a` within `b` (a[i] ≈ b[i + shift]), searched within ±max_shift
+fn estimate_shift_1d(a: &[f32], b: &[f32], max_shift: usize) -> i32 {
+    // Template is the middle of `a`, trimmed so every candidate shift stays inside it
+    let max_shift = max_shift.min(a.len() / 4);
+    let template_len = a.len() - 2 * max_shift;
+    if template_len == 0 || template_len > b.len() {
+        return 0;
+    }
+    let template = &a[max_shift..max_shift + template_len];
+
+    // Only search where the template can land for |shift| <= max_shift
+    let search = &b[..(max_shift * 2 + template_len).min(b.len())];
+    let (offset, _) = ncc_fft_1d(template, search);
+    offset - max_shift as i32
+}
+
 /// FFT-based normalized cross-correlation for a single row
 /// Returns the best offset and correlation score
-#[allow(dead_code)]
 pub fn ncc_fft_1d(template: &[f32], search: &[f32]) -> (i32, f32) {
     let n = search.len();
     let m = template.len();
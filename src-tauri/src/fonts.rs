@@ -0,0 +1,408 @@
+//! Fonts for rendered text (share captions, watermarks)
+//!
+//! Fonts are resolved once into a fallback chain and cached; every glyph is
+//! drawn with the first font in the chain that actually contains it, so CJK
+//! and symbols don't turn into boxes when the primary font lacks them.
+
+use ab_glyph::{point, Font, FontArc, FontVec, GlyphId, PxScale, ScaleFont};
+use image::{Rgba, RgbaImage};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::config;
+
+/// Which built-in chain to append after the user's fonts
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FontRole {
+    Text, // Captions and share templates
+    Mono, // Watermark numbers
+}
+
+/// Built-in text fonts, by file name, in preference order
+#[cfg(target_os = "macos")]
+const TEXT_FONTS: &[&str] = &[
+    "PingFang.ttc",
+    "STHeiti Light.ttc",
+    "Helvetica.ttc",
+    "Arial Unicode.ttf",
+    "Apple Symbols.ttf",
+];
+#[cfg(target_os = "windows")]
+const TEXT_FONTS: &[&str] = &[
+    "msyh.ttc",
+    "segoeui.ttf",
+    "YuGothM.ttc",
+    "malgun.ttf",
+    "seguisym.ttf",
+    "seguiemj.ttf",
+];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const TEXT_FONTS: &[&str] = &[
+    "DejaVuSans.ttf",
+    "NotoSans-Regular.ttf",
+    "LiberationSans-Regular.ttf",
+    "Cantarell-Regular.otf",
+    "NotoSansCJK-Regular.ttc",
+    "NotoSansCJKsc-Regular.otf",
+    "wqy-microhei.ttc",
+    "wqy-zenhei.ttc",
+    "DroidSansFallbackFull.ttf",
+    "NotoEmoji-Regular.ttf",
+    "Symbola.ttf",
+];
+
+/// Built-in monospace fonts, tried before the text chain for FontRole::Mono
+#[cfg(target_os = "macos")]
+const MONO_FONTS: &[&str] = &["Menlo.ttc", "Monaco.ttf"];
+#[cfg(target_os = "windows")]
+const MONO_FONTS: &[&str] = &["consola.ttf", "cour.ttf"];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const MONO_FONTS: &[&str] = &[
+    "DejaVuSansMono.ttf",
+    "LiberationMono-Regular.ttf",
+    "NotoSansMono-Regular.ttf",
+    "UbuntuMono-R.ttf",
+];
+
+/// System font directories searched for font file names
+fn font_dirs() -> Vec<PathBuf> {
+    let home = dirs::home_dir().unwrap_or_default();
+    #[cfg(target_os = "macos")]
+    {
+        vec![
+            PathBuf::from("/System/Library/Fonts"),
+            PathBuf::from("/Library/Fonts"),
+            home.join("Library/Fonts"),
+        ]
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let windir = std::env::var("WINDIR").unwrap_or_else(|_| "C:\\Windows".to_string());
+        vec![
+            PathBuf::from(windir).join("Fonts"),
+            home.join("AppData\\Local\\Microsoft\\Windows\\Fonts"),
+        ]
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        vec![
+            PathBuf::from("/usr/share/fonts"),
+            PathBuf::from("/usr/local/share/fonts"),
+            home.join(".local/share/fonts"),
+            home.join(".fonts"),
+        ]
+    }
+}
+
+/// Lowercased file name -> path for every font file under the system font dirs
+fn index_system_fonts() -> HashMap<String, PathBuf> {
+    fn walk(dir: &Path, depth: u32, index: &mut HashMap<String, PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else { return };
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.is_dir() {
+                if depth < 5 {
+                    walk(&path, depth + 1, index);
+                }
+            } else if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                index.entry(name.to_lowercase()).or_insert(path);
+            }
+        }
+    }
+
+    let mut index = HashMap::new();
+    for dir in font_dirs() {
+        walk(&dir, 0, &mut index);
+    }
+    index
+}
+
+/// Resolve a font entry: an absolute path or a file name, optionally "#<index>" for collections
+fn resolve_font_entry(entry: &str, index: &HashMap<String, PathBuf>) -> Result<(PathBuf, u32), String> {
+    let (name, face) = match entry.rsplit_once('#') {
+        Some((name, i)) if i.parse::<u32>().is_ok() => (name, i.parse::<u32>().unwrap_or(0)),
+        _ => (entry, 0),
+    };
+    let path = if Path::new(name).is_file() {
+        PathBuf::from(name)
+    } else {
+        index
+            .get(&name.to_lowercase())
+            .cloned()
+            .ok_or_else(|| format!("Font not found: {}", name))?
+    };
+    Ok((path, face))
+}
+
+fn read_font(path: &Path, face: u32) -> Result<FontArc, String> {
+    let data = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    FontVec::try_from_vec_and_index(data, face)
+        .map(FontArc::new)
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// A chain entry, read from disk the first time a glyph lookup reaches it
+///
+/// Fallbacks are often multi-MB CJK collections, so they stay unloaded until
+/// some glyph is missing from every font before them.
+struct FontSlot {
+    path: PathBuf,
+    face: u32,
+    font: OnceLock<Option<FontArc>>,
+}
+
+impl FontSlot {
+    fn new(path: PathBuf, face: u32) -> Self {
+        Self { path, face, font: OnceLock::new() }
+    }
+
+    fn get(&self) -> Option<&FontArc> {
+        self.font
+            .get_or_init(|| {
+                read_font(&self.path, self.face)
+                    .map_err(|e| println!("[fonts] Skipping font: {}", e))
+                    .ok()
+            })
+            .as_ref()
+    }
+
+    #[cfg(test)]
+    fn is_loaded(&self) -> bool {
+        self.font.get().is_some()
+    }
+}
+
+/// Ordered list of fonts; each glyph comes from the first font that has it
+pub struct FontChain {
+    slots: Vec<Arc<FontSlot>>,
+    primary: (usize, FontArc), // First loadable font; its metrics set the baseline
+}
+
+impl FontChain {
+    /// None when no slot holds a loadable font
+    fn new(slots: Vec<Arc<FontSlot>>) -> Option<Self> {
+        let primary = slots
+            .iter()
+            .enumerate()
+            .find_map(|(i, slot)| slot.get().map(|f| (i, f.clone())))?;
+        Some(Self { slots, primary })
+    }
+
+    /// Slot index and font for a char; later slots are only loaded on a miss
+    fn font_for(&self, c: char) -> (usize, &FontArc) {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.get().map(|f| (i, f)))
+            .find(|(_, f)| f.glyph_id(c).0 != 0)
+            .unwrap_or((self.primary.0, &self.primary.1))
+    }
+
+    /// Walk the glyphs of a line: (font, glyph id, pen x) for each char
+    fn layout(&self, scale: PxScale, text: &str, mut f: impl FnMut(&FontArc, GlyphId, f32)) -> f32 {
+        let mut x = 0.0f32;
+        let mut prev: Option<(usize, GlyphId)> = None;
+        for c in text.chars() {
+            let (i, font_arc) = self.font_for(c);
+            let font = font_arc.as_scaled(scale);
+            let id = font.glyph_id(c);
+            // Kerning only applies between glyphs of the same font
+            if let Some((prev_i, prev_id)) = prev {
+                if prev_i == i {
+                    x += font.kern(prev_id, id);
+                }
+            }
+            f(font_arc, id, x);
+            x += font.h_advance(id);
+            prev = Some((i, id));
+        }
+        x
+    }
+
+    /// Advance width of a single line in pixels
+    pub fn text_width(&self, scale: PxScale, text: &str) -> f32 {
+        self.layout(scale, text, |_, _, _| {})
+    }
+
+    /// Draw one line with its top-left corner at (x, y)
+    ///
+    /// Blends like imageproc's draw_text_mut (colour and alpha weighted by
+    /// coverage), so drawing into a transparent layer yields straight alpha.
+    pub fn draw_text(&self, img: &mut RgbaImage, color: Rgba<u8>, x: i32, y: i32, scale: PxScale, text: &str) {
+        // Shared baseline from the primary font so fallback glyphs line up
+        let baseline = self.primary.1.as_scaled(scale).ascent();
+        let (img_w, img_h) = (img.width() as i32, img.height() as i32);
+
+        self.layout(scale, text, |font, id, pen_x| {
+            let glyph = id.with_scale_and_position(scale, point(pen_x, baseline));
+            let Some(outline) = font.outline_glyph(glyph) else { return };
+            let bounds = outline.px_bounds();
+            outline.draw(|gx, gy, coverage| {
+                let px = x + bounds.min.x.round() as i32 + gx as i32;
+                let py = y + bounds.min.y.round() as i32 + gy as i32;
+                if px < 0 || py < 0 || px >= img_w || py >= img_h {
+                    return;
+                }
+                let coverage = coverage.clamp(0.0, 1.0);
+                let p = img.get_pixel_mut(px as u32, py as u32);
+                for c in 0..4 {
+                    let v = p[c] as f32 * (1.0 - coverage) + color[c] as f32 * coverage;
+                    p[c] = v.round().clamp(0.0, 255.0) as u8;
+                }
+            });
+        });
+    }
+}
+
+/// Chains built for one configured font list
+struct ChainCache {
+    user_fonts: Option<Vec<String>>,
+    text: Option<Arc<FontChain>>,
+    mono: Option<Arc<FontChain>>,
+}
+
+impl ChainCache {
+    const fn new() -> Self {
+        Self { user_fonts: None, text: None, mono: None }
+    }
+
+    /// Chain for a role, rebuilt only when the configured font list changed
+    fn get(&mut self, user_fonts: &[String], role: FontRole) -> Option<Arc<FontChain>> {
+        if self.user_fonts.as_deref() != Some(user_fonts) {
+            let index = index_system_fonts();
+            let user: Vec<&str> = user_fonts.iter().map(String::as_str).collect();
+            let text: Vec<&str> = user.iter().chain(TEXT_FONTS).copied().collect();
+            let mono: Vec<&str> = user.iter().chain(MONO_FONTS).chain(TEXT_FONTS).copied().collect();
+
+            // Chains share slots, so each file is read at most once
+            let mut slots: HashMap<&str, Arc<FontSlot>> = HashMap::new();
+            for entry in mono.iter().chain(text.iter()) {
+                if slots.contains_key(entry) {
+                    continue;
+                }
+                match resolve_font_entry(entry, &index) {
+                    Ok((path, face)) => {
+                        slots.insert(entry, Arc::new(FontSlot::new(path, face)));
+                    }
+                    Err(e) if user.contains(entry) => println!("[fonts] Skipping configured font: {}", e),
+                    Err(_) => {}
+                }
+            }
+            let build = |entries: &[&str]| {
+                FontChain::new(entries.iter().filter_map(|e| slots.get(e).cloned()).collect()).map(Arc::new)
+            };
+
+            self.text = build(&text);
+            self.mono = build(&mono);
+            self.user_fonts = Some(user_fonts.to_vec());
+            println!(
+                "[fonts] Built chains: text={} mono={} fonts (fallbacks load on demand)",
+                self.text.as_ref().map(|c| c.slots.len()).unwrap_or(0),
+                self.mono.as_ref().map(|c| c.slots.len()).unwrap_or(0)
+            );
+        }
+
+        match role {
+            FontRole::Text => self.text.clone(),
+            FontRole::Mono => self.mono.clone(),
+        }
+    }
+}
+
+static CHAINS: Mutex<ChainCache> = Mutex::new(ChainCache::new());
+
+/// Font chain for a role: configured fonts first, then the built-in fallbacks
+///
+/// Built once and reused until the configured font list changes.
+pub fn font_chain(role: FontRole) -> Option<Arc<FontChain>> {
+    let user_fonts = config::load_config().fonts;
+    // A panic while building must not break every later render
+    let mut cache = CHAINS.lock().unwrap_or_else(|e| e.into_inner());
+    cache.get(&user_fonts, role)
+}
+
+/// Check that every entry resolves to a loadable font
+pub fn validate_font_entries(entries: &[String]) -> Result<(), String> {
+    let index = index_system_fonts();
+    let errors: Vec<String> = entries
+        .iter()
+        .filter_map(|entry| {
+            resolve_font_entry(entry, &index)
+                .and_then(|(path, face)| read_font(&path, face))
+                .err()
+        })
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// First loadable built-in mono and text fonts, if this machine has both
+    fn system_font_pair() -> Option<(PathBuf, PathBuf)> {
+        let index = index_system_fonts();
+        let first = |names: &[&str]| {
+            names.iter().find_map(|name| {
+                let (path, face) = resolve_font_entry(name, &index).ok()?;
+                read_font(&path, face).ok().map(|_| path)
+            })
+        };
+        Some((first(MONO_FONTS)?, first(TEXT_FONTS)?))
+    }
+
+    #[test]
+    fn test_missing_glyph_falls_back_lazily() {
+        let Some((mono, text)) = system_font_pair() else {
+            println!("no system fonts, skipping");
+            return;
+        };
+        let first = read_font(&mono, 0).unwrap();
+        let second = read_font(&text, 0).unwrap();
+        // Some char the second font has and the first lacks
+        let Some(missing) = (0x20u32..0x3_0000)
+            .filter_map(char::from_u32)
+            .find(|&c| first.glyph_id(c).0 == 0 && second.glyph_id(c).0 != 0)
+        else {
+            println!("fonts cover the same chars, skipping");
+            return;
+        };
+
+        let chain = FontChain::new(vec![
+            Arc::new(FontSlot::new(mono, 0)),
+            Arc::new(FontSlot::new(text, 0)),
+        ])
+        .unwrap();
+        assert_eq!(chain.font_for('a').0, 0);
+        assert!(!chain.slots[1].is_loaded(), "fallback loaded before any miss");
+
+        assert_eq!(chain.font_for(missing).0, 1);
+        assert!(chain.slots[1].is_loaded());
+    }
+
+    #[test]
+    fn test_cache_rebuilds_when_font_list_changes() {
+        let Some((mono, _)) = system_font_pair() else {
+            println!("no system fonts, skipping");
+            return;
+        };
+        let mut cache = ChainCache::new();
+        let a = cache.get(&[], FontRole::Text).unwrap();
+        let b = cache.get(&[], FontRole::Text).unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+
+        // A configured font becomes the primary of the rebuilt chain
+        let user = vec![mono.to_string_lossy().to_string()];
+        let c = cache.get(&user, FontRole::Text).unwrap();
+        assert!(!Arc::ptr_eq(&a, &c));
+        assert_eq!(c.slots[c.primary.0].path, mono);
+
+        let d = cache.get(&[], FontRole::Text).unwrap();
+        assert!(!Arc::ptr_eq(&c, &d));
+        assert_ne!(d.slots[d.primary.0].path, mono);
+    }
+}
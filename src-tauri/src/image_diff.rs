@@ -0,0 +1,289 @@
+//! Visual diff between two captures
+//!
+//! The second image is aligned to the first (same size, or shifted by the
+//! translation that fft_match finds), changed pixels are highlighted on a
+//! faded copy of the first, and nearby changes are grouped into boxes.
+
+use image::{Rgba, RgbaImage};
+use imageproc::drawing::draw_hollow_rect_mut;
+use imageproc::rect::Rect;
+use serde::{Deserialize, Serialize};
+
+use crate::fft_match::estimate_translation;
+use crate::output::{build_output_path, save_image_unique, FilenameContext, OutputKind};
+use crate::share_compose::ACCENT;
+use crate::types::Region;
+
+/// Changes closer than this many pixels end up in the same box
+const CELL: u32 = 8;
+const CHANGED: Rgba<u8> = Rgba([217, 83, 79, 255]);
+const MISSING: Rgba<u8> = Rgba([190, 190, 190, 255]);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiffOptions {
+    #[serde(default = "default_threshold")]
+    pub threshold: u8, // Per-channel difference that counts as a change
+    #[serde(default = "default_align")]
+    pub align: bool, // Search for a translation before comparing
+    #[serde(default = "default_max_shift")]
+    pub max_shift: u32,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            threshold: default_threshold(),
+            align: default_align(),
+            max_shift: default_max_shift(),
+        }
+    }
+}
+
+fn default_threshold() -> u8 {
+    24
+}
+
+fn default_align() -> bool {
+    true
+}
+
+fn default_max_shift() -> u32 {
+    64
+}
+
+#[derive(Clone, Serialize)]
+pub struct DiffResult {
+    pub diff_path: String,
+    pub changed_percent: f32, // Of the overlapping area
+    pub changed_pixels: u64,
+    pub offset_x: i32, // Where `before` (x, y) is found in `after`: (x + offset_x, y + offset_y)
+    pub offset_y: i32,
+    pub boxes: Vec<Region>, // In `before` pixel coordinates
+}
+
+/// Diff image plus stats, before saving
+pub struct ImageDiff {
+    pub image: RgbaImage,
+    pub changed_pixels: u64,
+    pub changed_percent: f32,
+    pub offset: (i32, i32),
+    pub boxes: Vec<Region>,
+}
+
+/// Overlap of `a` with `b` shifted by (dx, dy), as a range in `a` coordinates
+fn overlap(a: &RgbaImage, b: &RgbaImage, (dx, dy): (i32, i32)) -> (u32, u32, u32, u32) {
+    let x0 = (-dx).max(0) as u32;
+    let y0 = (-dy).max(0) as u32;
+    let x1 = (b.width() as i32 - dx).clamp(0, a.width() as i32) as u32;
+    let y1 = (b.height() as i32 - dy).clamp(0, a.height() as i32) as u32;
+    (x0, y0, x1.max(x0), y1.max(y0))
+}
+
+fn pixel_diff(p: &Rgba<u8>, q: &Rgba<u8>) -> u8 {
+    (0..4).map(|c| p[c].abs_diff(q[c])).max().unwrap_or(0)
+}
+
+/// Mean difference over a sparse sample of the overlap (lower = better aligned)
+fn alignment_cost(a: &RgbaImage, b: &RgbaImage, offset: (i32, i32)) -> f32 {
+    let (x0, y0, x1, y1) = overlap(a, b, offset);
+    let mut sum = 0u64;
+    let mut count = 0u64;
+    for y in (y0..y1).step_by(3) {
+        for x in (x0..x1).step_by(3) {
+            let q = b.get_pixel((x as i32 + offset.0) as u32, (y as i32 + offset.1) as u32);
+            sum += pixel_diff(a.get_pixel(x, y), q) as u64;
+            count += 1;
+        }
+    }
+    if count == 0 {
+        f32::MAX
+    } else {
+        sum as f32 / count as f32
+    }
+}
+
+/// Group changed pixels into boxes via connected components on a coarse grid
+fn changed_boxes(changed: &[bool], width: u32, height: u32) -> Vec<Region> {
+    let (cols, rows) = (width.div_ceil(CELL) as usize, height.div_ceil(CELL) as usize);
+    let mut cells = vec![false; cols * rows];
+    for y in 0..height as usize {
+        for x in 0..width as usize {
+            if changed[y * width as usize + x] {
+                cells[(y / CELL as usize) * cols + x / CELL as usize] = true;
+            }
+        }
+    }
+
+    let mut seen = vec![false; cells.len()];
+    let mut boxes = Vec::new();
+    for start in 0..cells.len() {
+        if !cells[start] || seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut stack = vec![start];
+        let (mut min_c, mut min_r, mut max_c, mut max_r) = (usize::MAX, usize::MAX, 0, 0);
+        while let Some(i) = stack.pop() {
+            let (c, r) = (i % cols, i / cols);
+            min_c = min_c.min(c);
+            min_r = min_r.min(r);
+            max_c = max_c.max(c);
+            max_r = max_r.max(r);
+            // 8-connected so diagonal edits stay in one box
+            for nr in r.saturating_sub(1)..=(r + 1).min(rows - 1) {
+                for nc in c.saturating_sub(1)..=(c + 1).min(cols - 1) {
+                    let n = nr * cols + nc;
+                    if cells[n] && !seen[n] {
+                        seen[n] = true;
+                        stack.push(n);
+                    }
+                }
+            }
+        }
+
+        // Tighten the cell box to the changed pixels inside it
+        let (cx0, cy0) = (min_c as u32 * CELL, min_r as u32 * CELL);
+        let (cx1, cy1) = (((max_c + 1) as u32 * CELL).min(width), ((max_r + 1) as u32 * CELL).min(height));
+        let (mut x0, mut y0, mut x1, mut y1) = (u32::MAX, u32::MAX, 0, 0);
+        for y in cy0..cy1 {
+            for x in cx0..cx1 {
+                if changed[(y * width + x) as usize] {
+                    x0 = x0.min(x);
+                    y0 = y0.min(y);
+                    x1 = x1.max(x + 1);
+                    y1 = y1.max(y + 1);
+                }
+            }
+        }
+        boxes.push(Region { x: x0 as i32, y: y0 as i32, width: x1 - x0, height: y1 - y0 });
+    }
+    boxes
+}
+
+/// Compare `after` against `before`; the diff image has `before`'s size
+pub fn diff_images(before: &RgbaImage, after: &RgbaImage, options: &DiffOptions) -> ImageDiff {
+    let (w, h) = before.dimensions();
+
+    // Keep the found shift only if it actually lines the images up better
+    let mut offset = (0, 0);
+    if options.align {
+        let found = estimate_translation(before, after, options.max_shift);
+        if found != (0, 0) && alignment_cost(before, after, found) < alignment_cost(before, after, (0, 0)) {
+            offset = found;
+        }
+    }
+
+    let (x0, y0, x1, y1) = overlap(before, after, offset);
+    let mut changed = vec![false; (w * h) as usize];
+    let mut changed_pixels = 0u64;
+    let mut image = RgbaImage::from_pixel(w, h, MISSING);
+
+    for y in 0..h {
+        for x in 0..w {
+            let p = before.get_pixel(x, y);
+            if x < x0 || x >= x1 || y < y0 || y >= y1 {
+                continue; // No counterpart in `after`; left grey
+            }
+            let q = after.get_pixel((x as i32 + offset.0) as u32, (y as i32 + offset.1) as u32);
+            if pixel_diff(p, q) > options.threshold {
+                changed[(y * w + x) as usize] = true;
+                changed_pixels += 1;
+                image.put_pixel(x, y, CHANGED);
+            } else {
+                // Faded greyscale context so the highlights stand out
+                let luma = 0.299 * p[0] as f32 + 0.587 * p[1] as f32 + 0.114 * p[2] as f32;
+                let v = (255.0 - (255.0 - luma) * 0.35) as u8;
+                image.put_pixel(x, y, Rgba([v, v, v, 255]));
+            }
+        }
+    }
+
+    let boxes = changed_boxes(&changed, w, h);
+    for b in &boxes {
+        // Two-pixel outline just outside the changed area
+        for inset in 0..2 {
+            let rect = Rect::at(b.x - 2 + inset, b.y - 2 + inset)
+                .of_size(b.width + 4 - inset as u32 * 2, b.height + 4 - inset as u32 * 2);
+            draw_hollow_rect_mut(&mut image, rect, ACCENT);
+        }
+    }
+
+    let area = (x1 - x0) as u64 * (y1 - y0) as u64;
+    let changed_percent = if area == 0 {
+        100.0
+    } else {
+        (changed_pixels as f64 / area as f64 * 10000.0).round() as f32 / 100.0
+    };
+    ImageDiff { image, changed_pixels, changed_percent, offset, boxes }
+}
+
+/// Tauri command: diff two library images and save the highlighted result
+#[tauri::command(rename_all = "camelCase")]
+pub fn diff_captures(
+    before_path: String,
+    after_path: String,
+    options: Option<DiffOptions>,
+) -> Result<DiffResult, String> {
+    let open = |path: &str| {
+        image::open(path)
+            .map(|img| img.to_rgba8())
+            .map_err(|e| format!("Failed to open {}: {}", path, e))
+    };
+    let before = open(&before_path)?;
+    let after = open(&after_path)?;
+    let options = options.unwrap_or_default();
+
+    let diff = diff_images(&before, &after, &options);
+    println!(
+        "[diff] offset {:?}, {} changed pixels ({}%), {} boxes",
+        diff.offset,
+        diff.changed_pixels,
+        diff.changed_percent,
+        diff.boxes.len()
+    );
+
+    let path = build_output_path(OutputKind::Share, FilenameContext::new("diff"), "png")?;
+    let path = save_image_unique(&diff.image, &path)?;
+    Ok(DiffResult {
+        diff_path: path.to_string_lossy().to_string(),
+        changed_percent: diff.changed_percent,
+        changed_pixels: diff.changed_pixels,
+        offset_x: diff.offset.0,
+        offset_y: diff.offset.1,
+        boxes: diff.boxes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Blocky pseudo-random UI-like texture
+    fn texture(w: u32, h: u32) -> RgbaImage {
+        RgbaImage::from_fn(w, h, |x, y| {
+            let cell = (x / 12).wrapping_mul(73_856_093) ^ (y / 9).wrapping_mul(19_349_663);
+            let v = (cell.wrapping_mul(2_654_435_761) >> 24) as u8;
+            Rgba([v, v / 2, 255 - v, 255])
+        })
+    }
+
+    #[test]
+    fn test_diff_finds_shift_and_change() {
+        let base = texture(300, 200);
+        let before = image::imageops::crop_imm(&base, 10, 10, 260, 160).to_image();
+        // `after` is the same content moved by (6, 4), with one edited block
+        let mut after = image::imageops::crop_imm(&base, 4, 6, 260, 160).to_image();
+        for y in 60..80 {
+            for x in 100..130 {
+                after.put_pixel(x, y, Rgba([0, 255, 0, 255]));
+            }
+        }
+
+        let diff = diff_images(&before, &after, &DiffOptions::default());
+        assert_eq!(diff.offset, (6, 4));
+        assert_eq!(diff.boxes.len(), 1);
+        let b = &diff.boxes[0];
+        assert_eq!((b.x, b.y, b.width, b.height), (94, 56, 30, 20));
+        assert!(diff.changed_percent > 0.0 && diff.changed_percent < 2.0);
+    }
+}
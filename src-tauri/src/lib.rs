@@ -14,21 +14,37 @@ mod scroll_event;
 #[cfg(target_os = "macos")]
 mod window_detect;
 
+mod animation;
+mod annotate;
+mod annotation_layers;
 mod capture;
+mod caption;
+mod collage;
+mod color;
 mod commands;
 mod config;
 mod fft_match;
+mod fonts;
+mod image_diff;
+mod mockup;
+mod output;
+mod palette;
 mod row_hash;
 mod permission;
+mod presets;
 mod share_compose;
+mod share_template;
 mod shortcuts;
 mod state;
+mod text_layout;
 mod tray;
 mod types;
+mod watermark;
+mod window_capture;
 mod windows;
 
 use commands::open_selector_internal;
-use shortcuts::{get_action_for_shortcut, is_show_main_shortcut, is_stop_recording_shortcut, register_shortcuts_from_config, unregister_stop_shortcuts, unregister_stop_scroll_shortcuts};
+use shortcuts::{get_action_for_shortcut, is_delayed_screenshot_shortcut, is_repeat_last_shortcut, preset_for_shortcut, is_show_main_shortcut, is_stop_recording_shortcut, register_shortcuts_from_config, unregister_stop_shortcuts, unregister_stop_scroll_shortcuts};
 use state::{AppState, SharedState};
 use tray::{build_tray_menu, load_tray_icon};
 pub use types::*;
@@ -125,6 +141,15 @@ pub fn run() {
 
                     // Check if this is a stop/cancel shortcut (ESC, etc.)
                     if is_stop_recording_shortcut(shortcut) {
+                        // Cancel a delayed capture; its thread unregisters the stop shortcuts
+                        {
+                            let mut s = state_for_shortcut.lock().unwrap();
+                            if s.countdown_active {
+                                println!("[DEBUG][shortcut] 取消延时截图");
+                                s.countdown_active = false;
+                                return;
+                            }
+                        }
                         // Close selector window if open
                         if let Some(selector_win) = app.get_webview_window("selector") {
                             if selector_win.is_visible().unwrap_or(false) {
@@ -146,6 +171,43 @@ pub fn run() {
                         return;
                     }
 
+                    if is_delayed_screenshot_shortcut(shortcut) {
+                        // Region is chosen in the selector first; the countdown starts on confirm
+                        println!("[DEBUG][shortcut] 延时截图");
+                        let already_open = app.get_webview_window("selector").is_some();
+                        if !already_open {
+                            let mut s = state_for_shortcut.lock().unwrap();
+                            s.pending_mode = Some(CaptureMode::Image);
+                            s.pending_delay = Some(config::load_config().capture_delay_secs);
+                        }
+                        let opened = open_selector_internal(app.clone());
+                        if let Err(e) = &opened {
+                            println!("[DEBUG][shortcut] 延时截图打开选区失败: {}", e);
+                        }
+                        // No selector came up (error or missing permission): drop the delay
+                        // so it can't leak into the next ordinary selection
+                        if !already_open && (opened.is_err() || app.get_webview_window("selector").is_none()) {
+                            state_for_shortcut.lock().unwrap().pending_delay = None;
+                        }
+                        return;
+                    }
+
+                    if is_repeat_last_shortcut(shortcut) {
+                        println!("[DEBUG][shortcut] 重复上次截取");
+                        if let Err(e) = commands::repeat_last_capture(app.clone()) {
+                            println!("[DEBUG][shortcut] 重复上次截取失败: {}", e);
+                        }
+                        return;
+                    }
+
+                    if let Some(id) = preset_for_shortcut(shortcut) {
+                        println!("[DEBUG][shortcut] 区域预设: {}", id);
+                        if let Err(e) = commands::capture_preset(app.clone(), id) {
+                            println!("[DEBUG][shortcut] 区域预设截取失败: {}", e);
+                        }
+                        return;
+                    }
+
                     if let Some(mode) = get_action_for_shortcut(shortcut) {
                         println!("[DEBUG][shortcut] {:?} triggered -> {:?}", shortcut, mode);
                         state_for_shortcut.lock().unwrap().pending_mode = Some(mode);
@@ -167,6 +229,11 @@ pub fn run() {
             commands::get_screen_snapshot,
             commands::get_magnifier_snapshot,
             commands::get_magnifier_pixels,
+            commands::pick_color,
+            commands::get_color_history,
+            commands::clear_color_history,
+            color::convert_color,
+            color::color_contrast,
             commands::clear_pending_mode,
             commands::capture_screen_now,
             commands::clear_screen_background,
@@ -187,8 +254,15 @@ pub fn run() {
             commands::get_screenshot_count,
             commands::get_watermark_position,
             commands::set_watermark_position,
+            commands::get_watermark_config,
+            commands::set_watermark_config,
+            commands::get_fonts,
+            commands::set_fonts,
             commands::get_show_caption_editor,
             commands::set_show_caption_editor,
+            commands::get_output_dir,
+            commands::set_output_dir,
+            commands::set_filename_template,
             commands::start_recording,
             commands::stop_recording,
             commands::get_recording_info,
@@ -198,6 +272,23 @@ pub fn run() {
             commands::get_frame_thumbnail,
             commands::get_filmstrip,
             commands::save_screenshot,
+            commands::save_desktop_screenshot,
+            commands::repeat_last_capture,
+            commands::capture_preset,
+            commands::start_delayed_capture,
+            commands::cancel_delayed_capture,
+            commands::take_pending_delay,
+            commands::get_region_presets,
+            commands::save_region_preset,
+            commands::remove_region_preset,
+            commands::get_aspect_ratio_lock,
+            commands::set_aspect_ratio_lock,
+            commands::set_capture_delay,
+            commands::set_timelapse_defaults,
+            commands::start_timelapse,
+            commands::stop_timelapse,
+            commands::is_timelapse_active,
+            commands::export_timelapse,
             commands::save_caption,
             commands::get_image_description,
             commands::open_file,
@@ -236,8 +327,20 @@ pub fn run() {
             // Annotation commands
             commands::capture_region_preview,
             commands::save_annotated_screenshot,
+            commands::render_annotated_screenshot,
+            annotation_layers::get_annotation_layers,
+            annotation_layers::update_annotations,
             // Share compose
             share_compose::compose_share,
+            share_compose::beautify_share,
+            mockup::mockup_share,
+            collage::compose_collage,
+            palette::extract_palette_colors,
+            image_diff::diff_captures,
+            window_capture::list_capturable_windows,
+            window_capture::capture_window,
+            share_compose::list_share_templates,
+            share_compose::preview_share_template,
             // Debug commands
             commands::open_devtools,
             commands::set_selector_mouse_passthrough,
@@ -326,6 +429,29 @@ pub fn run() {
                         state_for_menu.lock().unwrap().pending_mode = Some(CaptureMode::Video);
                         let _ = open_selector_internal(app.clone());
                     }
+                    "delayed_screenshot" => {
+                        // From the tray there is no region to pick, so the whole screen is used
+                        if let Err(e) = commands::start_delayed_capture(app.clone(), None, None) {
+                            println!("[tray] Delayed capture failed: {}", e);
+                        }
+                    }
+                    "timelapse" => {
+                        // Toggles: stop a running timelapse, else start one on the last region
+                        let mut s = state_for_menu.lock().unwrap();
+                        if s.timelapse_active {
+                            s.timelapse_active = false;
+                        } else {
+                            drop(s);
+                            if let Err(e) = commands::start_timelapse(app.clone(), None, None, None) {
+                                println!("[tray] Timelapse failed: {}", e);
+                            }
+                        }
+                    }
+                    "repeat_last" => {
+                        if let Err(e) = commands::repeat_last_capture(app.clone()) {
+                            println!("[tray] Repeat last capture failed: {}", e);
+                        }
+                    }
                     "settings" => {
                         let _ = open_settings_window(app.clone());
                     }
@@ -335,6 +461,12 @@ pub fn run() {
                     "quit" => {
                         app.exit(0);
                     }
+                    id if id.starts_with(presets::PRESET_ACTION_PREFIX) => {
+                        let preset_id = id.trim_start_matches(presets::PRESET_ACTION_PREFIX).to_string();
+                        if let Err(e) = commands::capture_preset(app.clone(), preset_id) {
+                            println!("[tray] Preset capture failed: {}", e);
+                        }
+                    }
                     _ => {}
                 })
                 .menu_on_left_click(true)
@@ -0,0 +1,351 @@
+//! Device and browser mockup frames
+//!
+//! Frames are drawn procedurally (no bundled assets) and scale with the
+//! screenshot, then placed on a background via the share_compose primitives.
+
+use ab_glyph::PxScale;
+use image::imageops::FilterType;
+use image::{Rgba, RgbaImage};
+
+use crate::fonts::{font_chain, FontRole};
+use crate::share_compose::{
+    deliver_share, draw_shape_shadow, fill_background, fill_circle_aa, fill_rounded_rect, round_corners,
+};
+use crate::share_template::{Background, ShadowSpec};
+use crate::text_layout::truncate_with_ellipsis;
+use crate::watermark::blend_layer;
+
+/// Frame styles
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MockupFrame {
+    Browser,
+    Phone,
+    Laptop,
+}
+
+/// Options for mockup rendering
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct MockupOptions {
+    pub frame: MockupFrame,
+    #[serde(default)]
+    pub url: Option<String>, // Browser URL bar text
+    #[serde(default)]
+    pub dark: bool, // Dark browser chrome / space-black devices
+    #[serde(default = "default_mockup_background")]
+    pub background: Background,
+    #[serde(default = "default_mockup_padding")]
+    pub padding: u32,
+    #[serde(default = "default_mockup_shadow")]
+    pub shadow: Option<ShadowSpec>,
+    #[serde(default)]
+    pub crop: bool, // Phone / laptop: fill the screen and crop from the top instead of letterboxing
+}
+
+fn default_mockup_background() -> Background {
+    Background::Gradient {
+        from: "#F0EEE6".to_string(),
+        to: "#D4A27F".to_string(),
+        angle: 90.0,
+    }
+}
+
+fn default_mockup_padding() -> u32 {
+    80
+}
+
+fn default_mockup_shadow() -> Option<ShadowSpec> {
+    Some(ShadowSpec {
+        blur: 32.0,
+        offset_y: 16,
+        ..ShadowSpec::default()
+    })
+}
+
+/// Render the screenshot inside the chosen frame, on the configured background
+pub fn render_mockup(source: &RgbaImage, options: &MockupOptions) -> RgbaImage {
+    let framed = match options.frame {
+        MockupFrame::Browser => browser_frame(source, options.url.as_deref(), options.dark),
+        MockupFrame::Phone => phone_frame(source, options.dark, options.crop),
+        MockupFrame::Laptop => laptop_frame(source, options.dark, options.crop),
+    };
+
+    let (w, h) = framed.dimensions();
+    let padding = options.padding;
+    let mut canvas = RgbaImage::new(w + padding * 2, h + padding * 2);
+    fill_background(&mut canvas, &options.background);
+    if let Some(ref shadow) = options.shadow {
+        draw_shape_shadow(&mut canvas, &framed, padding as i64, padding as i64, shadow);
+    }
+    blend_layer(&mut canvas, &framed, padding as i64, padding as i64, 1.0);
+    canvas
+}
+
+/// Letterbox colour of device screens
+const SCREEN_BACKGROUND: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+/// The screenshot sized to a device screen: whole and centred, or cropped to fill it
+fn fit_screen(source: &RgbaImage, w: u32, h: u32, crop: bool) -> RgbaImage {
+    if crop {
+        return cover_top(source, w, h);
+    }
+    let (src_w, src_h) = source.dimensions();
+    let scale = (w as f32 / src_w as f32).min(h as f32 / src_h as f32);
+    let scaled_w = ((src_w as f32 * scale).round() as u32).clamp(1, w);
+    let scaled_h = ((src_h as f32 * scale).round() as u32).clamp(1, h);
+    let scaled = image::imageops::resize(source, scaled_w, scaled_h, FilterType::Lanczos3);
+    let mut screen = RgbaImage::from_pixel(w, h, SCREEN_BACKGROUND);
+    image::imageops::overlay(&mut screen, &scaled, ((w - scaled_w) / 2) as i64, ((h - scaled_h) / 2) as i64);
+    screen
+}
+
+/// Scale to cover (w, h), cropping horizontally centred and from the top
+fn cover_top(source: &RgbaImage, w: u32, h: u32) -> RgbaImage {
+    let (src_w, src_h) = source.dimensions();
+    let scale = (w as f32 / src_w as f32).max(h as f32 / src_h as f32);
+    let scaled_w = ((src_w as f32 * scale).ceil() as u32).max(w);
+    let scaled_h = ((src_h as f32 * scale).ceil() as u32).max(h);
+    let scaled = image::imageops::resize(source, scaled_w, scaled_h, FilterType::Lanczos3);
+    image::imageops::crop_imm(&scaled, (scaled_w - w) / 2, 0, w, h).to_image()
+}
+
+/// Browser window: traffic lights and a URL bar above the page
+fn browser_frame(source: &RgbaImage, url: Option<&str>, dark: bool) -> RgbaImage {
+    let (src_w, src_h) = source.dimensions();
+    let bar_h = (src_w as f32 * 0.045).clamp(40.0, 96.0) as u32;
+    let radius = bar_h as f32 * 0.3;
+    let (bar_color, field_color, text_color, line_color) = if dark {
+        (Rgba([44, 44, 46, 255]), Rgba([28, 28, 30, 255]), Rgba([174, 174, 178, 255]), Rgba([20, 20, 20, 255]))
+    } else {
+        (Rgba([236, 234, 231, 255]), Rgba([255, 255, 255, 255]), Rgba([99, 99, 102, 255]), Rgba([210, 208, 204, 255]))
+    };
+
+    let mut frame = RgbaImage::from_pixel(src_w, src_h + bar_h, bar_color);
+    image::imageops::overlay(&mut frame, source, 0, bar_h as i64);
+    for x in 0..src_w {
+        frame.put_pixel(x, bar_h - 1, line_color);
+    }
+
+    // Traffic lights
+    let r = bar_h as f32 * 0.14;
+    let cy = bar_h as f32 / 2.0;
+    let lights = [Rgba([255, 95, 87, 255]), Rgba([254, 188, 46, 255]), Rgba([40, 200, 64, 255])];
+    for (i, color) in lights.iter().enumerate() {
+        fill_circle_aa(&mut frame, bar_h as f32 * 0.45 + i as f32 * r * 3.2, cy, r, *color);
+    }
+
+    // URL field, centred between the lights and the right edge
+    let field_x = (bar_h as f32 * 0.45 + r * 9.0) as u32;
+    let field_w = src_w.saturating_sub(field_x * 2);
+    let field_h = (bar_h as f32 * 0.62) as u32;
+    let field_y = (bar_h - field_h) / 2;
+    fill_rounded_rect(&mut frame, field_x as i64, field_y as i64, field_w, field_h, field_h as f32 / 2.0, field_color);
+
+    if let (Some(url), Some(font)) = (url.map(str::trim).filter(|u| !u.is_empty()), font_chain(FontRole::Text)) {
+        let scale = PxScale::from(field_h as f32 * 0.55);
+        let text_x = field_x + field_h / 2;
+        let max_w = field_w.saturating_sub(field_h);
+        let url = truncate_with_ellipsis(&font, scale, url, max_w);
+        let text_y = field_y as f32 + (field_h as f32 - scale.y) / 2.0;
+        font.draw_text(&mut frame, text_color, text_x as i32, text_y as i32, scale, &url);
+    }
+
+    round_corners(&mut frame, radius as u32);
+    frame
+}
+
+/// Phone: rounded body, thin bezel, dynamic-island cutout
+fn phone_frame(source: &RgbaImage, dark: bool, crop: bool) -> RgbaImage {
+    let screen_w = source.width().clamp(320, 1290);
+    let screen_h = (screen_w as f32 * 19.5 / 9.0) as u32;
+    let bezel = (screen_w as f32 * 0.045) as u32;
+    let body_w = screen_w + bezel * 2;
+    let body_h = screen_h + bezel * 2;
+    let body_radius = screen_w as f32 * 0.15;
+    let body_color = if dark { Rgba([28, 28, 30, 255]) } else { Rgba([52, 52, 56, 255]) };
+    let edge_color = if dark { Rgba([72, 72, 76, 255]) } else { Rgba([190, 190, 195, 255]) };
+
+    // Side buttons stick out a little past the body
+    let button_w = (bezel / 3).max(2);
+    let mut frame = RgbaImage::new(body_w + button_w * 2, body_h);
+    let body_x = button_w as i64;
+    let unit = screen_w as f32 / 100.0;
+    for (side_x, top, len) in [
+        (0i64, 22.0, 7.0),            // Action button
+        (0, 34.0, 13.0),              // Volume up
+        (0, 50.0, 13.0),              // Volume down
+        ((body_w + button_w) as i64, 38.0, 20.0), // Power
+    ] {
+        fill_rounded_rect(
+            &mut frame, side_x, (top * unit) as i64, button_w, (len * unit) as u32,
+            button_w as f32 / 2.0, edge_color,
+        );
+    }
+
+    fill_rounded_rect(&mut frame, body_x, 0, body_w, body_h, body_radius, edge_color);
+    let rim = (bezel / 6).max(1);
+    fill_rounded_rect(
+        &mut frame, body_x + rim as i64, rim as i64, body_w - rim * 2, body_h - rim * 2,
+        body_radius - rim as f32, body_color,
+    );
+
+    let mut screen = fit_screen(source, screen_w, screen_h, crop);
+    round_corners(&mut screen, (body_radius - bezel as f32).max(0.0) as u32);
+    blend_layer(&mut frame, &screen, body_x + bezel as i64, bezel as i64, 1.0);
+
+    // Dynamic island
+    let island_w = (screen_w as f32 * 0.3) as u32;
+    let island_h = (screen_w as f32 * 0.085) as u32;
+    let island_x = body_x + ((body_w - island_w) / 2) as i64;
+    let island_y = (bezel as f32 + screen_w as f32 * 0.03) as i64;
+    fill_rounded_rect(&mut frame, island_x, island_y, island_w, island_h, island_h as f32 / 2.0, Rgba([0, 0, 0, 255]));
+
+    frame
+}
+
+/// Laptop: lid with bezel and camera, aluminium base with a thumb notch
+fn laptop_frame(source: &RgbaImage, dark: bool, crop: bool) -> RgbaImage {
+    let screen_w = source.width().clamp(640, 2560);
+    let screen_h = (screen_w as f32 * 10.0 / 16.0) as u32;
+    let bezel = (screen_w as f32 * 0.022) as u32;
+    let top_bezel = bezel + bezel / 2;
+    let lid_w = screen_w + bezel * 2;
+    let lid_h = screen_h + top_bezel + bezel;
+    let lid_radius = bezel as f32 * 1.2;
+
+    let base_w = (lid_w as f32 * 1.16) as u32;
+    let base_h = (screen_w as f32 * 0.032) as u32;
+    let (base_color, notch_color, shade_color) = if dark {
+        (Rgba([58, 58, 62, 255]), Rgba([36, 36, 40, 255]), Rgba([40, 40, 44, 255]))
+    } else {
+        (Rgba([214, 215, 218, 255]), Rgba([176, 178, 182, 255]), Rgba([168, 170, 174, 255]))
+    };
+
+    let mut frame = RgbaImage::new(base_w, lid_h + base_h);
+    let lid_x = ((base_w - lid_w) / 2) as i64;
+
+    fill_rounded_rect(&mut frame, lid_x, 0, lid_w, lid_h + bezel, lid_radius, Rgba([22, 22, 24, 255]));
+    let screen = fit_screen(source, screen_w, screen_h, crop);
+    image::imageops::overlay(&mut frame, &screen, lid_x + bezel as i64, top_bezel as i64);
+
+    // Camera dot
+    fill_circle_aa(
+        &mut frame,
+        base_w as f32 / 2.0,
+        top_bezel as f32 / 2.0,
+        (bezel as f32 * 0.14).max(1.5),
+        Rgba([48, 48, 52, 255]),
+    );
+
+    // Base: flat top edge, rounded bottom, darker underside strip
+    let base_y = lid_h as i64;
+    fill_rounded_rect(&mut frame, 0, base_y, base_w, base_h, base_h as f32 * 0.6, base_color);
+    for x in 0..base_w {
+        for y in 0..(base_h / 3).max(1) {
+            frame.put_pixel(x, lid_h + y, base_color);
+        }
+    }
+    let shade_h = (base_h / 4).max(1);
+    let shade_inset = (base_h as f32 * 0.6) as u32;
+    fill_rounded_rect(
+        &mut frame, shade_inset as i64, base_y + (base_h - shade_h) as i64,
+        base_w - shade_inset * 2, shade_h, shade_h as f32 / 2.0, shade_color,
+    );
+
+    let notch_w = (screen_w as f32 * 0.14) as u32;
+    let notch_h = (base_h as f32 * 0.32) as u32;
+    fill_rounded_rect(
+        &mut frame, ((base_w - notch_w) / 2) as i64, base_y, notch_w, notch_h,
+        notch_h as f32 / 2.0, notch_color,
+    );
+
+    frame
+}
+
+/// Tauri command: render a mockup frame around a library image and save it
+#[tauri::command]
+pub fn mockup_share(
+    app: tauri::AppHandle,
+    source_path: String,
+    options: MockupOptions,
+) -> Result<String, String> {
+    let source = image::open(&source_path)
+        .map_err(|e| format!("Failed to open image: {}", e))?
+        .to_rgba8();
+
+    let composed = render_mockup(&source, &options);
+    deliver_share(&app, &composed, "mockup")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Rgba<u8> = Rgba([220, 40, 40, 255]);
+    const BLUE: Rgba<u8> = Rgba([40, 80, 220, 255]);
+    const GREEN: Rgba<u8> = Rgba([40, 160, 60, 255]);
+
+    #[test]
+    fn test_browser_frame_insets() {
+        let source = RgbaImage::from_pixel(800, 500, RED);
+        let options = MockupOptions {
+            frame: MockupFrame::Browser,
+            url: None,
+            dark: false,
+            background: Background::Solid { color: "#FFFFFF".to_string() },
+            padding: 10,
+            shadow: None,
+            crop: false,
+        };
+        let out = render_mockup(&source, &options);
+
+        // 800 * 0.045 = 36, clamped up to a 40px bar
+        let bar_h = 40;
+        assert_eq!(out.dimensions(), (800 + 20, 500 + bar_h + 20));
+        assert_eq!(*out.get_pixel(10 + 400, 10 + bar_h + 250), RED);
+        assert_eq!(*out.get_pixel(10 + 400, 10 + bar_h - 1), Rgba([210, 208, 204, 255])); // Hairline
+        assert_eq!(*out.get_pixel(5, 5), Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_phone_frame_crops_from_top() {
+        // Tall page: red header, blue body, green footer that falls below the screen
+        let source = RgbaImage::from_fn(400, 2000, |_, y| match y {
+            0..100 => RED,
+            1900.. => GREEN,
+            _ => BLUE,
+        });
+        let frame = phone_frame(&source, false, true);
+
+        // Screen 400 x 866 (19.5:9), bezel 18, side buttons 6px on each side
+        let (screen_h, bezel, button_w) = (866, 18, 6);
+        assert_eq!(frame.dimensions(), (400 + bezel * 2 + button_w * 2, screen_h + bezel * 2));
+
+        let screen_x = button_w + bezel;
+        assert_eq!(*frame.get_pixel(screen_x + 40, bezel + 60), RED);
+        assert_eq!(*frame.get_pixel(screen_x + 200, bezel + 500), BLUE);
+        assert_eq!(*frame.get_pixel(screen_x + 200, bezel + screen_h - 60), BLUE);
+        assert!(frame.pixels().all(|p| *p != GREEN), "footer should be cropped away");
+    }
+
+    #[test]
+    fn test_phone_frame_letterboxes_wide_capture() {
+        // 16:9 desktop capture with marked left / right edges
+        let source = RgbaImage::from_fn(1600, 900, |x, _| match x {
+            0..40 => GREEN,
+            1560.. => BLUE,
+            _ => RED,
+        });
+        let frame = phone_frame(&source, false, false);
+
+        // Screen 1290 x 2795, bezel 58, side buttons 19px: the full width fits, 1290 x 726 centred
+        let (screen_w, screen_h, bezel, button_w) = (1290, 2795, 58, 19);
+        assert_eq!(frame.dimensions(), (screen_w + bezel * 2 + button_w * 2, screen_h + bezel * 2));
+
+        let (screen_x, mid_y) = (button_w + bezel, bezel + screen_h / 2);
+        assert_eq!(*frame.get_pixel(screen_x + 10, mid_y), GREEN);
+        assert_eq!(*frame.get_pixel(screen_x + screen_w / 2, mid_y), RED);
+        assert_eq!(*frame.get_pixel(screen_x + screen_w - 10, mid_y), BLUE);
+        assert_eq!(*frame.get_pixel(screen_x + screen_w / 2, bezel + 400), SCREEN_BACKGROUND);
+    }
+}
@@ -11,6 +11,7 @@ use std::ffi::c_void;
 extern "C" {
     fn CGMainDisplayID() -> u32;
     fn CGDisplayCreateImage(display_id: u32) -> *mut c_void;
+    fn CGGetDisplaysWithPoint(point: CGPoint, max_displays: u32, displays: *mut u32, count: *mut u32) -> i32;
     fn CGImageGetWidth(image: *const c_void) -> usize;
     fn CGImageGetHeight(image: *const c_void) -> usize;
     fn CGImageGetBytesPerRow(image: *const c_void) -> usize;
@@ -21,6 +22,12 @@ extern "C" {
     fn CFRelease(cf: *const c_void);
 }
 
+#[repr(C)]
+struct CGPoint {
+    x: f64,
+    y: f64,
+}
+
 /// Raw CGImage handle
 pub struct CGImageRef(*mut c_void);
 
@@ -48,10 +55,16 @@ impl Drop for CGImageRef {
     }
 }
 
-/// Fast screen capture using CoreGraphics (typically 10-50ms)
-pub fn capture_cgimage() -> Option<CGImageRef> {
+/// Fast capture of the display containing a global point, in points (typically 10-50ms)
+///
+/// Falls back to the main display when no display contains the point.
+pub fn capture_cgimage_at(x: f64, y: f64) -> Option<CGImageRef> {
     unsafe {
-        let display_id = CGMainDisplayID();
+        let mut display_id = 0u32;
+        let mut count = 0u32;
+        if CGGetDisplaysWithPoint(CGPoint { x, y }, 1, &mut display_id, &mut count) != 0 || count == 0 {
+            display_id = CGMainDisplayID();
+        }
         let cg_image = CGDisplayCreateImage(display_id);
         if cg_image.is_null() {
             None
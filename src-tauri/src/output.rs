@@ -0,0 +1,348 @@
+//! Output location and filename templates
+//!
+//! Every file Lovshot writes into the library goes through here, so the
+//! root directory and naming scheme are configured in one place.
+
+use chrono::{DateTime, Local};
+use image::RgbaImage;
+use std::fs::{self, File, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::config::{self, FilenameTemplates};
+use crate::types::Region;
+
+/// Kind of file being written (selects the filename template)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputKind {
+    Screenshot,
+    Recording,
+    Share,
+}
+
+impl OutputKind {
+    fn template(self, templates: &FilenameTemplates) -> &str {
+        match self {
+            OutputKind::Screenshot => &templates.screenshot,
+            OutputKind::Recording => &templates.recording,
+            OutputKind::Share => &templates.share,
+        }
+    }
+
+    fn fallback_prefix(self) -> &'static str {
+        match self {
+            OutputKind::Screenshot => "screenshot",
+            OutputKind::Recording => "recording",
+            OutputKind::Share => "share",
+        }
+    }
+}
+
+/// Values available to filename placeholders
+#[derive(Clone, Debug, Default)]
+pub struct FilenameContext {
+    pub mode: String,             // {mode}
+    pub counter: Option<u64>,     // {counter}, reserved on demand if None
+    pub app_name: Option<String>, // {app}, empty when unknown
+}
+
+impl FilenameContext {
+    pub fn new(mode: &str) -> Self {
+        Self {
+            mode: mode.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_counter(mut self, counter: u64) -> Self {
+        self.counter = Some(counter);
+        self
+    }
+
+    pub fn with_app_name(mut self, app_name: Option<String>) -> Self {
+        self.app_name = app_name;
+        self
+    }
+}
+
+/// Default library root: ~/Pictures/lovshot
+pub fn default_lovshot_dir() -> PathBuf {
+    dirs::picture_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("lovshot")
+}
+
+/// Library root from config (falls back to ~/Pictures/lovshot)
+pub fn lovshot_dir() -> PathBuf {
+    resolve_output_dir(config::load_config().output_dir.as_deref())
+}
+
+/// Resolve a configured output dir, expanding a leading "~"
+pub fn resolve_output_dir(configured: Option<&str>) -> PathBuf {
+    let configured = match configured.map(str::trim) {
+        Some(p) if !p.is_empty() => p,
+        _ => return default_lovshot_dir(),
+    };
+
+    if configured == "~" {
+        return dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    }
+    if let Some(rest) = configured
+        .strip_prefix("~/")
+        .or_else(|| configured.strip_prefix("~\\"))
+    {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+    PathBuf::from(configured)
+}
+
+/// Build the full path for a new file in the library root (creates the dir)
+pub fn build_output_path(kind: OutputKind, ctx: FilenameContext, ext: &str) -> Result<PathBuf, String> {
+    let cfg = config::load_config();
+    let output_dir = resolve_output_dir(cfg.output_dir.as_deref());
+    std::fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
+
+    let template = kind.template(&cfg.filename_templates);
+    let mut ctx = ctx;
+    if ctx.counter.is_none() && template.contains("{counter}") {
+        ctx.counter = Some(config::next_screenshot_number());
+    }
+
+    let mut stem = render_filename(template, &ctx, Local::now());
+    if stem.is_empty() {
+        let fallback = default_template(kind);
+        stem = render_filename(&fallback, &ctx, Local::now());
+    }
+
+    Ok(output_dir.join(format!("{}.{}", stem, ext)))
+}
+
+fn default_template(kind: OutputKind) -> String {
+    format!("{}_{{date}}_{{time}}", kind.fallback_prefix())
+}
+
+/// Expand placeholders in a filename template (without extension)
+///
+/// Supported: {date} (YYYYMMDD), {time} (HHMMSS), {counter}, {mode}, {app}.
+/// Unknown placeholders are kept as-is.
+pub fn render_filename(template: &str, ctx: &FilenameContext, now: DateTime<Local>) -> String {
+    let mut out = String::with_capacity(template.len() + 16);
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            out.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+
+        let key = &after[..end];
+        match key {
+            "date" => out.push_str(&now.format("%Y%m%d").to_string()),
+            "time" => out.push_str(&now.format("%H%M%S").to_string()),
+            "counter" => out.push_str(&ctx.counter.map(|c| c.to_string()).unwrap_or_default()),
+            "mode" => out.push_str(&sanitize_component(&ctx.mode)),
+            "app" => out.push_str(&sanitize_component(ctx.app_name.as_deref().unwrap_or(""))),
+            _ => {
+                out.push('{');
+                out.push_str(key);
+                out.push('}');
+            }
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+
+    tidy_separators(&sanitize_component(&out))
+}
+
+/// Replace characters that are invalid in filenames on any platform
+fn sanitize_component(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Collapse separators left behind by empty placeholders ("a__b" -> "a_b")
+fn tidy_separators(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut prev_sep = false;
+    for c in s.chars() {
+        let is_sep = c == '_' || c == '-';
+        if is_sep && prev_sep {
+            continue;
+        }
+        prev_sep = is_sep;
+        out.push(c);
+    }
+    out.trim_matches(|c: char| c == '_' || c == '-' || c == '.' || c.is_whitespace())
+        .to_string()
+}
+
+/// Claim a filename that doesn't exist yet, appending _1, _2... on conflict
+///
+/// The file is created empty with `create_new`, so two captures in the same
+/// second can never pick the same name.
+pub fn create_unique_file(path: &Path) -> Result<PathBuf, String> {
+    let dir = path.parent().ok_or("Invalid output path")?;
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("file");
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+
+    let mut counter = 0u32;
+    loop {
+        let candidate = if counter == 0 {
+            path.to_path_buf()
+        } else if ext.is_empty() {
+            dir.join(format!("{}_{}", stem, counter))
+        } else {
+            dir.join(format!("{}_{}.{}", stem, counter, ext))
+        };
+
+        match OpenOptions::new().write(true).create_new(true).open(&candidate) {
+            Ok(_) => return Ok(candidate),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => counter += 1,
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+}
+
+/// Write a file through a hidden temp file in the same dir, then rename it
+/// into place, so a crash never leaves a half-written file at `path`
+pub fn write_file_atomic<F>(path: &Path, write: F) -> Result<(), String>
+where
+    F: FnOnce(&Path) -> Result<(), String>,
+{
+    let dir = path.parent().ok_or("Invalid output path")?;
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("file");
+
+    let mut attempt = 0u32;
+    let tmp = loop {
+        let candidate = dir.join(format!(".{}.{}-{}.part", name, std::process::id(), attempt));
+        match OpenOptions::new().write(true).create_new(true).open(&candidate) {
+            Ok(_) => break candidate,
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => attempt += 1,
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+
+    let result = write(&tmp).and_then(|_| {
+        File::open(&tmp)
+            .and_then(|f| f.sync_all())
+            .map_err(|e| e.to_string())?;
+        fs::rename(&tmp, path).map_err(|e| e.to_string())
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Encode an image atomically, format chosen from `path`'s extension
+pub fn save_image_atomic(img: &RgbaImage, path: &Path) -> Result<(), String> {
+    let format = image::ImageFormat::from_path(path).map_err(|e| e.to_string())?;
+    write_file_atomic(path, |tmp| {
+        img.save_with_format(tmp, format).map_err(|e| e.to_string())
+    })
+}
+
+/// Save an image under a fresh, collision-free name derived from `path`
+/// Returns the path actually written
+pub fn save_image_unique(img: &RgbaImage, path: &Path) -> Result<PathBuf, String> {
+    let final_path = create_unique_file(path)?;
+    if let Err(e) = save_image_atomic(img, &final_path) {
+        let _ = fs::remove_file(&final_path);
+        return Err(e);
+    }
+    Ok(final_path)
+}
+
+/// Name of the app owning the window at the centre of a region, if known
+pub fn app_name_for_region(region: &Region) -> Option<String> {
+    #[cfg(target_os = "macos")]
+    {
+        let cx = region.x as f64 + region.width as f64 / 2.0;
+        let cy = region.y as f64 + region.height as f64 / 2.0;
+        let pid = crate::window_detect::get_window_pid_at_position(cx, cy)?;
+        crate::window_detect::get_app_name_from_pid(pid)
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let cx = region.x as f64 + region.width as f64 / 2.0;
+        let cy = region.y as f64 + region.height as f64 / 2.0;
+        crate::window_capture::window_at_position(cx, cy)
+            .map(|w| w.app_name)
+            .filter(|name| !name.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn fixed_now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 3, 9, 7, 5, 1).unwrap()
+    }
+
+    #[test]
+    fn test_default_templates() {
+        let ctx = FilenameContext::new("image");
+        let name = render_filename("screenshot_{date}_{time}", &ctx, fixed_now());
+        assert_eq!(name, "screenshot_20240309_070501");
+    }
+
+    #[test]
+    fn test_all_placeholders() {
+        let ctx = FilenameContext::new("gif")
+            .with_counter(42)
+            .with_app_name(Some("Google Chrome".to_string()));
+        let name = render_filename("{app}-{mode}-#{counter}-{date}", &ctx, fixed_now());
+        assert_eq!(name, "Google Chrome-gif-#42-20240309");
+    }
+
+    #[test]
+    fn test_missing_app_and_unknown_placeholder() {
+        let ctx = FilenameContext::new("image");
+        let name = render_filename("{app}_shot_{foo}_{time}", &ctx, fixed_now());
+        assert_eq!(name, "shot_{foo}_070501");
+    }
+
+    #[test]
+    fn test_unique_file_appends_suffix() {
+        let dir = std::env::temp_dir().join(format!("lovshot_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("shot.png");
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(dir.join("shot_1.png"));
+
+        let first = create_unique_file(&path).unwrap();
+        let second = create_unique_file(&path).unwrap();
+        assert_eq!(first, path);
+        assert_eq!(second, dir.join("shot_1.png"));
+
+        let img = RgbaImage::from_pixel(4, 4, image::Rgba([1, 2, 3, 255]));
+        save_image_atomic(&img, &second).unwrap();
+        assert_eq!(image::open(&second).unwrap().to_rgba8(), img);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sanitizes_path_separators() {
+        let ctx = FilenameContext::new("image").with_app_name(Some("a/b:c".to_string()));
+        let name = render_filename("../{app}", &ctx, fixed_now());
+        assert!(!name.contains('/'));
+        assert!(!name.contains(':'));
+    }
+}
@@ -0,0 +1,266 @@
+//! Dominant colour extraction (median cut refined by k-means)
+//!
+//! Works on a library image or a region of the frozen screen; the palette can
+//! also be rendered as a swatch strip under the image and saved like a share.
+
+use ab_glyph::PxScale;
+use image::imageops::FilterType;
+use image::{Rgba, RgbaImage};
+use serde::Serialize;
+
+use crate::color::relative_luminance;
+use crate::fonts::{font_chain, FontRole};
+use crate::share_compose::{deliver_share, BG_WARM};
+use crate::state::SharedState;
+use crate::types::Region;
+
+/// Pixels are sampled from a downscaled copy no larger than this on either side
+const SAMPLE_EDGE: u32 = 256;
+const KMEANS_ROUNDS: usize = 4;
+
+/// One palette entry
+#[derive(Clone, Debug, Serialize)]
+pub struct PaletteColor {
+    pub hex: String,
+    pub rgb: [u8; 3],
+    pub coverage: f32, // Percent of (opaque) pixels closest to this colour
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PaletteResult {
+    pub colors: Vec<PaletteColor>,
+    pub image_path: Option<String>, // Saved swatch-strip image, when requested
+}
+
+/// Opaque pixels of a downscaled copy
+fn sample_pixels(img: &RgbaImage) -> Vec<[u8; 3]> {
+    let (w, h) = img.dimensions();
+    let scale = (SAMPLE_EDGE as f32 / w.max(h) as f32).min(1.0);
+    let small;
+    let img = if scale < 1.0 {
+        let sw = ((w as f32 * scale).round() as u32).max(1);
+        let sh = ((h as f32 * scale).round() as u32).max(1);
+        // Nearest keeps real screen colours instead of inventing blends at edges
+        small = image::imageops::resize(img, sw, sh, FilterType::Nearest);
+        &small
+    } else {
+        img
+    };
+    img.pixels().filter(|p| p[3] >= 128).map(|p| [p[0], p[1], p[2]]).collect()
+}
+
+fn channel_range(pixels: &[[u8; 3]]) -> (usize, u8) {
+    (0..3)
+        .map(|c| {
+            let (min, max) = pixels.iter().fold((255u8, 0u8), |(lo, hi), p| (lo.min(p[c]), hi.max(p[c])));
+            (c, max.saturating_sub(min))
+        })
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or((0, 0))
+}
+
+fn mean(pixels: &[[u8; 3]]) -> [f32; 3] {
+    let mut sum = [0f64; 3];
+    for p in pixels {
+        for c in 0..3 {
+            sum[c] += p[c] as f64;
+        }
+    }
+    let n = pixels.len().max(1) as f64;
+    sum.map(|v| (v / n) as f32)
+}
+
+/// Median cut: repeatedly split the box with the widest channel range (weighted by size)
+fn median_cut(pixels: Vec<[u8; 3]>, n: usize) -> Vec<[f32; 3]> {
+    let mut boxes = vec![pixels];
+    while boxes.len() < n {
+        let Some((i, channel)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .map(|(i, b)| {
+                let (channel, range) = channel_range(b);
+                (i, channel, range as usize * b.len())
+            })
+            .filter(|&(_, _, score)| score > 0)
+            .max_by_key(|&(_, _, score)| score)
+            .map(|(i, channel, _)| (i, channel))
+        else {
+            break; // Fewer distinct colours than requested
+        };
+
+        let mut b = boxes.swap_remove(i);
+        b.sort_unstable_by_key(|p| p[channel]);
+        let upper = b.split_off(b.len() / 2);
+        boxes.push(b);
+        boxes.push(upper);
+    }
+    boxes.iter().filter(|b| !b.is_empty()).map(|b| mean(b)).collect()
+}
+
+fn dist2(p: [u8; 3], c: [f32; 3]) -> f32 {
+    (0..3).map(|i| (p[i] as f32 - c[i]).powi(2)).sum()
+}
+
+fn nearest(p: [u8; 3], centers: &[[f32; 3]]) -> usize {
+    centers
+        .iter()
+        .enumerate()
+        .min_by(|a, b| dist2(p, *a.1).total_cmp(&dist2(p, *b.1)))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+/// Top `n` colours by coverage, most common first
+pub fn extract_palette(img: &RgbaImage, n: usize) -> Vec<PaletteColor> {
+    let pixels = sample_pixels(img);
+    if pixels.is_empty() || n == 0 {
+        return Vec::new();
+    }
+
+    // Median cut seeds, then a few k-means rounds pull centres onto the real clusters
+    let mut centers = median_cut(pixels.clone(), n);
+    let mut counts = vec![0usize; centers.len()];
+    for _ in 0..KMEANS_ROUNDS {
+        let mut sums = vec![[0f64; 3]; centers.len()];
+        counts.iter_mut().for_each(|c| *c = 0);
+        for &p in &pixels {
+            let i = nearest(p, &centers);
+            counts[i] += 1;
+            for c in 0..3 {
+                sums[i][c] += p[c] as f64;
+            }
+        }
+        for (i, center) in centers.iter_mut().enumerate() {
+            if counts[i] > 0 {
+                *center = sums[i].map(|v| (v / counts[i] as f64) as f32);
+            }
+        }
+    }
+
+    let total = pixels.len() as f32;
+    let mut colors: Vec<PaletteColor> = centers
+        .iter()
+        .zip(&counts)
+        .filter(|(_, &count)| count > 0)
+        .map(|(center, &count)| {
+            let rgb = center.map(|v| v.round().clamp(0.0, 255.0) as u8);
+            PaletteColor {
+                hex: format!("#{:02X}{:02X}{:02X}", rgb[0], rgb[1], rgb[2]),
+                rgb,
+                coverage: (count as f32 / total * 1000.0).round() / 10.0,
+            }
+        })
+        .collect();
+    colors.sort_by(|a, b| b.coverage.total_cmp(&a.coverage));
+    colors
+}
+
+/// The image with a strip of equal-width swatches (hex and coverage) underneath
+pub fn render_swatch_strip(source: &RgbaImage, colors: &[PaletteColor]) -> RgbaImage {
+    let (w, h) = source.dimensions();
+    let strip_h = (w as f32 * 0.1).clamp(72.0, 180.0) as u32;
+    let mut canvas = RgbaImage::from_pixel(w, h + strip_h, BG_WARM);
+    image::imageops::overlay(&mut canvas, source, 0, 0);
+    if colors.is_empty() {
+        return canvas;
+    }
+
+    let font = font_chain(FontRole::Mono);
+    let n = colors.len() as u32;
+    for (i, color) in colors.iter().enumerate() {
+        let x0 = w * i as u32 / n;
+        let x1 = w * (i as u32 + 1) / n;
+        let fill = Rgba([color.rgb[0], color.rgb[1], color.rgb[2], 255]);
+        for y in h..h + strip_h {
+            for x in x0..x1 {
+                canvas.put_pixel(x, y, fill);
+            }
+        }
+
+        let Some(ref font) = font else { continue };
+        let ink = if relative_luminance(color.rgb) > 0.4 {
+            Rgba([24, 24, 24, 255])
+        } else {
+            Rgba([255, 255, 255, 255])
+        };
+        let size = (strip_h as f32 * 0.2).min((x1 - x0) as f32 / 5.0);
+        if size < 8.0 {
+            continue; // Too narrow for readable labels
+        }
+        let pct = format!("{:.1}%", color.coverage);
+        let cx = (x0 + x1) as f32 / 2.0;
+        let mut y = h as f32 + (strip_h as f32 - size * 2.1) / 2.0;
+        for (text, text_size) in [(color.hex.as_str(), size), (pct.as_str(), size * 0.8)] {
+            let scale = PxScale::from(text_size);
+            let tw = font.text_width(scale, text);
+            font.draw_text(&mut canvas, ink, (cx - tw / 2.0) as i32, y as i32, scale, text);
+            y += size * 1.3;
+        }
+    }
+    canvas
+}
+
+/// Tauri command: dominant colours of a library image or of a region of the frozen screen
+///
+/// With `render_strip`, the image plus a swatch strip is copied and saved like a share.
+#[tauri::command(rename_all = "camelCase")]
+pub fn extract_palette_colors(
+    app: tauri::AppHandle,
+    state: tauri::State<SharedState>,
+    source_path: Option<String>,
+    region: Option<Region>,
+    count: Option<usize>,
+    render_strip: Option<bool>,
+) -> Result<PaletteResult, String> {
+    let img = match (source_path, region) {
+        (Some(path), _) => image::open(&path)
+            .map_err(|e| format!("Failed to open image: {}", e))?
+            .to_rgba8(),
+        (None, Some(region)) => {
+            let s = state.lock().unwrap();
+            let snapshot = s.cached_snapshot.as_ref().ok_or("No screen snapshot")?;
+            crate::commands::crop_snapshot(snapshot, &region, s.screen_x, s.screen_y, s.screen_scale)?
+        }
+        (None, None) => return Err("Need an image path or a region".to_string()),
+    };
+
+    let count = count.unwrap_or(6).clamp(1, 16);
+    let colors = extract_palette(&img, count);
+    println!("[palette] {} colours from {}x{}", colors.len(), img.width(), img.height());
+
+    let image_path = if render_strip.unwrap_or(false) {
+        let composed = render_swatch_strip(&img, &colors);
+        Some(deliver_share(&app, &composed, "palette")?)
+    } else {
+        None
+    };
+    Ok(PaletteResult { colors, image_path })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palette_coverage() {
+        // 3/4 red, 1/4 blue, plus a transparent strip that must be ignored
+        let mut img = RgbaImage::from_pixel(40, 50, Rgba([220, 30, 30, 255]));
+        for y in 0..40 {
+            for x in 30..40 {
+                img.put_pixel(x, y, Rgba([20, 40, 200, 255]));
+            }
+        }
+        for y in 40..50 {
+            for x in 0..40 {
+                img.put_pixel(x, y, Rgba([0, 255, 0, 0]));
+            }
+        }
+
+        let colors = extract_palette(&img, 4);
+        assert_eq!(colors.len(), 2);
+        assert_eq!(colors[0].hex, "#DC1E1E");
+        assert_eq!(colors[0].coverage, 75.0);
+        assert_eq!(colors[1].rgb, [20, 40, 200]);
+    }
+}
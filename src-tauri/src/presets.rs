@@ -0,0 +1,145 @@
+//! Named region presets and the selection aspect-ratio lock
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{CaptureMode, Region};
+
+/// Shortcut actions for presets are named "preset:<id>"
+pub const PRESET_ACTION_PREFIX: &str = "preset:";
+
+/// A saved capture area: an absolute rect, or a size centred on the cursor
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RegionPreset {
+    pub id: String,
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub x: Option<i32>, // Both set = absolute rect; otherwise centred on the cursor
+    #[serde(default)]
+    pub y: Option<i32>,
+    #[serde(default)]
+    pub mode: CaptureMode,
+}
+
+impl RegionPreset {
+    fn centered(id: &str, name: &str, width: u32, height: u32) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            width,
+            height,
+            x: None,
+            y: None,
+            mode: CaptureMode::Image,
+        }
+    }
+
+    /// Region to capture; cursor-centred presets are kept inside `bounds` (x, y, width, height)
+    pub fn resolve(&self, cursor: (f64, f64), bounds: (i32, i32, u32, u32)) -> Region {
+        if let (Some(x), Some(y)) = (self.x, self.y) {
+            return Region { x, y, width: self.width, height: self.height };
+        }
+        let (bx, by, bw, bh) = bounds;
+        let place = |center: f64, size: u32, start: i32, extent: u32| {
+            let pos = (center - size as f64 / 2.0).round() as i32;
+            // Larger than the screen: pin to its top-left edge
+            pos.clamp(start, (start + extent as i32 - size as i32).max(start))
+        };
+        Region {
+            x: place(cursor.0, self.width, bx, bw),
+            y: place(cursor.1, self.height, by, bh),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Output scale that saves a capture taken at `capture_scale` at exactly width × height pixels
+    ///
+    /// Regions are in points, so a 2x screen captures the preset at twice its size.
+    pub fn output_scale(&self, capture_scale: f32) -> f32 {
+        1.0 / capture_scale.max(1.0)
+    }
+}
+
+pub fn default_region_presets() -> Vec<RegionPreset> {
+    vec![
+        RegionPreset::centered("hd", "HD 1280×720", 1280, 720),
+        RegionPreset::centered("og", "Open Graph 1200×630", 1200, 630),
+    ]
+}
+
+/// "16:9", "1.91:1" or "1.5" to width / height
+pub fn parse_aspect_ratio(s: &str) -> Option<f32> {
+    let ratio = match s.split_once(':') {
+        Some((w, h)) => w.trim().parse::<f32>().ok()? / h.trim().parse::<f32>().ok()?,
+        None => s.trim().parse().ok()?,
+    };
+    (ratio.is_finite() && ratio > 0.0).then_some(ratio)
+}
+
+/// Shrink a region to the aspect ratio, keeping its top-left corner
+pub fn fit_aspect(region: &Region, ratio: f32) -> Region {
+    let (w, h) = (region.width as f32, region.height as f32);
+    let (width, height) = if w / h > ratio {
+        ((h * ratio).round() as u32, region.height)
+    } else {
+        (region.width, (w / ratio).round() as u32)
+    };
+    Region {
+        x: region.x,
+        y: region.y,
+        width: width.max(1),
+        height: height.max(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_centres_and_clamps() {
+        let og = &default_region_presets()[1];
+        let screen = (0, 0, 1920, 1080);
+
+        let r = og.resolve((960.0, 540.0), screen);
+        assert_eq!((r.x, r.y, r.width, r.height), (360, 225, 1200, 630));
+
+        // Near the bottom-right corner the rect is pushed back on screen
+        let r = og.resolve((1900.0, 1070.0), screen);
+        assert_eq!((r.x, r.y), (720, 450));
+
+        // Secondary monitor to the left
+        let r = og.resolve((-1900.0, 10.0), (-1920, 0, 1920, 1080));
+        assert_eq!((r.x, r.y), (-1920, 0));
+    }
+
+    #[test]
+    fn test_preset_saves_at_its_size_on_hidpi() {
+        use crate::capture::{plan_region, scaled_size, DisplayInfo};
+
+        let hd = &default_region_presets()[0];
+        for scale_factor in [1.0, 1.5, 2.0] {
+            let display = DisplayInfo { id: 0, x: 0, y: 0, width: 1440, height: 900, scale_factor };
+            let region = hd.resolve((720.0, 450.0), (0, 0, 1440, 900));
+            let (capture_scale, parts) = plan_region(&[display], region.x, region.y, region.width, region.height);
+            let (w, h) = parts[0].size;
+            assert_eq!(scaled_size(w, h, hd.output_scale(capture_scale)), (1280, 720));
+        }
+    }
+
+    #[test]
+    fn test_aspect_ratio() {
+        assert_eq!(parse_aspect_ratio("16:9"), Some(16.0 / 9.0));
+        assert_eq!(parse_aspect_ratio(" 1.5 "), Some(1.5));
+        assert_eq!(parse_aspect_ratio("4:0"), None);
+        assert_eq!(parse_aspect_ratio("wide"), None);
+
+        let region = Region { x: 10, y: 20, width: 1000, height: 1000 };
+        let fitted = fit_aspect(&region, 16.0 / 9.0);
+        assert_eq!((fitted.x, fitted.y, fitted.width, fitted.height), (10, 20, 1000, 563));
+        let fitted = fit_aspect(&Region { x: 0, y: 0, width: 2000, height: 630 }, 1200.0 / 630.0);
+        assert_eq!((fitted.width, fitted.height), (1200, 630));
+    }
+}
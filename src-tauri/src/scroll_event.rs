@@ -41,7 +41,7 @@ fn do_scroll_capture(
     _delta_y: f64,
     _use_fixed_delta: bool,
 ) -> CaptureResult {
-    use crate::capture::Screen;
+    use crate::capture::{capture_region, Screen};
     use crate::commands::{generate_preview_base64, stitch_scroll_image};
     use crate::fft_match::detect_scroll_delta_fft;
     use image::RgbaImage;
@@ -66,11 +66,7 @@ fn do_scroll_capture(
         Ok(s) => s,
         Err(_) => return CaptureResult::Error,
     };
-    let screen = match screens.first() {
-        Some(s) => s,
-        None => return CaptureResult::Error,
-    };
-    let captured = match screen.capture_area(region.x, region.y, region.width, region.height) {
+    let captured = match capture_region(&screens, region.x, region.y, region.width, region.height) {
         Ok(c) => c,
         Err(_) => return CaptureResult::Error,
     };
@@ -1,7 +1,13 @@
-use ab_glyph::{FontRef, PxScale};
+use ab_glyph::PxScale;
 use image::{Rgba, RgbaImage};
-use imageproc::drawing::draw_text_mut;
-use std::path::PathBuf;
+
+use crate::fonts::{font_chain, FontChain, FontRole};
+use crate::share_template::{find_template, load_custom_templates, Background, CaptionPosition, ShadowSpec, ShareTemplateInfo, TemplateSpec};
+use crate::text_layout::{text_width, truncate_with_ellipsis, wrap_text};
+use crate::watermark::{blend_layer, parse_hex_color, render_watermark_text, WatermarkLayer};
+use crate::output::{
+    build_output_path, create_unique_file, save_image_unique, write_file_atomic, FilenameContext, OutputKind,
+};
 
 /// Share template types
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -11,120 +17,230 @@ pub enum ShareTemplate {
     Card,          // 卡片式（带圆角边框）
     Minimal,       // 极简（小字号）
     Social,        // 类似即刻/X 风格
+    Custom(Box<TemplateSpec>), // 用户模板（templates 目录）
 }
 
-/// Colors from Lovstudio design system
-const BG_WARM: Rgba<u8> = Rgba([249, 249, 247, 255]);      // #F9F9F7
-const TEXT_DARK: Rgba<u8> = Rgba([24, 24, 24, 255]);        // #181818
-const TEXT_MUTED: Rgba<u8> = Rgba([135, 134, 127, 255]);    // #87867F
-const ACCENT: Rgba<u8> = Rgba([204, 120, 92, 255]);         // #CC785C
-
-/// Load system font (PingFang on macOS)
-fn load_font() -> Option<FontRef<'static>> {
-    #[cfg(target_os = "macos")]
-    {
-        let font_paths = [
-            "/System/Library/Fonts/PingFang.ttc",
-            "/System/Library/Fonts/STHeiti Light.ttc",
-            "/System/Library/Fonts/Helvetica.ttc",
-        ];
-        for path in font_paths {
-            if let Ok(data) = std::fs::read(path) {
-                // Leak to get 'static lifetime (acceptable for font data)
-                let leaked: &'static [u8] = Box::leak(data.into_boxed_slice());
-                if let Ok(font) = FontRef::try_from_slice(leaked) {
-                    return Some(font);
-                }
-            }
+/// Built-in templates: (id, display name)
+const BUILTIN_TEMPLATES: [(&str, &str); 4] = [
+    ("caption_below", "Caption Below"),
+    ("card", "Card"),
+    ("minimal", "Minimal"),
+    ("social", "Social"),
+];
+
+impl ShareTemplate {
+    /// Resolve a template id (built-in first, then user templates)
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "caption_below" => Some(ShareTemplate::CaptionBelow),
+            "card" => Some(ShareTemplate::Card),
+            "minimal" => Some(ShareTemplate::Minimal),
+            "social" => Some(ShareTemplate::Social),
+            _ => find_template(id).map(|spec| ShareTemplate::Custom(Box::new(spec))),
         }
     }
-    #[cfg(not(target_os = "macos"))]
-    {
-        // Fallback: try common font paths
-        let font_paths = [
-            "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
-            "C:\\Windows\\Fonts\\msyh.ttc",
-        ];
-        for path in font_paths {
-            if let Ok(data) = std::fs::read(path) {
-                let leaked: &'static [u8] = Box::leak(data.into_boxed_slice());
-                if let Ok(font) = FontRef::try_from_slice(leaked) {
-                    return Some(font);
-                }
-            }
-        }
-    }
-    None
 }
 
-/// Measure text width (approximate)
-fn measure_text_width(text: &str, scale: PxScale) -> u32 {
-    // Rough estimate: ~0.5 em per character for CJK, ~0.3 for ASCII
-    let mut width = 0.0f32;
-    for c in text.chars() {
-        if c.is_ascii() {
-            width += scale.x * 0.5;
-        } else {
-            width += scale.x * 1.0;
-        }
-    }
-    width as u32
+/// Options for the screenshot beautifier
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct BeautifyOptions {
+    #[serde(default = "default_beautify_background")]
+    pub background: Background,
+    #[serde(default = "default_beautify_padding")]
+    pub padding: u32,
+    #[serde(default = "default_beautify_radius")]
+    pub corner_radius: u32,
+    #[serde(default = "default_beautify_shadow")]
+    pub shadow: Option<ShadowSpec>,
+    #[serde(default)]
+    pub window_chrome: bool, // macOS-style title bar with traffic lights
+    #[serde(default)]
+    pub chrome_title: Option<String>,
 }
 
-/// Word wrap text to fit within max_width
-fn wrap_text(text: &str, scale: PxScale, max_width: u32) -> Vec<String> {
-    let mut lines = Vec::new();
-    let mut current_line = String::new();
-    let mut current_width = 0.0f32;
-
-    for c in text.chars() {
-        let char_width = if c.is_ascii() { scale.x * 0.5 } else { scale.x * 1.0 };
-
-        if c == '\n' {
-            lines.push(current_line.clone());
-            current_line.clear();
-            current_width = 0.0;
-            continue;
+impl Default for BeautifyOptions {
+    fn default() -> Self {
+        Self {
+            background: default_beautify_background(),
+            padding: default_beautify_padding(),
+            corner_radius: default_beautify_radius(),
+            shadow: default_beautify_shadow(),
+            window_chrome: false,
+            chrome_title: None,
         }
-
-        if current_width + char_width > max_width as f32 && !current_line.is_empty() {
-            lines.push(current_line.clone());
-            current_line.clear();
-            current_width = 0.0;
-        }
-
-        current_line.push(c);
-        current_width += char_width;
     }
+}
 
-    if !current_line.is_empty() {
-        lines.push(current_line);
+fn default_beautify_background() -> Background {
+    Background::Gradient {
+        from: "#CC785C".to_string(),
+        to: "#D4A27F".to_string(),
+        angle: 45.0,
     }
+}
+
+fn default_beautify_padding() -> u32 {
+    64
+}
+
+fn default_beautify_radius() -> u32 {
+    12
+}
 
-    lines
+fn default_beautify_shadow() -> Option<ShadowSpec> {
+    Some(ShadowSpec::default())
 }
 
+/// Colors from Lovstudio design system
+pub(crate) const BG_WARM: Rgba<u8> = Rgba([249, 249, 247, 255]);      // #F9F9F7
+pub(crate) const TEXT_DARK: Rgba<u8> = Rgba([24, 24, 24, 255]);        // #181818
+pub(crate) const TEXT_MUTED: Rgba<u8> = Rgba([135, 134, 127, 255]);    // #87867F
+pub(crate) const ACCENT: Rgba<u8> = Rgba([204, 120, 92, 255]);         // #CC785C
+
 /// Compose share image with template
 pub fn compose_share_image(
     source_path: &str,
     caption: &str,
     template: ShareTemplate,
 ) -> Result<RgbaImage, String> {
-    let font = load_font().ok_or("Failed to load font")?;
     let source = image::open(source_path)
         .map_err(|e| format!("Failed to open image: {}", e))?
         .to_rgba8();
+    compose_share_rgba(&source, caption, template)
+}
+
+/// Compose an in-memory image with template
+pub fn compose_share_rgba(
+    source: &RgbaImage,
+    caption: &str,
+    template: ShareTemplate,
+) -> Result<RgbaImage, String> {
+    let composed = compose_layout(source, caption, template)?;
+    let mut canvas = composed.canvas;
+    if let Some(ref watermark) = composed.watermark {
+        watermark.apply(&mut canvas);
+    }
+    Ok(canvas)
+}
 
-    match template {
-        ShareTemplate::CaptionBelow => compose_caption_below(&source, caption, &font),
-        ShareTemplate::Card => compose_card(&source, caption, &font),
-        ShareTemplate::Minimal => compose_minimal(&source, caption, &font),
-        ShareTemplate::Social => compose_social(&source, caption, &font),
+/// Compose every frame of an animation
+///
+/// The template (caption, branding, background) is rendered once around a
+/// transparent stand-in, then each frame is dropped into the same spot.
+pub fn compose_share_frames(
+    frames: &[RgbaImage],
+    caption: &str,
+    template: ShareTemplate,
+) -> Result<Vec<RgbaImage>, String> {
+    let first = frames.first().ok_or("No frames to compose")?;
+    let layout = compose_layout(&RgbaImage::new(first.width(), first.height()), caption, template)?;
+    Ok(frames.iter().map(|frame| layout.with_source(frame)).collect())
+}
+
+fn compose_layout(source: &RgbaImage, caption: &str, template: ShareTemplate) -> Result<Composed, String> {
+    let font = font_chain(FontRole::Text).ok_or("Failed to load font")?;
+
+    let mut composed = match template {
+        ShareTemplate::CaptionBelow => compose_caption_below(source, caption, &font),
+        ShareTemplate::Card => compose_card(source, caption, &font),
+        ShareTemplate::Minimal => compose_minimal(source, caption, &font),
+        ShareTemplate::Social => compose_social(source, caption, &font),
+        ShareTemplate::Custom(spec) => compose_custom(source, caption, &font, &spec),
+    }?;
+    // Corner positions stamp the whole composed canvas, like screenshots and GIFs
+    let (width, height) = composed.canvas.dimensions();
+    composed.watermark = WatermarkLayer::for_corner(width, height, crate::config::last_screenshot_number());
+    Ok(composed)
+}
+
+/// A composed share canvas and where the source image sits in it
+///
+/// The corner watermark is kept apart so it lands on top of each frame.
+struct Composed {
+    canvas: RgbaImage,
+    x: i64,
+    y: i64,
+    corner_radius: u32,
+    watermark: Option<WatermarkLayer>,
+}
+
+impl Composed {
+    fn new(canvas: RgbaImage, x: i64, y: i64, corner_radius: u32) -> Self {
+        Self { canvas, x, y, corner_radius, watermark: None }
+    }
+
+    /// The canvas with `source` placed the way the template placed the original
+    fn with_source(&self, source: &RgbaImage) -> RgbaImage {
+        let mut canvas = self.canvas.clone();
+        let mut content = source.clone();
+        round_corners(&mut content, self.corner_radius);
+        blend_layer(&mut canvas, &content, self.x, self.y, 1.0);
+        if let Some(ref watermark) = self.watermark {
+            watermark.apply(&mut canvas);
+        }
+        canvas
     }
 }
 
+/// Frames and delays (1/100 s) of an animated GIF; None for stills and other formats
+fn load_gif_animation(path: &str) -> Option<(Vec<RgbaImage>, Vec<u16>)> {
+    use image::AnimationDecoder;
+
+    if !path.to_lowercase().ends_with(".gif") {
+        return None;
+    }
+    let file = std::io::BufReader::new(std::fs::File::open(path).ok()?);
+    let decoder = image::codecs::gif::GifDecoder::new(file).ok()?;
+    let frames = decoder.into_frames().collect_frames().ok()?;
+    if frames.len() < 2 {
+        return None;
+    }
+
+    let delays = frames
+        .iter()
+        .map(|f| {
+            let (num, den) = f.delay().numer_denom_ms();
+            ((num as f32 / den.max(1) as f32) / 10.0).round().max(1.0) as u16
+        })
+        .collect();
+    let frames = frames.into_iter().map(|f| f.into_buffer()).collect();
+    Some((frames, delays))
+}
+
+/// Encode composed frames as a looping GIF into the library
+fn save_animated_share(frames: &[RgbaImage], delays: &[u16], mode: &str) -> Result<std::path::PathBuf, String> {
+    let first = frames.first().ok_or("No frames to save")?;
+    let (width, height) = first.dimensions();
+    if width > u16::MAX as u32 || height > u16::MAX as u32 {
+        return Err("Image too large for GIF".to_string());
+    }
+
+    let path = build_output_path(OutputKind::Share, FilenameContext::new(mode), "gif")
+        .and_then(|p| create_unique_file(&p))?;
+    let result = write_file_atomic(&path, |tmp_path| {
+        let mut file = std::fs::File::create(tmp_path).map_err(|e| e.to_string())?;
+        let mut encoder = gif::Encoder::new(&mut file, width as u16, height as u16, &[])
+            .map_err(|e| e.to_string())?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(|e| e.to_string())?;
+
+        for (frame, delay) in frames.iter().zip(delays) {
+            let mut pixels = frame.as_raw().clone();
+            let mut gif_frame = gif::Frame::from_rgba_speed(width as u16, height as u16, &mut pixels, 10);
+            gif_frame.delay = *delay;
+            encoder.write_frame(&gif_frame).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    });
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&path);
+        return Err(e);
+    }
+    Ok(path)
+}
+
 /// Template: Caption Below - 文字在图下（白底）
-fn compose_caption_below(source: &RgbaImage, caption: &str, font: &FontRef) -> Result<RgbaImage, String> {
+fn compose_caption_below(source: &RgbaImage, caption: &str, font: &FontChain) -> Result<Composed, String> {
     let (src_w, src_h) = source.dimensions();
     let padding = 24u32;
     let font_size = 28.0;
@@ -133,7 +249,7 @@ fn compose_caption_below(source: &RgbaImage, caption: &str, font: &FontRef) -> R
 
     // Wrap text
     let max_text_width = src_w.saturating_sub(padding * 2);
-    let lines = wrap_text(caption, scale, max_text_width);
+    let lines = wrap_text(font, scale, caption, max_text_width);
     let text_height = (lines.len() as u32) * line_height + padding;
 
     // Create canvas
@@ -147,14 +263,14 @@ fn compose_caption_below(source: &RgbaImage, caption: &str, font: &FontRef) -> R
     let text_y_start = src_h + padding / 2;
     for (i, line) in lines.iter().enumerate() {
         let y = text_y_start + (i as u32 * line_height);
-        draw_text_mut(&mut canvas, TEXT_DARK, padding as i32, y as i32, scale, font, line);
+        font.draw_text(&mut canvas, TEXT_DARK, padding as i32, y as i32, scale, line);
     }
 
-    Ok(canvas)
+    Ok(Composed::new(canvas, 0, 0, 0))
 }
 
 /// Template: Card - 卡片式（带边框）
-fn compose_card(source: &RgbaImage, caption: &str, font: &FontRef) -> Result<RgbaImage, String> {
+fn compose_card(source: &RgbaImage, caption: &str, font: &FontChain) -> Result<Composed, String> {
     let (src_w, src_h) = source.dimensions();
     let card_padding = 20u32;
     let outer_padding = 32u32;
@@ -164,7 +280,7 @@ fn compose_card(source: &RgbaImage, caption: &str, font: &FontRef) -> Result<Rgb
 
     // Wrap text
     let max_text_width = src_w.saturating_sub(card_padding * 2);
-    let lines = wrap_text(caption, scale, max_text_width);
+    let lines = wrap_text(font, scale, caption, max_text_width);
     let text_block_height = if lines.is_empty() { 0 } else {
         (lines.len() as u32) * line_height + card_padding
     };
@@ -207,15 +323,15 @@ fn compose_card(source: &RgbaImage, caption: &str, font: &FontRef) -> Result<Rgb
         let text_y_start = img_y + src_h + card_padding / 2;
         for (i, line) in lines.iter().enumerate() {
             let y = text_y_start + (i as u32 * line_height);
-            draw_text_mut(&mut canvas, TEXT_DARK, img_x as i32, y as i32, scale, font, line);
+            font.draw_text(&mut canvas, TEXT_DARK, img_x as i32, y as i32, scale, line);
         }
     }
 
-    Ok(canvas)
+    Ok(Composed::new(canvas, img_x as i64, img_y as i64, 0))
 }
 
 /// Template: Minimal - 极简（小字号）
-fn compose_minimal(source: &RgbaImage, caption: &str, font: &FontRef) -> Result<RgbaImage, String> {
+fn compose_minimal(source: &RgbaImage, caption: &str, font: &FontChain) -> Result<Composed, String> {
     let (src_w, src_h) = source.dimensions();
     let font_size = 16.0;
     let scale = PxScale::from(font_size);
@@ -223,13 +339,7 @@ fn compose_minimal(source: &RgbaImage, caption: &str, font: &FontRef) -> Result<
 
     // Single line, truncate if too long
     let max_width = src_w.saturating_sub(padding * 2);
-    let mut display_text = caption.replace('\n', " ");
-    while measure_text_width(&display_text, scale) > max_width && display_text.len() > 3 {
-        display_text.pop();
-    }
-    if display_text.len() < caption.len() {
-        display_text.push_str("…");
-    }
+    let display_text = truncate_with_ellipsis(font, scale, &caption.replace('\n', " "), max_width);
 
     let text_height = (font_size * 1.5) as u32 + padding;
     let canvas_h = src_h + text_height;
@@ -239,16 +349,16 @@ fn compose_minimal(source: &RgbaImage, caption: &str, font: &FontRef) -> Result<
     image::imageops::overlay(&mut canvas, source, 0, 0);
 
     // Draw text centered
-    let text_width = measure_text_width(&display_text, scale);
-    let text_x = ((src_w - text_width) / 2) as i32;
+    let display_width = text_width(font, scale, &display_text).ceil() as u32;
+    let text_x = (src_w.saturating_sub(display_width) / 2) as i32;
     let text_y = (src_h + padding / 2) as i32;
-    draw_text_mut(&mut canvas, TEXT_MUTED, text_x, text_y, scale, font, &display_text);
+    font.draw_text(&mut canvas, TEXT_MUTED, text_x, text_y, scale, &display_text);
 
-    Ok(canvas)
+    Ok(Composed::new(canvas, 0, 0, 0))
 }
 
 /// Template: Social - 类似即刻/X 风格
-fn compose_social(source: &RgbaImage, caption: &str, font: &FontRef) -> Result<RgbaImage, String> {
+fn compose_social(source: &RgbaImage, caption: &str, font: &FontChain) -> Result<Composed, String> {
     let (src_w, src_h) = source.dimensions();
     let padding = 20u32;
     let font_size = 22.0;
@@ -258,7 +368,7 @@ fn compose_social(source: &RgbaImage, caption: &str, font: &FontRef) -> Result<R
     // Wrap text
     let content_width = src_w.max(320);
     let max_text_width = content_width.saturating_sub(padding * 2);
-    let lines = wrap_text(caption, scale, max_text_width);
+    let lines = wrap_text(font, scale, caption, max_text_width);
 
     // Text above image
     let text_block_height = if lines.is_empty() { 0 } else {
@@ -275,7 +385,7 @@ fn compose_social(source: &RgbaImage, caption: &str, font: &FontRef) -> Result<R
     // Draw text at top
     let mut y_offset = padding / 2;
     for line in &lines {
-        draw_text_mut(&mut canvas, TEXT_DARK, padding as i32, y_offset as i32, scale, font, line);
+        font.draw_text(&mut canvas, TEXT_DARK, padding as i32, y_offset as i32, scale, line);
         y_offset += line_height;
     }
 
@@ -288,21 +398,285 @@ fn compose_social(source: &RgbaImage, caption: &str, font: &FontRef) -> Result<R
     let watermark_y = text_block_height + src_h + 8;
     let watermark_scale = PxScale::from(14.0);
 
+    let via = "via lovshot";
+    let via_width = text_width(font, watermark_scale, via).ceil() as u32;
+
+    // Brand position: configured watermark (text/logo) sits before "via lovshot"
     let cfg = crate::config::load_config();
-    let watermark = if matches!(cfg.watermark_position, crate::config::WatermarkPosition::Brand) {
-        let count = crate::config::count_screenshots();
-        format!("#{} via lovshot", count)
+    let brand = if matches!(cfg.watermark_position, crate::config::WatermarkPosition::Brand) {
+        crate::watermark::brand_layer(crate::config::last_screenshot_number(), 14.0)
     } else {
-        "via lovshot".to_string()
+        None
     };
+    let gap = if brand.is_some() { 6 } else { 0 };
+    let brand_width = brand.as_ref().map(|(layer, _)| layer.width()).unwrap_or(0);
+    let wm_x = (canvas_w.saturating_sub(brand_width + gap + via_width) / 2) as i32;
 
-    let wm_width = measure_text_width(&watermark, watermark_scale);
-    let wm_x = ((canvas_w - wm_width) / 2) as i32;
-    draw_text_mut(&mut canvas, TEXT_MUTED, wm_x, watermark_y as i32, watermark_scale, font, &watermark);
+    if let Some((layer, opacity)) = brand {
+        let layer_y = watermark_y as i64 + 8 - layer.height() as i64 / 2;
+        crate::watermark::blend_layer(&mut canvas, &layer, wm_x as i64, layer_y, opacity);
+    }
+    let via_x = wm_x + (brand_width + gap) as i32;
+    font.draw_text(&mut canvas, TEXT_MUTED, via_x, watermark_y as i32, watermark_scale, via);
+
+    Ok(Composed::new(canvas, img_x, img_y, 0))
+}
+
+/// Template: user-defined (padding, background, corners, shadow, caption position)
+fn compose_custom(source: &RgbaImage, caption: &str, font: &FontChain, spec: &TemplateSpec) -> Result<Composed, String> {
+    let (src_w, src_h) = source.dimensions();
+    let padding = spec.padding;
+    let scale = PxScale::from(spec.font_size);
+    let line_height = (spec.font_size * 1.5) as u32;
+    let text_color = parse_hex_color(&spec.text_color).unwrap_or(TEXT_DARK);
+
+    let lines = if spec.caption_position == CaptionPosition::None {
+        Vec::new()
+    } else {
+        wrap_text(font, scale, caption, src_w)
+    };
+    let caption_gap = if lines.is_empty() { 0 } else { spec.font_size as u32 };
+    let caption_h = lines.len() as u32 * line_height + caption_gap;
+
+    // Watermark text sits in its own row under the content
+    let watermark = spec.watermark_text.as_deref().map(|text| {
+        let number = if text.contains("{number}") { crate::config::last_screenshot_number() } else { 0 };
+        render_watermark_text(text, number, chrono::Local::now())
+    }).filter(|t| !t.trim().is_empty());
+    let wm_size = (spec.font_size * 0.6).max(10.0);
+    let wm_h = if watermark.is_some() { (wm_size * 2.0) as u32 } else { 0 };
+
+    let canvas_w = src_w + padding * 2;
+    let canvas_h = src_h + caption_h + wm_h + padding * 2;
+    let mut canvas = RgbaImage::new(canvas_w, canvas_h);
+    fill_background(&mut canvas, &spec.background);
+
+    let (img_y, text_y) = match spec.caption_position {
+        CaptionPosition::Above => (padding + caption_h, padding),
+        _ => (padding, padding + src_h + caption_gap),
+    };
+
+    if let Some(ref shadow) = spec.shadow {
+        draw_shadow(&mut canvas, padding as i64, img_y as i64, src_w, src_h, spec.corner_radius, shadow);
+    }
+    let mut content = source.clone();
+    round_corners(&mut content, spec.corner_radius);
+    blend_layer(&mut canvas, &content, padding as i64, img_y as i64, 1.0);
+
+    for (i, line) in lines.iter().enumerate() {
+        let y = text_y + i as u32 * line_height;
+        font.draw_text(&mut canvas, text_color, padding as i32, y as i32, scale, line);
+    }
+
+    if let Some(text) = watermark {
+        let wm_scale = PxScale::from(wm_size);
+        let wm_w = text_width(font, wm_scale, &text).ceil() as u32;
+        let wm_x = (canvas_w - padding).saturating_sub(wm_w) as i32;
+        let wm_y = (canvas_h - padding - wm_h + (wm_h - wm_size as u32) / 2) as i32;
+        // Draw into a transparent layer so the muted text keeps the canvas opaque
+        let mut layer = RgbaImage::from_pixel(wm_w.max(1), wm_size.ceil() as u32, Rgba([text_color[0], text_color[1], text_color[2], 0]));
+        font.draw_text(&mut layer, text_color, 0, 0, wm_scale, &text);
+        blend_layer(&mut canvas, &layer, wm_x as i64, wm_y as i64, 0.6);
+    }
+
+    Ok(Composed::new(canvas, padding as i64, img_y as i64, spec.corner_radius))
+}
+
+/// Beautify: place a screenshot on a background with padding, rounded corners and shadow
+pub fn beautify_image(source: &RgbaImage, options: &BeautifyOptions) -> Result<RgbaImage, String> {
+    let mut content = if options.window_chrome {
+        add_window_chrome(source, options.chrome_title.as_deref())
+    } else {
+        source.clone()
+    };
+    round_corners(&mut content, options.corner_radius);
+
+    let (content_w, content_h) = content.dimensions();
+    let padding = options.padding;
+    let mut canvas = RgbaImage::new(content_w + padding * 2, content_h + padding * 2);
+    fill_background(&mut canvas, &options.background);
+
+    if let Some(ref shadow) = options.shadow {
+        draw_shadow(&mut canvas, padding as i64, padding as i64, content_w, content_h, options.corner_radius, shadow);
+    }
+    blend_layer(&mut canvas, &content, padding as i64, padding as i64, 1.0);
 
     Ok(canvas)
 }
 
+/// Add a macOS-style title bar (traffic lights + optional centered title) above an image
+fn add_window_chrome(source: &RgbaImage, title: Option<&str>) -> RgbaImage {
+    let (src_w, src_h) = source.dimensions();
+    // Title bar scales with the screenshot so Retina captures don't get a tiny bar
+    let bar_h = (src_w as f32 * 0.035).clamp(28.0, 64.0) as u32;
+    let mut framed = RgbaImage::from_pixel(src_w, src_h + bar_h, Rgba([232, 230, 227, 255]));
+    image::imageops::overlay(&mut framed, source, 0, bar_h as i64);
+
+    // Hairline between title bar and content
+    for x in 0..src_w {
+        framed.put_pixel(x, bar_h - 1, Rgba([210, 208, 204, 255]));
+    }
+
+    let r = bar_h as f32 * 0.21;
+    let cy = bar_h as f32 / 2.0;
+    let lights = [
+        Rgba([255, 95, 87, 255]),  // Close
+        Rgba([254, 188, 46, 255]), // Minimize
+        Rgba([40, 200, 64, 255]),  // Zoom
+    ];
+    for (i, color) in lights.iter().enumerate() {
+        let cx = bar_h as f32 * 0.5 + i as f32 * r * 3.2;
+        fill_circle_aa(&mut framed, cx, cy, r, *color);
+    }
+
+    if let Some(title) = title.map(str::trim).filter(|t| !t.is_empty()) {
+        if let Some(font) = font_chain(FontRole::Text) {
+            let scale = PxScale::from(bar_h as f32 * 0.45);
+            let max_w = src_w.saturating_sub(bar_h * 4);
+            let title = truncate_with_ellipsis(&font, scale, title, max_w);
+            let title_w = text_width(&font, scale, &title).ceil() as u32;
+            let x = (src_w.saturating_sub(title_w) / 2) as i32;
+            let y = (cy - scale.y / 2.0) as i32;
+            font.draw_text(&mut framed, Rgba([77, 77, 77, 255]), x, y, scale, &title);
+        }
+    }
+    framed
+}
+
+/// Anti-aliased filled circle, blended over the image
+pub(crate) fn fill_circle_aa(img: &mut RgbaImage, cx: f32, cy: f32, r: f32, color: Rgba<u8>) {
+    let (w, h) = img.dimensions();
+    let x0 = (cx - r - 1.0).max(0.0) as u32;
+    let y0 = (cy - r - 1.0).max(0.0) as u32;
+    let x1 = ((cx + r + 1.0).ceil() as u32).min(w);
+    let y1 = ((cy + r + 1.0).ceil() as u32).min(h);
+    let mut layer = RgbaImage::from_pixel(x1.saturating_sub(x0), y1.saturating_sub(y0), Rgba([color[0], color[1], color[2], 0]));
+    for (lx, ly, p) in layer.enumerate_pixels_mut() {
+        let dx = (x0 + lx) as f32 + 0.5 - cx;
+        let dy = (y0 + ly) as f32 + 0.5 - cy;
+        let coverage = (r - (dx * dx + dy * dy).sqrt() + 0.5).clamp(0.0, 1.0);
+        p[3] = (color[3] as f32 * coverage).round() as u8;
+    }
+    blend_layer(img, &layer, x0 as i64, y0 as i64, 1.0);
+}
+
+/// Fill the canvas with a solid colour, linear gradient or cover-scaled image
+pub(crate) fn fill_background(canvas: &mut RgbaImage, background: &Background) {
+    match background {
+        Background::Image { path } => match image::open(path) {
+            Ok(img) => {
+                let (w, h) = canvas.dimensions();
+                let cover = img.resize_to_fill(w, h, image::imageops::FilterType::Triangle).to_rgba8();
+                image::imageops::replace(canvas, &cover, 0, 0);
+            }
+            Err(e) => {
+                println!("[share_compose] Failed to load background {}: {}", path, e);
+                fill_background(canvas, &Background::default());
+            }
+        },
+        Background::Solid { color } => {
+            let color = parse_hex_color(color).unwrap_or(BG_WARM);
+            for p in canvas.pixels_mut() {
+                *p = color;
+            }
+        }
+        Background::Gradient { from, to, angle } => {
+            let from = parse_hex_color(from).unwrap_or(BG_WARM);
+            let to = parse_hex_color(to).unwrap_or(BG_WARM);
+            let (w, h) = (canvas.width() as f32, canvas.height() as f32);
+            let (dx, dy) = (angle.to_radians().cos(), angle.to_radians().sin());
+            // Project corners onto the gradient axis to normalise t into 0..1
+            let extent = (w * dx.abs() + h * dy.abs()).max(1.0);
+            for (x, y, p) in canvas.enumerate_pixels_mut() {
+                let px = x as f32 + 0.5 - w / 2.0;
+                let py = y as f32 + 0.5 - h / 2.0;
+                let t = ((px * dx + py * dy) / extent + 0.5).clamp(0.0, 1.0);
+                for c in 0..4 {
+                    p[c] = (from[c] as f32 + (to[c] as f32 - from[c] as f32) * t).round() as u8;
+                }
+            }
+        }
+    }
+}
+
+/// Anti-aliased coverage of a rounded rectangle at pixel (x, y)
+fn rounded_rect_coverage(x: u32, y: u32, w: u32, h: u32, radius: f32) -> f32 {
+    let r = radius.min(w as f32 / 2.0).min(h as f32 / 2.0);
+    let px = x as f32 + 0.5;
+    let py = y as f32 + 0.5;
+    let cx = px.clamp(r, w as f32 - r);
+    let cy = py.clamp(r, h as f32 - r);
+    let dist = ((px - cx).powi(2) + (py - cy).powi(2)).sqrt();
+    (r - dist + 0.5).clamp(0.0, 1.0)
+}
+
+/// Make the corners of an image transparent
+pub(crate) fn round_corners(img: &mut RgbaImage, radius: u32) {
+    if radius == 0 {
+        return;
+    }
+    let (w, h) = img.dimensions();
+    let r = radius as f32;
+    for (x, y, p) in img.enumerate_pixels_mut() {
+        // Only the corner squares can be affected
+        let in_corner = (x < radius || x >= w.saturating_sub(radius)) && (y < radius || y >= h.saturating_sub(radius));
+        if in_corner {
+            let coverage = rounded_rect_coverage(x, y, w, h, r);
+            p[3] = (p[3] as f32 * coverage).round() as u8;
+        }
+    }
+}
+
+/// Blurred drop shadow for a (rounded) rect placed at (x, y)
+pub(crate) fn draw_shadow(canvas: &mut RgbaImage, x: i64, y: i64, w: u32, h: u32, radius: u32, shadow: &ShadowSpec) {
+    let color = parse_hex_color(&shadow.color).unwrap_or(Rgba([0, 0, 0, 80]));
+    let margin = (shadow.blur * 2.0).ceil() as u32;
+    let mut layer = RgbaImage::from_pixel(w + margin * 2, h + margin * 2, Rgba([color[0], color[1], color[2], 0]));
+    for sy in 0..h {
+        for sx in 0..w {
+            let coverage = rounded_rect_coverage(sx, sy, w, h, radius as f32);
+            layer.put_pixel(sx + margin, sy + margin, Rgba([color[0], color[1], color[2], (color[3] as f32 * coverage).round() as u8]));
+        }
+    }
+    if shadow.blur > 0.0 {
+        layer = image::imageops::blur(&layer, shadow.blur / 2.0);
+    }
+    let lx = x + shadow.offset_x as i64 - margin as i64;
+    let ly = y + shadow.offset_y as i64 - margin as i64;
+    blend_layer(canvas, &layer, lx, ly, 1.0);
+}
+
+/// Blurred drop shadow following the alpha of an arbitrary shape placed at (x, y)
+pub(crate) fn draw_shape_shadow(canvas: &mut RgbaImage, shape: &RgbaImage, x: i64, y: i64, shadow: &ShadowSpec) {
+    let color = parse_hex_color(&shadow.color).unwrap_or(Rgba([0, 0, 0, 80]));
+    let margin = (shadow.blur * 2.0).ceil() as u32;
+    let (w, h) = shape.dimensions();
+    let mut layer = RgbaImage::from_pixel(w + margin * 2, h + margin * 2, Rgba([color[0], color[1], color[2], 0]));
+    for (sx, sy, p) in shape.enumerate_pixels() {
+        let a = (color[3] as u32 * p[3] as u32 / 255) as u8;
+        layer.put_pixel(sx + margin, sy + margin, Rgba([color[0], color[1], color[2], a]));
+    }
+    if shadow.blur > 0.0 {
+        layer = image::imageops::blur(&layer, shadow.blur / 2.0);
+    }
+    let lx = x + shadow.offset_x as i64 - margin as i64;
+    let ly = y + shadow.offset_y as i64 - margin as i64;
+    blend_layer(canvas, &layer, lx, ly, 1.0);
+}
+
+/// Anti-aliased filled rounded rectangle, blended over the image
+pub(crate) fn fill_rounded_rect(img: &mut RgbaImage, x: i64, y: i64, w: u32, h: u32, radius: f32, color: Rgba<u8>) {
+    if w == 0 || h == 0 {
+        return;
+    }
+    let mut layer = RgbaImage::from_pixel(w, h, Rgba([color[0], color[1], color[2], 0]));
+    for (lx, ly, p) in layer.enumerate_pixels_mut() {
+        let coverage = rounded_rect_coverage(lx, ly, w, h, radius);
+        p[3] = (color[3] as f32 * coverage).round() as u8;
+    }
+    blend_layer(img, &layer, x, y, 1.0);
+}
+
 /// Tauri command: compose and save share image
 #[tauri::command]
 pub fn compose_share(
@@ -311,17 +685,59 @@ pub fn compose_share(
     caption: String,
     template: String,
 ) -> Result<String, String> {
-    use tauri_plugin_clipboard_manager::ClipboardExt;
+    let template = ShareTemplate::from_id(&template).unwrap_or_else(|| {
+        println!("[compose_share] Unknown template '{}', using caption_below", template);
+        ShareTemplate::CaptionBelow
+    });
+
+    // Animated GIFs keep every frame; the clipboard only holds stills, so these go to the library only
+    if let Some((frames, delays)) = load_gif_animation(&source_path) {
+        println!("[compose_share] Animated source, {} frames", frames.len());
+        let composed = compose_share_frames(&frames, &caption, template)?;
+        let path = save_animated_share(&composed, &delays, "share")?;
+        println!("[compose_share] Saved animation to {:?}", path);
+        return Ok(path.to_string_lossy().to_string());
+    }
 
-    let template = match template.as_str() {
-        "caption_below" => ShareTemplate::CaptionBelow,
-        "card" => ShareTemplate::Card,
-        "minimal" => ShareTemplate::Minimal,
-        "social" => ShareTemplate::Social,
-        _ => ShareTemplate::CaptionBelow,
-    };
+    let composed = compose_share_image(&source_path, &caption, template)?;
+    deliver_share(&app, &composed, "share")
+}
 
+/// Tauri command: render a template as a PNG data URL for the share picker (nothing is saved)
+#[tauri::command]
+pub fn preview_share_template(source_path: String, caption: String, template: String) -> Result<String, String> {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    let template = ShareTemplate::from_id(&template).ok_or_else(|| format!("Unknown template: {}", template))?;
     let composed = compose_share_image(&source_path, &caption, template)?;
+    let png = crate::annotation_layers::encode_png(&composed)?;
+    Ok(format!("data:image/png;base64,{}", STANDARD.encode(png)))
+}
+
+/// Tauri command: beautify a screenshot (background, padding, corners, shadow, chrome)
+#[tauri::command]
+pub fn beautify_share(
+    app: tauri::AppHandle,
+    source_path: String,
+    options: Option<BeautifyOptions>,
+) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    if let Background::Image { ref path } = options.background {
+        if !std::path::Path::new(path).is_file() {
+            return Err(format!("Background image not found: {}", path));
+        }
+    }
+    let source = image::open(&source_path)
+        .map_err(|e| format!("Failed to open image: {}", e))?
+        .to_rgba8();
+
+    let composed = beautify_image(&source, &options)?;
+    deliver_share(&app, &composed, "beautify")
+}
+
+/// Copy a composed image to the clipboard and save it into the library
+pub(crate) fn deliver_share(app: &tauri::AppHandle, composed: &RgbaImage, mode: &str) -> Result<String, String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
 
     // Copy to clipboard
     let tauri_image = tauri::image::Image::new_owned(
@@ -333,16 +749,109 @@ pub fn compose_share(
         .map_err(|e| format!("Clipboard error: {}", e))?;
 
     // Save to file
-    let output_dir = dirs::picture_dir()
-        .or_else(|| dirs::home_dir())
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("lovshot");
-    std::fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
-
-    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-    let filename = output_dir.join(format!("share_{}.png", timestamp));
-    composed.save(&filename).map_err(|e| format!("Save error: {}", e))?;
+    let filename = build_output_path(OutputKind::Share, FilenameContext::new(mode), "png")?;
+    let filename = save_image_unique(composed, &filename).map_err(|e| format!("Save error: {}", e))?;
 
-    println!("[compose_share] Saved to {:?}", filename);
+    println!("[{}] Saved to {:?}", mode, filename);
     Ok(filename.to_string_lossy().to_string())
 }
+
+/// Tauri command: built-in and user templates for the share picker
+#[tauri::command]
+pub fn list_share_templates() -> Vec<ShareTemplateInfo> {
+    let mut templates: Vec<ShareTemplateInfo> = BUILTIN_TEMPLATES
+        .iter()
+        .map(|(id, name)| ShareTemplateInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            builtin: true,
+            path: None,
+        })
+        .collect();
+
+    for (spec, path) in load_custom_templates() {
+        if BUILTIN_TEMPLATES.iter().any(|(id, _)| *id == spec.id) {
+            println!("[share_template] '{}' shadows a built-in template, skipping", spec.id);
+            continue;
+        }
+        templates.push(ShareTemplateInfo {
+            id: spec.id,
+            name: spec.name,
+            builtin: false,
+            path: Some(path.to_string_lossy().to_string()),
+        });
+    }
+    templates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Rgba<u8> = Rgba([220, 40, 40, 255]);
+
+    fn transparent_options() -> BeautifyOptions {
+        BeautifyOptions {
+            background: Background::Solid { color: "#00000000".to_string() },
+            padding: 20,
+            corner_radius: 10,
+            shadow: None,
+            window_chrome: false,
+            chrome_title: None,
+        }
+    }
+
+    #[test]
+    fn test_beautify_canvas_and_rounded_corners() {
+        let source = RgbaImage::from_pixel(120, 80, RED);
+        let out = beautify_image(&source, &transparent_options()).unwrap();
+
+        assert_eq!(out.dimensions(), (120 + 40, 80 + 40));
+        // Rounded corners cut into the screenshot and let the background through
+        assert_eq!(out.get_pixel(20, 20)[3], 0);
+        assert_eq!(out.get_pixel(20 + 119, 20 + 79)[3], 0);
+        assert_eq!(*out.get_pixel(20 + 60, 20 + 40), RED);
+        assert_eq!(*out.get_pixel(20 + 60, 20), RED); // Edge midpoints stay square
+        assert_eq!(out.get_pixel(5, 5)[3], 0);
+
+        // Window chrome adds its title bar above the content
+        let chrome = beautify_image(&source, &BeautifyOptions { window_chrome: true, ..transparent_options() }).unwrap();
+        assert_eq!(chrome.dimensions(), (160, 80 + 28 + 40));
+        assert_eq!(*chrome.get_pixel(20 + 60, 20 + 28 + 40), RED);
+    }
+
+    #[test]
+    fn test_share_frames_keep_offset_per_frame() {
+        if font_chain(FontRole::Text).is_none() {
+            println!("no fonts, skipping");
+            return;
+        }
+        let colors = [RED, Rgba([40, 160, 60, 255]), Rgba([40, 80, 220, 255])];
+        let frames: Vec<RgbaImage> = colors.iter().map(|&c| RgbaImage::from_pixel(200, 120, c)).collect();
+
+        let composed = compose_share_frames(&frames, "caption", ShareTemplate::Card).unwrap();
+        assert_eq!(composed.len(), frames.len());
+
+        // Each frame lands exactly where a still compose puts the image
+        for (frame, out) in frames.iter().zip(&composed) {
+            let still = compose_share_rgba(frame, "caption", ShareTemplate::Card).unwrap();
+            assert_eq!(out.dimensions(), still.dimensions());
+            assert!(out == &still);
+        }
+
+        // Frames differ only inside one source-sized rect, the same for every frame
+        let diff_box = |a: &RgbaImage, b: &RgbaImage| {
+            let (mut x0, mut y0, mut x1, mut y1) = (u32::MAX, u32::MAX, 0, 0);
+            for (x, y, p) in a.enumerate_pixels() {
+                if p != b.get_pixel(x, y) {
+                    (x0, y0, x1, y1) = (x0.min(x), y0.min(y), x1.max(x + 1), y1.max(y + 1));
+                }
+            }
+            (x0, y0, x1 - x0, y1 - y0)
+        };
+        let first = diff_box(&composed[0], &composed[1]);
+        assert_eq!((first.2, first.3), (200, 120));
+        assert!(first.0 > 0 && first.1 > 0, "card template should offset the image");
+        assert_eq!(diff_box(&composed[1], &composed[2]), first);
+    }
+}
@@ -0,0 +1,258 @@
+//! User-defined share templates
+//!
+//! Templates live in `<config dir>/lovshot/templates/` as `.json` or `.toml`
+//! files. The id is the file stem unless the file sets `id` itself.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::config;
+use crate::watermark::parse_hex_color;
+
+/// Canvas background
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Background {
+    Solid {
+        color: String,
+    },
+    /// Linear gradient; angle in degrees, 0 = left to right, 90 = top to bottom
+    Gradient {
+        from: String,
+        to: String,
+        #[serde(default)]
+        angle: f32,
+    },
+    /// Image file scaled to cover the canvas
+    Image {
+        path: String,
+    },
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::Solid {
+            color: "#F9F9F7".to_string(),
+        }
+    }
+}
+
+/// Where the caption goes relative to the screenshot
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptionPosition {
+    Above,
+    Below,
+    None,
+}
+
+/// Drop shadow under the screenshot
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ShadowSpec {
+    #[serde(default = "default_shadow_blur")]
+    pub blur: f32,
+    #[serde(default)]
+    pub offset_x: i32,
+    #[serde(default = "default_shadow_offset_y")]
+    pub offset_y: i32,
+    #[serde(default = "default_shadow_color")]
+    pub color: String,
+}
+
+impl Default for ShadowSpec {
+    fn default() -> Self {
+        Self {
+            blur: default_shadow_blur(),
+            offset_x: 0,
+            offset_y: default_shadow_offset_y(),
+            color: default_shadow_color(),
+        }
+    }
+}
+
+fn default_shadow_blur() -> f32 {
+    16.0
+}
+
+fn default_shadow_offset_y() -> i32 {
+    8
+}
+
+fn default_shadow_color() -> String {
+    "#00000050".to_string()
+}
+
+/// A share layout loaded from a template file
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TemplateSpec {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "default_padding")]
+    pub padding: u32,
+    #[serde(default)]
+    pub background: Background,
+    #[serde(default)]
+    pub corner_radius: u32,
+    #[serde(default)]
+    pub shadow: Option<ShadowSpec>,
+    #[serde(default = "default_caption_position")]
+    pub caption_position: CaptionPosition,
+    #[serde(default = "default_font_size")]
+    pub font_size: f32,
+    #[serde(default = "default_text_color")]
+    pub text_color: String,
+    #[serde(default)]
+    pub watermark_text: Option<String>, // Placeholders as in the watermark config
+}
+
+fn default_padding() -> u32 {
+    48
+}
+
+fn default_caption_position() -> CaptionPosition {
+    CaptionPosition::Below
+}
+
+fn default_font_size() -> f32 {
+    24.0
+}
+
+fn default_text_color() -> String {
+    "#181818".to_string()
+}
+
+impl TemplateSpec {
+    /// Reject values that would fail at render time
+    pub fn validate(&self) -> Result<(), String> {
+        let mut colors = vec![self.text_color.as_str()];
+        match &self.background {
+            Background::Solid { color } => colors.push(color),
+            Background::Gradient { from, to, .. } => {
+                colors.push(from);
+                colors.push(to);
+            }
+            Background::Image { path } => {
+                if !Path::new(path).is_file() {
+                    return Err(format!("Background image not found: {}", path));
+                }
+            }
+        }
+        if let Some(ref shadow) = self.shadow {
+            colors.push(&shadow.color);
+            if shadow.blur < 0.0 {
+                return Err("Shadow blur must not be negative".to_string());
+            }
+        }
+        if let Some(bad) = colors.into_iter().find(|c| parse_hex_color(c).is_none()) {
+            return Err(format!("Invalid color: {}", bad));
+        }
+        if !(6.0..=200.0).contains(&self.font_size) {
+            return Err(format!("Font size out of range: {}", self.font_size));
+        }
+        Ok(())
+    }
+}
+
+/// Template entry shown in the share picker
+#[derive(Clone, Debug, Serialize)]
+pub struct ShareTemplateInfo {
+    pub id: String,
+    pub name: String,
+    pub builtin: bool,
+    pub path: Option<String>,
+}
+
+/// Directory scanned for template files
+pub fn templates_dir() -> PathBuf {
+    config::get_config_path().with_file_name("templates")
+}
+
+/// Parse a single template file (.json or .toml)
+pub fn parse_template_file(path: &Path) -> Result<TemplateSpec, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    let mut spec: TemplateSpec = match ext.as_str() {
+        "json" => serde_json::from_str(&content).map_err(|e| e.to_string())?,
+        "toml" => toml::from_str(&content).map_err(|e| e.to_string())?,
+        _ => return Err(format!("Unsupported template format: {}", ext)),
+    };
+
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("template");
+    if spec.id.trim().is_empty() {
+        spec.id = stem.to_string();
+    }
+    if spec.name.trim().is_empty() {
+        spec.name = spec.id.clone();
+    }
+    spec.validate()?;
+    Ok(spec)
+}
+
+/// All valid templates in the templates dir, sorted by id, with their source files
+pub fn load_custom_templates() -> Vec<(TemplateSpec, PathBuf)> {
+    let entries = match std::fs::read_dir(templates_dir()) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut templates: Vec<(TemplateSpec, PathBuf)> = Vec::new();
+    for path in entries.flatten().map(|e| e.path()) {
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+        if ext != "json" && ext != "toml" {
+            continue;
+        }
+        match parse_template_file(&path) {
+            Ok(spec) => {
+                if templates.iter().any(|(t, _)| t.id == spec.id) {
+                    println!("[share_template] Duplicate id '{}' in {:?}, skipping", spec.id, path);
+                    continue;
+                }
+                templates.push((spec, path));
+            }
+            Err(e) => println!("[share_template] Invalid template {:?}: {}", path, e),
+        }
+    }
+    templates.sort_by(|a, b| a.0.id.cmp(&b.0.id));
+    templates
+}
+
+/// Look up a user template by id
+pub fn find_template(id: &str) -> Option<TemplateSpec> {
+    load_custom_templates()
+        .into_iter()
+        .map(|(spec, _)| spec)
+        .find(|spec| spec.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_toml_and_json_templates() {
+        let dir = std::env::temp_dir().join(format!("lovshot_templates_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let toml_path = dir.join("brand.toml");
+        std::fs::write(
+            &toml_path,
+            "padding = 64\ncaption_position = \"above\"\n\
+             background = { type = \"gradient\", from = \"#CC785C\", to = \"#181818\", angle = 90 }\n\
+             [shadow]\nblur = 24\n",
+        )
+        .unwrap();
+        let spec = parse_template_file(&toml_path).unwrap();
+        assert_eq!(spec.id, "brand");
+        assert_eq!(spec.padding, 64);
+        assert_eq!(spec.caption_position, CaptionPosition::Above);
+        assert_eq!(spec.shadow.unwrap().offset_y, 8);
+
+        let json_path = dir.join("bad.json");
+        std::fs::write(&json_path, r##"{"id": "x", "text_color": "#zzzzzz"}"##).unwrap();
+        assert!(parse_template_file(&json_path).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -3,6 +3,7 @@ use tauri_plugin_global_shortcut::GlobalShortcutExt;
 use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut};
 
 use crate::config;
+use crate::presets::PRESET_ACTION_PREFIX;
 use crate::types::CaptureMode;
 
 /// Parse shortcut string to Shortcut struct (e.g., "Alt+A" -> Shortcut)
@@ -126,6 +127,34 @@ pub fn is_show_main_shortcut(shortcut: &Shortcut) -> bool {
     is_shortcut_for_action(shortcut, "show_main")
 }
 
+/// Check if a shortcut is a repeat_last shortcut
+pub fn is_repeat_last_shortcut(shortcut: &Shortcut) -> bool {
+    is_shortcut_for_action(shortcut, "repeat_last")
+}
+
+/// Check if a shortcut is a delayed_screenshot shortcut
+pub fn is_delayed_screenshot_shortcut(shortcut: &Shortcut) -> bool {
+    is_shortcut_for_action(shortcut, "delayed_screenshot")
+}
+
+/// Region preset bound to a shortcut (actions named "preset:<id>")
+pub fn preset_for_shortcut(shortcut: &Shortcut) -> Option<String> {
+    let config = config::load_config();
+    for (action, shortcuts) in &config.shortcuts {
+        let Some(id) = action.strip_prefix(PRESET_ACTION_PREFIX) else {
+            continue;
+        };
+        let matched = shortcuts
+            .iter()
+            .filter(|cfg| cfg.enabled)
+            .any(|cfg| parse_shortcut(&cfg.to_shortcut_string()).is_ok_and(|parsed| &parsed == shortcut));
+        if matched {
+            return Some(id.to_string());
+        }
+    }
+    None
+}
+
 /// Generic check if a shortcut matches an action
 fn is_shortcut_for_action(shortcut: &Shortcut, action: &str) -> bool {
     let config = config::load_config();
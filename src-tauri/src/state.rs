@@ -1,4 +1,5 @@
 use crate::types::{CaptureMode, Region};
+use crate::window_capture::WindowEntry;
 use image::RgbaImage;
 use std::sync::{Arc, Mutex};
 
@@ -16,6 +17,10 @@ pub struct AppState {
     pub magnifier_snapshot: Option<String>,    // base64 screenshot for magnifier
     pub shortcuts_paused_for_editing: bool,
     pub shortcuts_paused_for_tray_menu: bool,
+    pub pending_delay: Option<u32>,   // Selector opened for a delayed screenshot (seconds)
+    pub countdown_active: bool,       // Delayed capture counting down; cleared to cancel
+    pub timelapse_active: bool,       // Interval capture running; cleared to stop
+    pub window_cache: Vec<WindowEntry>, // Windows listed when the selector opened, topmost first
     // Scroll capture state
     pub scroll_capturing: bool,
     pub scroll_frames: Vec<RgbaImage>,
@@ -39,6 +44,10 @@ impl Default for AppState {
             magnifier_snapshot: None,
             shortcuts_paused_for_editing: false,
             shortcuts_paused_for_tray_menu: false,
+            pending_delay: None,
+            countdown_active: false,
+            timelapse_active: false,
+            window_cache: Vec::new(),
             scroll_capturing: false,
             scroll_frames: Vec::new(),
             scroll_offsets: Vec::new(),
@@ -0,0 +1,163 @@
+//! Text measurement and line breaking for rendered captions
+//!
+//! Widths come from the font chain's real advance widths and kerning (the
+//! same metrics it draws with), so wrapped lines fit what is drawn.
+
+use ab_glyph::PxScale;
+
+use crate::fonts::FontChain;
+
+const ELLIPSIS: &str = "…";
+
+/// Characters that must not start a line (closing punctuation, small kana...)
+const NO_LINE_START: &str = ",.!?:;)]}%'\"»›、。，．！？：；）］｝〕〉》」』】〙〗〟’”｠ー…‥・ゝゞ々ぁぃぅぇぉっゃゅょゎァィゥェォッャュョヮヵヶ゛゜";
+
+/// Characters that must not end a line (opening brackets, currency...)
+const NO_LINE_END: &str = "([{'\"«‹（［｛〔〈《「『【〘〖〝‘“｟$£¥€＄￥";
+
+/// Width of a single line in pixels, using advance widths and kerning
+pub fn text_width(font: &FontChain, scale: PxScale, text: &str) -> f32 {
+    font.text_width(scale, text)
+}
+
+/// Wrap text to `max_width` pixels using real glyph metrics
+pub fn wrap_text(font: &FontChain, scale: PxScale, text: &str, max_width: u32) -> Vec<String> {
+    wrap_with(text, max_width as f32, |s| font.text_width(scale, s))
+}
+
+/// Shorten a single line with a trailing "…" so it fits `max_width` pixels
+pub fn truncate_with_ellipsis(font: &FontChain, scale: PxScale, text: &str, max_width: u32) -> String {
+    truncate_with(text, max_width as f32, |s| font.text_width(scale, s))
+}
+
+/// CJK ideographs, kana, hangul and fullwidth forms may break between any two characters
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x11FF     // Hangul Jamo
+        | 0x2E80..=0x2FFF   // CJK radicals, Kangxi
+        | 0x3000..=0x30FF   // CJK punctuation, Hiragana, Katakana
+        | 0x3100..=0x31FF   // Bopomofo, Katakana ext
+        | 0x3400..=0x4DBF   // CJK ext A
+        | 0x4E00..=0x9FFF   // CJK unified
+        | 0xAC00..=0xD7AF   // Hangul syllables
+        | 0xF900..=0xFAFF   // CJK compatibility
+        | 0xFE30..=0xFE4F   // CJK compatibility forms
+        | 0xFF00..=0xFFEF   // Halfwidth and fullwidth forms
+        | 0x20000..=0x2FFFF // CJK ext B+
+    )
+}
+
+/// Whether a line may break between `prev` and `next`
+fn can_break_between(prev: char, next: char) -> bool {
+    if next.is_whitespace() || NO_LINE_START.contains(next) || NO_LINE_END.contains(prev) {
+        return false;
+    }
+    prev.is_whitespace() || is_cjk(prev) || is_cjk(next) || (prev == '-' && next.is_alphanumeric())
+}
+
+/// Split a paragraph into unbreakable segments (words, CJK characters with their punctuation)
+fn segments(paragraph: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    let mut prev: Option<char> = None;
+    for (i, c) in paragraph.char_indices() {
+        if let Some(p) = prev {
+            if can_break_between(p, c) {
+                result.push(&paragraph[start..i]);
+                start = i;
+            }
+        }
+        prev = Some(c);
+    }
+    if start < paragraph.len() {
+        result.push(&paragraph[start..]);
+    }
+    result
+}
+
+/// Greedy line breaking with a caller-supplied width function
+fn wrap_with(text: &str, max_width: f32, measure: impl Fn(&str) -> f32) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+
+        for segment in segments(paragraph) {
+            let candidate = format!("{}{}", line, segment);
+            if line.is_empty() || measure(candidate.trim_end()) <= max_width {
+                line = candidate;
+            } else {
+                lines.push(line.trim_end().to_string());
+                line = segment.trim_start().to_string();
+            }
+
+            // A single word wider than the line: fall back to breaking between characters
+            while measure(line.trim_end()) > max_width && line.chars().count() > 1 {
+                let mut head = String::new();
+                for c in line.chars() {
+                    head.push(c);
+                    if head.chars().count() > 1 && measure(&head) > max_width {
+                        head.pop();
+                        break;
+                    }
+                }
+                let rest = line[head.len()..].trim_start().to_string();
+                lines.push(head);
+                line = rest;
+            }
+        }
+
+        lines.push(line.trim_end().to_string());
+    }
+
+    // Drop trailing blank lines (e.g. caption ending with '\n')
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+fn truncate_with(text: &str, max_width: f32, measure: impl Fn(&str) -> f32) -> String {
+    if measure(text) <= max_width {
+        return text.to_string();
+    }
+    let mut chars: Vec<char> = text.chars().collect();
+    while !chars.is_empty() {
+        chars.pop();
+        let head: String = chars.iter().collect();
+        let candidate = format!("{}{}", head.trim_end(), ELLIPSIS);
+        if measure(&candidate) <= max_width {
+            return candidate;
+        }
+    }
+    ELLIPSIS.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Monospace stand-in: ASCII = 1, everything else = 2
+    fn mono(s: &str) -> f32 {
+        s.chars().map(|c| if c.is_ascii() { 1.0 } else { 2.0 }).sum()
+    }
+
+    #[test]
+    fn test_wraps_latin_at_word_boundaries() {
+        let lines = wrap_with("the quick brown fox", 10.0, mono);
+        assert_eq!(lines, vec!["the quick", "brown fox"]);
+    }
+
+    #[test]
+    fn test_cjk_keeps_punctuation_off_line_start() {
+        // By width alone "，" would start the second line
+        let lines = wrap_with("你好，世界", 5.0, mono);
+        assert_eq!(lines, vec!["你", "好，", "世界"]);
+    }
+
+    #[test]
+    fn test_ellipsis_truncation() {
+        assert_eq!(truncate_with("hello world", 20.0, mono), "hello world");
+        assert_eq!(truncate_with("hello world", 8.0, mono), "hello…");
+    }
+}
@@ -1,9 +1,11 @@
 use crate::capture::Screen;
 use crate::config;
 use tauri::image::Image as TauriImage;
-use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::menu::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindowBuilder};
 
+use crate::presets::PRESET_ACTION_PREFIX;
+use crate::state::SharedState;
 use crate::types::Region;
 
 /// Build tray menu with current shortcuts from config
@@ -34,6 +36,16 @@ pub fn build_tray_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, tauri::Error
         .and_then(|v| v.first())
         .map(|s| s.to_shortcut_string())
         .unwrap_or_else(|| "Alt+S".to_string());
+    let repeat_last_shortcut = cfg
+        .shortcuts
+        .get("repeat_last")
+        .and_then(|v| v.iter().find(|s| s.enabled))
+        .map(|s| s.to_shortcut_string());
+    let delayed_shortcut = cfg
+        .shortcuts
+        .get("delayed_screenshot")
+        .and_then(|v| v.iter().find(|s| s.enabled))
+        .map(|s| s.to_shortcut_string());
     let show_main_shortcut = cfg
         .shortcuts
         .get("show_main")
@@ -67,6 +79,45 @@ pub fn build_tray_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, tauri::Error
         false,
         Some(video_shortcut.as_str()),
     )?;
+    let menu_repeat_last = MenuItem::with_id(
+        app,
+        "repeat_last",
+        "Repeat Last Capture",
+        true,
+        repeat_last_shortcut.as_deref(),
+    )?;
+    // Preset ids double as menu ids and shortcut actions ("preset:<id>")
+    let preset_items = cfg
+        .region_presets
+        .iter()
+        .map(|preset| {
+            let action = format!("{}{}", PRESET_ACTION_PREFIX, preset.id);
+            let accelerator = cfg
+                .shortcuts
+                .get(&action)
+                .and_then(|v| v.iter().find(|s| s.enabled))
+                .map(|s| s.to_shortcut_string());
+            MenuItem::with_id(app, action.clone(), &preset.name, true, accelerator.as_deref())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let preset_refs: Vec<&dyn IsMenuItem<tauri::Wry>> =
+        preset_items.iter().map(|item| item as &dyn IsMenuItem<tauri::Wry>).collect();
+    let menu_presets = Submenu::with_items(app, "Region Presets", !preset_refs.is_empty(), &preset_refs)?;
+    let menu_delayed = MenuItem::with_id(
+        app,
+        "delayed_screenshot",
+        &format!("Screenshot in {}s", cfg.capture_delay_secs),
+        true,
+        delayed_shortcut.as_deref(),
+    )?;
+    let timelapse_active = app.state::<SharedState>().lock().unwrap().timelapse_active;
+    let menu_timelapse = MenuItem::with_id(
+        app,
+        "timelapse",
+        if timelapse_active { "Stop Timelapse" } else { "Start Timelapse" },
+        true,
+        None::<&str>,
+    )?;
     let menu_sep1 = PredefinedMenuItem::separator(app)?;
     let menu_settings = MenuItem::with_id(app, "settings", "Settings...", true, None::<&str>)?;
     let menu_sep2 = PredefinedMenuItem::separator(app)?;
@@ -82,9 +133,13 @@ pub fn build_tray_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, tauri::Error
             &menu_devtools,
             &menu_sep0,
             &menu_screenshot,
+            &menu_delayed,
             &menu_gif,
             &menu_scroll,
             &menu_video,
+            &menu_repeat_last,
+            &menu_timelapse,
+            &menu_presets,
             &menu_sep1,
             &menu_settings,
             &menu_sep2,
@@ -135,29 +190,49 @@ pub fn update_tray_icon(app: &AppHandle, is_recording: bool) {
 
 /// Create recording border overlay window
 pub fn create_recording_overlay(app: &AppHandle, region: &Region, static_mode: bool) {
+    open_overlay_window(app, region, if static_mode { "&static=1" } else { "" });
+}
+
+/// Create the countdown overlay for a delayed capture (same window as the recording border)
+pub fn create_countdown_overlay(app: &AppHandle, region: &Region, seconds: u32) {
+    open_overlay_window(app, region, &format!("&countdown={}", seconds));
+}
+
+/// Transparent click-through overlay covering the screen that holds the region
+fn open_overlay_window(app: &AppHandle, region: &Region, extra_query: &str) {
     if app.get_webview_window("recording-overlay").is_some() {
         return;
     }
 
     let screens = Screen::all().unwrap_or_default();
-    if screens.is_empty() {
+    let cx = region.x + region.width as i32 / 2;
+    let cy = region.y + region.height as i32 / 2;
+    let Some(screen) = screens
+        .iter()
+        .find(|s| {
+            let d = &s.display_info;
+            cx >= d.x && cx < d.x + d.width as i32 && cy >= d.y && cy < d.y + d.height as i32
+        })
+        .or_else(|| screens.first())
+    else {
         return;
-    }
+    };
 
-    let screen = &screens[0];
     let scale = screen.display_info.scale_factor;
     let screen_x = screen.display_info.x;
     let screen_y = screen.display_info.y;
     let width = screen.display_info.width;
     let height = screen.display_info.height;
 
-    let mut url = format!(
-        "/overlay.html?x={}&y={}&w={}&h={}",
-        region.x, region.y, region.width, region.height
+    // The overlay page positions the region relative to its own window
+    let url = format!(
+        "/overlay.html?x={}&y={}&w={}&h={}{}",
+        region.x - screen_x,
+        region.y - screen_y,
+        region.width,
+        region.height,
+        extra_query
     );
-    if static_mode {
-        url.push_str("&static=1");
-    }
 
     let win = WebviewWindowBuilder::new(app, "recording-overlay", WebviewUrl::App(url.into()))
         .title("Recording Overlay")
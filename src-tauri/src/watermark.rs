@@ -0,0 +1,233 @@
+//! Screenshot watermark rendering
+//!
+//! Renders the configured text/logo watermark once into a transparent layer,
+//! which is then blended onto screenshots, every GIF frame, or share output.
+
+use ab_glyph::PxScale;
+use chrono::{DateTime, Local};
+use image::{Rgba, RgbaImage};
+
+use crate::config::{self, WatermarkConfig, WatermarkPosition};
+use crate::fonts::{font_chain, FontRole};
+
+/// Expand watermark placeholders: {number}, {date} (YYYY-MM-DD), {user}
+pub fn render_watermark_text(template: &str, number: u64, now: DateTime<Local>) -> String {
+    template
+        .replace("{number}", &number.to_string())
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{user}", &current_user_name())
+}
+
+fn current_user_name() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default()
+}
+
+/// Parse "#RRGGBB" or "#RRGGBBAA" (leading '#' optional)
+pub fn parse_hex_color(s: &str) -> Option<Rgba<u8>> {
+    let hex = s.trim().trim_start_matches('#');
+    let bytes = hex::decode(hex).ok()?;
+    match bytes.as_slice() {
+        [r, g, b] => Some(Rgba([*r, *g, *b, 255])),
+        [r, g, b, a] => Some(Rgba([*r, *g, *b, *a])),
+        _ => None,
+    }
+}
+
+/// Whether a corner watermark will be drawn (None and Brand draw nothing here)
+pub fn corner_watermark_enabled() -> bool {
+    !matches!(
+        config::load_config().watermark_position,
+        WatermarkPosition::None | WatermarkPosition::Brand
+    )
+}
+
+/// A pre-rendered watermark ready to be blended at a fixed position
+pub struct WatermarkLayer {
+    layer: RgbaImage,
+    x: i64,
+    y: i64,
+    opacity: f32,
+}
+
+impl WatermarkLayer {
+    /// Prepare the corner watermark for an image of the given size
+    /// Returns None if the watermark is disabled (None/Brand) or empty
+    pub fn for_corner(width: u32, height: u32, number: u64) -> Option<Self> {
+        let cfg = config::load_config();
+        let position = cfg.watermark_position;
+
+        // Brand is drawn by share_compose next to "via lovshot"
+        if matches!(position, WatermarkPosition::None | WatermarkPosition::Brand) {
+            println!("[watermark] Position {:?}, skipping corner watermark", position);
+            return None;
+        }
+
+        let base_size = font_size_for(&cfg.watermark, width, height);
+        let layer = render_layer(&cfg.watermark, base_size, number)?;
+        let (layer_w, layer_h) = layer.dimensions();
+        let margin = (base_size * cfg.watermark.margin.max(0.0)) as u32;
+
+        let (x, y) = match position {
+            WatermarkPosition::TopLeft => (margin, margin),
+            WatermarkPosition::TopRight => (width.saturating_sub(layer_w + margin), margin),
+            WatermarkPosition::BottomLeft => (margin, height.saturating_sub(layer_h + margin)),
+            WatermarkPosition::BottomRight => (
+                width.saturating_sub(layer_w + margin),
+                height.saturating_sub(layer_h + margin),
+            ),
+            _ => return None,
+        };
+
+        println!("[watermark] Prepared #{} at ({}, {}) position {:?}", number, x, y, position);
+        Some(Self {
+            layer,
+            x: x as i64,
+            y: y as i64,
+            opacity: cfg.watermark.opacity.clamp(0.0, 1.0),
+        })
+    }
+
+    /// Blend the watermark onto an image
+    pub fn apply(&self, img: &mut RgbaImage) {
+        blend_layer(img, &self.layer, self.x, self.y, self.opacity);
+    }
+}
+
+/// Add the configured corner watermark to a screenshot
+pub fn add_screenshot_watermark(img: &mut RgbaImage, number: u64) {
+    if let Some(wm) = WatermarkLayer::for_corner(img.width(), img.height(), number) {
+        wm.apply(img);
+    }
+}
+
+/// Watermark (logo + text) for the share_compose footer when position is Brand
+/// Returns the layer and its opacity, drawn next to "via lovshot"
+pub fn brand_layer(number: u64, font_size: f32) -> Option<(RgbaImage, f32)> {
+    let cfg = config::load_config();
+    if cfg.watermark_position != WatermarkPosition::Brand {
+        return None;
+    }
+    let layer = render_layer(&cfg.watermark, font_size, number)?;
+    Some((layer, cfg.watermark.opacity.clamp(0.0, 1.0)))
+}
+
+fn font_size_for(cfg: &WatermarkConfig, width: u32, height: u32) -> f32 {
+    // Scale font size based on image dimensions
+    (width.min(height) as f32 * cfg.size.max(0.0)).clamp(14.0, 48.0)
+}
+
+/// Render logo + text into a tightly sized transparent layer
+fn render_layer(cfg: &WatermarkConfig, base_size: f32, number: u64) -> Option<RgbaImage> {
+    let color = parse_hex_color(&cfg.color).unwrap_or(Rgba([40, 40, 40, 255]));
+    let text = render_watermark_text(&cfg.text, number, Local::now());
+    let text = text.trim();
+
+    let font = if text.is_empty() {
+        None
+    } else {
+        let font = font_chain(FontRole::Mono);
+        if font.is_none() {
+            println!("[watermark] Font not found, drawing logo only");
+        }
+        font
+    };
+    let scale = PxScale::from(base_size);
+    let (text_w, text_h) = match font {
+        Some(ref f) => {
+            (f.text_width(scale, text).ceil() as u32, base_size.ceil() as u32)
+        }
+        None => (0, 0),
+    };
+
+    let logo = cfg.image_path.as_deref().and_then(|path| {
+        match image::open(path) {
+            Ok(img) => {
+                let logo = img.to_rgba8();
+                // Logo sits next to the text at roughly cap height x 1.5
+                let target_h = (base_size * if text_w > 0 { 1.5 } else { 2.0 }).max(1.0) as u32;
+                let target_w = ((logo.width() as f32 * target_h as f32 / logo.height().max(1) as f32) as u32).max(1);
+                Some(image::imageops::resize(&logo, target_w, target_h, image::imageops::FilterType::Lanczos3))
+            }
+            Err(e) => {
+                println!("[watermark] Failed to load logo {}: {}", path, e);
+                None
+            }
+        }
+    });
+
+    let (logo_w, logo_h) = logo.as_ref().map(|l| l.dimensions()).unwrap_or((0, 0));
+    let gap = if logo_w > 0 && text_w > 0 { (base_size * 0.4) as u32 } else { 0 };
+    let layer_w = logo_w + gap + text_w;
+    let layer_h = logo_h.max(text_h);
+    if layer_w == 0 || layer_h == 0 {
+        return None;
+    }
+
+    // Transparent pixels carry the text colour so anti-aliased edges keep straight alpha
+    let mut layer = RgbaImage::from_pixel(layer_w, layer_h, Rgba([color[0], color[1], color[2], 0]));
+    if let Some(ref logo) = logo {
+        image::imageops::overlay(&mut layer, logo, 0, ((layer_h - logo_h) / 2) as i64);
+    }
+    if let Some(ref f) = font {
+        let text_y = ((layer_h - text_h) / 2) as i32;
+        f.draw_text(&mut layer, color, (logo_w + gap) as i32, text_y, scale, text);
+    }
+    Some(layer)
+}
+
+/// Source-over blend of a straight-alpha layer with extra opacity
+pub fn blend_layer(dst: &mut RgbaImage, layer: &RgbaImage, x: i64, y: i64, opacity: f32) {
+    let (dst_w, dst_h) = dst.dimensions();
+    for (lx, ly, src) in layer.enumerate_pixels() {
+        let dx = x + lx as i64;
+        let dy = y + ly as i64;
+        if dx < 0 || dy < 0 || dx >= dst_w as i64 || dy >= dst_h as i64 {
+            continue;
+        }
+        let sa = src[3] as f32 / 255.0 * opacity;
+        if sa <= 0.0 {
+            continue;
+        }
+        let d = dst.get_pixel_mut(dx as u32, dy as u32);
+        let da = d[3] as f32 / 255.0;
+        let out_a = sa + da * (1.0 - sa);
+        if out_a <= 0.0 {
+            continue;
+        }
+        for c in 0..3 {
+            let v = (src[c] as f32 * sa + d[c] as f32 * da * (1.0 - sa)) / out_a;
+            d[c] = v.round().clamp(0.0, 255.0) as u8;
+        }
+        d[3] = (out_a * 255.0).round() as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_render_watermark_text() {
+        let now = Local.with_ymd_and_hms(2024, 12, 31, 23, 0, 0).unwrap();
+        assert_eq!(render_watermark_text("#{number} · {date}", 7, now), "#7 · 2024-12-31");
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#282828"), Some(Rgba([40, 40, 40, 255])));
+        assert_eq!(parse_hex_color("ff000080"), Some(Rgba([255, 0, 0, 128])));
+        assert_eq!(parse_hex_color("#abc"), None);
+    }
+
+    #[test]
+    fn test_blend_layer_keeps_opaque_background() {
+        let mut dst = RgbaImage::from_pixel(2, 1, Rgba([255, 255, 255, 255]));
+        let layer = RgbaImage::from_pixel(1, 1, Rgba([0, 0, 0, 255]));
+        blend_layer(&mut dst, &layer, 1, 0, 0.5);
+        assert_eq!(*dst.get_pixel(0, 0), Rgba([255, 255, 255, 255]));
+        assert_eq!(*dst.get_pixel(1, 0), Rgba([128, 128, 128, 255]));
+    }
+}
@@ -0,0 +1,196 @@
+//! Window capture via xcap's Window API
+//!
+//! Grabs a window's own pixels instead of cropping the screen, so overlapping
+//! windows don't bleed into "window" screenshots.
+
+use image::RgbaImage;
+use serde::Serialize;
+use tauri::AppHandle;
+use xcap::Window;
+
+use crate::capture::window_bounds;
+use crate::commands::finish_screenshot;
+use crate::share_compose::draw_shape_shadow;
+use crate::share_template::ShadowSpec;
+use crate::watermark::blend_layer;
+
+/// Largest shadow margin accepted, in logical pixels
+const MAX_SHADOW_MARGIN: u32 = 200;
+
+/// A capturable top-level window
+#[derive(Clone, Debug, Serialize)]
+pub struct WindowEntry {
+    pub id: u32,
+    pub pid: u32,
+    pub title: String,
+    pub app_name: String,
+    pub x: i32, // Logical pixels, like Region
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub focused: bool,
+}
+
+impl WindowEntry {
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x as f64
+            && x < self.x as f64 + self.width as f64
+            && y >= self.y as f64
+            && y < self.y as f64 + self.height as f64
+    }
+}
+
+fn describe(window: &Window) -> Option<WindowEntry> {
+    let (x, y, width, height) = window_bounds(window)?;
+    Some(WindowEntry {
+        id: window.id().ok()?,
+        pid: window.pid().unwrap_or(0),
+        title: window.title().unwrap_or_default(),
+        app_name: window.app_name().unwrap_or_default(),
+        x,
+        y,
+        width,
+        height,
+        focused: window.is_focused().unwrap_or(false),
+    })
+}
+
+/// Visible windows of other apps, topmost first (Lovshot's own windows are skipped)
+pub fn list_windows() -> Result<Vec<(Window, WindowEntry)>, String> {
+    let own_pid = std::process::id();
+    let mut windows: Vec<(i32, Window, WindowEntry)> = Window::all()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|w| !w.is_minimized().unwrap_or(false))
+        .filter_map(|w| {
+            let entry = describe(&w)?;
+            if entry.pid == own_pid || entry.width == 0 || entry.height == 0 {
+                return None;
+            }
+            Some((w.z().unwrap_or(0), w, entry))
+        })
+        .collect();
+    // Higher z is closer to the front
+    windows.sort_by_key(|(z, _, _)| std::cmp::Reverse(*z));
+    Ok(windows.into_iter().map(|(_, w, entry)| (w, entry)).collect())
+}
+
+/// Capturable windows without their xcap handles, topmost first
+pub fn window_entries() -> Vec<WindowEntry> {
+    list_windows()
+        .map(|windows| windows.into_iter().map(|(_, entry)| entry).collect())
+        .unwrap_or_default()
+}
+
+/// Topmost window of another app containing a logical point
+///
+/// `windows` must be topmost first, like [`list_windows`]; hit-testing the
+/// stacking order means a covered window never wins over the one actually
+/// visible under the point.
+pub fn hit_test(windows: &[WindowEntry], x: f64, y: f64, own_pid: u32) -> Option<&WindowEntry> {
+    windows
+        .iter()
+        .filter(|w| w.pid != own_pid && w.width > 0 && w.height > 0)
+        .find(|w| w.contains(x, y))
+}
+
+/// Topmost window of another app at a logical point, from a fresh window list
+pub fn window_at_position(x: f64, y: f64) -> Option<WindowEntry> {
+    hit_test(&window_entries(), x, y, std::process::id()).cloned()
+}
+
+/// Window image on a transparent canvas with a soft drop shadow around it
+pub fn add_shadow_margin(img: &RgbaImage, margin: u32) -> RgbaImage {
+    let (w, h) = img.dimensions();
+    let mut canvas = RgbaImage::new(w + margin * 2, h + margin * 2);
+    let shadow = ShadowSpec {
+        blur: margin as f32 / 2.0,
+        offset_x: 0,
+        offset_y: (margin / 4) as i32,
+        ..ShadowSpec::default()
+    };
+    draw_shape_shadow(&mut canvas, img, margin as i64, margin as i64, &shadow);
+    blend_layer(&mut canvas, img, margin as i64, margin as i64, 1.0);
+    canvas
+}
+
+/// Tauri command: windows that can be captured, topmost first
+#[tauri::command]
+pub fn list_capturable_windows() -> Result<Vec<WindowEntry>, String> {
+    Ok(list_windows()?.into_iter().map(|(_, entry)| entry).collect())
+}
+
+/// Tauri command: capture one window's own pixels, even when other windows cover it
+///
+/// `shadow_margin` (logical pixels) adds a transparent border with a drop shadow.
+#[tauri::command(rename_all = "camelCase")]
+pub fn capture_window(
+    app: AppHandle,
+    window_id: u32,
+    shadow_margin: Option<u32>,
+    caption_mode: Option<bool>,
+) -> Result<String, String> {
+    let (window, entry) = list_windows()?
+        .into_iter()
+        .find(|(_, entry)| entry.id == window_id)
+        .ok_or_else(|| format!("Window {} not found", window_id))?;
+    println!(
+        "[capture_window] {} \"{}\" at {},{} {}x{}",
+        entry.app_name, entry.title, entry.x, entry.y, entry.width, entry.height
+    );
+
+    let mut img = window.capture_image().map_err(|e| e.to_string())?;
+    let margin = shadow_margin.unwrap_or(0).min(MAX_SHADOW_MARGIN);
+    if margin > 0 {
+        // Captured pixels are physical; scale the margin to match
+        let scale = img.width() as f32 / entry.width.max(1) as f32;
+        img = add_shadow_margin(&img, (margin as f32 * scale).round() as u32);
+    }
+
+    let app_name = Some(entry.app_name).filter(|name| !name.is_empty());
+    finish_screenshot(&app, img, app_name, 1.0, "window", caption_mode.unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn test_shadow_margin() {
+        let window = RgbaImage::from_pixel(40, 30, Rgba([10, 120, 200, 255]));
+        let out = add_shadow_margin(&window, 20);
+        assert_eq!(out.dimensions(), (80, 70));
+
+        // Window pixels untouched, corners fully transparent, shadow below the window
+        assert_eq!(*out.get_pixel(40, 35), Rgba([10, 120, 200, 255]));
+        assert_eq!(out.get_pixel(0, 0)[3], 0);
+        assert!(out.get_pixel(40, 52)[3] > 0);
+        assert!(out.get_pixel(40, 52)[3] > out.get_pixel(40, 12)[3]);
+    }
+
+    #[test]
+    fn test_hit_test_topmost_first() {
+        let entry = |id: u32, pid: u32, x: i32, y: i32, width: u32, height: u32| WindowEntry {
+            id,
+            pid,
+            title: String::new(),
+            app_name: String::new(),
+            x,
+            y,
+            width,
+            height,
+            focused: false,
+        };
+        let own_pid = 42;
+        let windows = vec![
+            entry(1, own_pid, 0, 0, 800, 600), // our own selector on top
+            entry(2, 7, 100, 100, 0, 300),     // zero-size
+            entry(3, 7, 100, 100, 200, 200),   // front window
+            entry(4, 8, 0, 0, 800, 600),       // covered by the front window
+        ];
+        assert_eq!(hit_test(&windows, 150.0, 150.0, own_pid).map(|w| w.id), Some(3));
+        assert_eq!(hit_test(&windows, 50.0, 50.0, own_pid).map(|w| w.id), Some(4));
+        assert_eq!(hit_test(&windows, 900.0, 50.0, own_pid).map(|w| w.id), None);
+    }
+}
@@ -302,7 +302,7 @@ pub fn activate_app_by_pid(pid: i32) -> bool {
 }
 
 /// Get application name from PID
-fn get_app_name_from_pid(pid: i32) -> Option<String> {
+pub fn get_app_name_from_pid(pid: i32) -> Option<String> {
     use objc::{class, msg_send, sel, sel_impl};
 
     unsafe {
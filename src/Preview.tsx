@@ -4,7 +4,11 @@ import { getCurrentWindow } from "@tauri-apps/api/window";
 import { emit } from "@tauri-apps/api/event";
 import { LogicalSize } from "@tauri-apps/api/dpi";
 import { domToCanvas } from "modern-screenshot";
+import type Konva from "konva";
 import { Checkbox } from "./components/ui/checkbox";
+import { AnnotationCanvas } from "./components/AnnotationCanvas";
+import { useAnnotationEditor } from "./hooks/useAnnotationEditor";
+import type { Annotation, AnnotationTool } from "./types/annotation";
 import {
   Select,
   SelectContent,
@@ -20,7 +24,31 @@ const SHARE_TEMPLATES = [
   { id: "quote", label: "引用" },
 ] as const;
 
-type TemplateId = (typeof SHARE_TEMPLATES)[number]["id"];
+// Local templates render in the DOM; "rs:<id>" are built-in and user templates rendered in Rust
+type TemplateId = (typeof SHARE_TEMPLATES)[number]["id"] | `rs:${string}`;
+const RUST_TEMPLATE_PREFIX = "rs:";
+
+interface ShareTemplateInfo {
+  id: string;
+  name: string;
+  builtin: boolean;
+  path: string | null;
+}
+
+// Unannotated capture + scene embedded in annotated screenshots
+interface EditableAnnotations {
+  original: string;
+  scene: { scale: number; annotations: Annotation[] };
+}
+
+const ANNOTATE_TOOLS: { id: AnnotationTool; label: string }[] = [
+  { id: "select", label: "选择" },
+  { id: "rect", label: "矩形" },
+  { id: "mosaic", label: "马赛克" },
+  { id: "arrow", label: "箭头" },
+  { id: "text", label: "文字" },
+];
+const ANNOTATE_TOOLBAR_HEIGHT = 44;
 
 export default function Preview() {
   const params = new URLSearchParams(window.location.search);
@@ -38,6 +66,45 @@ export default function Preview() {
   const [screenshotCount, setScreenshotCount] = useState(0);
   const [pinned, setPinned] = useState(false);
   const [showCaptionEditor, setShowCaptionEditor] = useState(true);
+  const [shareTemplates, setShareTemplates] = useState<ShareTemplateInfo[]>([]);
+  const [renderedShare, setRenderedShare] = useState<string | null>(null);
+  const [layers, setLayers] = useState<EditableAnnotations | null>(null);
+  const [isAnnotating, setIsAnnotating] = useState(false);
+  const [annotateSize, setAnnotateSize] = useState({ width: 0, height: 0 });
+  const [imageVersion, setImageVersion] = useState(0);
+  const editor = useAnnotationEditor();
+  const stageRef = useRef<Konva.Stage>(null);
+  // Bust the webview cache after the file is re-rendered in place
+  const imageSrc = convertFileSrc(path) + (imageVersion ? `?v=${imageVersion}` : "");
+
+  // Rust-rendered templates (including files in the templates folder) for the picker
+  useEffect(() => {
+    invoke<ShareTemplateInfo[]>("list_share_templates").then(setShareTemplates).catch(console.error);
+  }, []);
+
+  const rustTemplate = template.startsWith(RUST_TEMPLATE_PREFIX) ? template.slice(RUST_TEMPLATE_PREFIX.length) : null;
+
+  // Re-render the Rust template preview as the caption changes
+  useEffect(() => {
+    if (!isCaptionMode || !rustTemplate || !path) {
+      setRenderedShare(null);
+      return;
+    }
+    const timer = setTimeout(() => {
+      invoke<string>("preview_share_template", { sourcePath: path, caption, template: rustTemplate })
+        .then(setRenderedShare)
+        .catch(console.error);
+    }, 300);
+    return () => clearTimeout(timer);
+  }, [isCaptionMode, rustTemplate, path, caption]);
+
+  // Annotated screenshots keep their layers, so they can be reopened for editing
+  useEffect(() => {
+    if (!path) return;
+    invoke<EditableAnnotations | null>("get_annotation_layers", { path })
+      .then(setLayers)
+      .catch(console.error);
+  }, [path]);
 
   // Load settings from config
   useEffect(() => {
@@ -127,6 +194,68 @@ export default function Preview() {
     }
   };
 
+  // Open the embedded original with its scene, at the size it was annotated at
+  const startAnnotating = () => {
+    if (!layers) return;
+    const img = new Image();
+    img.onload = async () => {
+      const scale = layers.scene.scale > 0 ? layers.scene.scale : 1;
+      const width = Math.round(img.naturalWidth / scale);
+      const height = Math.round(img.naturalHeight / scale);
+      setAnnotateSize({ width, height });
+      editor.load(layers.scene.annotations);
+      setIsAnnotating(true);
+      try {
+        await getCurrentWindow().setSize(new LogicalSize(width, height + ANNOTATE_TOOLBAR_HEIGHT));
+      } catch (e) {
+        console.error("[Preview] resize failed:", e);
+      }
+    };
+    img.src = layers.original;
+  };
+
+  const stopAnnotating = () => {
+    setIsAnnotating(false);
+    editor.reset();
+    if (imgSize.width > 0) resizeWindowToFit(imgSize.width, imgSize.height);
+  };
+
+  // Re-render the file in place from the original and the edited scene
+  const saveAnnotations = async () => {
+    if (!layers) return;
+    const scene = { scale: layers.scene.scale, annotations: editor.annotations };
+    try {
+      await invoke("update_annotations", { path, scene });
+      setLayers({ ...layers, scene });
+      setImageVersion(Date.now());
+      stopAnnotating();
+    } catch (err) {
+      console.error("[Preview] Failed to update annotations:", err);
+    }
+  };
+
+  useEffect(() => {
+    if (!isAnnotating) return;
+
+    const onKeyDown = (e: KeyboardEvent) => {
+      if (e.target instanceof HTMLTextAreaElement) return;
+      if (e.key === "Escape") {
+        e.preventDefault();
+        stopAnnotating();
+      } else if ((e.metaKey || e.ctrlKey) && e.key === "z") {
+        e.preventDefault();
+        if (e.shiftKey) editor.redo();
+        else editor.undo();
+      } else if (e.key === "Delete" || e.key === "Backspace") {
+        e.preventDefault();
+        editor.deleteSelected();
+      }
+    };
+
+    window.addEventListener("keydown", onKeyDown);
+    return () => window.removeEventListener("keydown", onKeyDown);
+  }, [isAnnotating, editor]);
+
   const handleClick = async () => {
     if (!isCaptionMode) {
       // 点击预览进入 caption 模式
@@ -149,7 +278,7 @@ export default function Preview() {
 
   // Auto-save and close on window blur (unless pinned, composing, or dev mode)
   useEffect(() => {
-    if (!isCaptionMode) return;
+    if (!isCaptionMode || isAnnotating) return;
     // 开发模式下禁用失焦关闭，方便调试
     if (import.meta.env.DEV) return;
 
@@ -164,10 +293,10 @@ export default function Preview() {
     });
 
     return () => { unlisten.then((f) => f()); };
-  }, [isCaptionMode, path]);
+  }, [isCaptionMode, isAnnotating, path]);
 
   useEffect(() => {
-    if (!isCaptionMode) return;
+    if (!isCaptionMode || isAnnotating) return;
 
     const onKeyDown = async (e: KeyboardEvent) => {
       if (e.key === "Escape") {
@@ -185,7 +314,7 @@ export default function Preview() {
 
     window.addEventListener("keydown", onKeyDown);
     return () => window.removeEventListener("keydown", onKeyDown);
-  }, [isCaptionMode, path]);
+  }, [isCaptionMode, isAnnotating, path]);
 
   // Set min size on caption mode enter, resize when image first loads
   useEffect(() => {
@@ -234,11 +363,23 @@ export default function Preview() {
     }
   };
 
+  // Rust templates compose, copy and save in one step
+  const composeRustTemplate = async (id: string) => {
+    const saved = await invoke<string>("compose_share", { sourcePath: path, caption: captionRef.current.trim(), template: id });
+    await invoke("save_caption", { path, caption: captionRef.current.trim(), closeWindow: !pinned });
+    await emit("image-saved", { path: saved });
+    console.log("[Preview] Composed with template", id, "->", saved);
+  };
+
   const handleCopyComposed = async () => {
     if (composing || !renderRef.current || !captionRef.current.trim()) return;
     setComposing(true);
 
     try {
+      if (rustTemplate) {
+        await composeRustTemplate(rustTemplate);
+        return;
+      }
       const result = await captureTemplate();
       if (!result) return;
 
@@ -260,8 +401,6 @@ export default function Preview() {
 
   // Render template content for html2canvas capture
   const renderTemplateContent = () => {
-    const imageSrc = convertFileSrc(path);
-
     const handleImageLoad = (e: React.SyntheticEvent<HTMLImageElement>) => {
       if (imageLoaded) return; // 已加载过，切换模板时不再调整窗口
       const img = e.currentTarget;
@@ -324,10 +463,61 @@ export default function Preview() {
         );
 
       default:
+        if (rustTemplate) {
+          return (
+            <div className="tpl-rendered">
+              <img src={renderedShare ?? imageSrc} alt="" onLoad={handleImageLoad} />
+            </div>
+          );
+        }
         return null;
     }
   };
 
+  if (isAnnotating && layers) {
+    return (
+      <div className="annotate-editor">
+        <div className="annotate-toolbar" style={{ height: ANNOTATE_TOOLBAR_HEIGHT }} data-tauri-drag-region>
+          {ANNOTATE_TOOLS.map((t) => (
+            <button
+              key={t.id}
+              className={`annotate-tool${editor.activeTool === t.id ? " active" : ""}`}
+              onClick={() => editor.setActiveTool(t.id)}
+            >
+              {t.label}
+            </button>
+          ))}
+          <span className="annotate-divider" />
+          <button className="annotate-tool" onClick={editor.undo} disabled={!editor.canUndo}>撤销</button>
+          <button className="annotate-tool" onClick={editor.redo} disabled={!editor.canRedo}>重做</button>
+          <div className="annotate-actions">
+            <button className="share-btn secondary" onClick={stopAnnotating}>取消</button>
+            <button className="share-btn" onClick={saveAnnotations}>保存</button>
+          </div>
+        </div>
+        <AnnotationCanvas
+          imageUrl={layers.original}
+          width={annotateSize.width}
+          height={annotateSize.height}
+          left={0}
+          top={ANNOTATE_TOOLBAR_HEIGHT}
+          annotations={editor.annotations}
+          selectedId={editor.selectedId}
+          activeTool={editor.activeTool}
+          activeColor={editor.activeColor}
+          activeStyles={editor.activeStyles}
+          strokeWidth={editor.strokeWidth}
+          fontSize={editor.fontSize}
+          onAddAnnotation={editor.addAnnotation}
+          onUpdateAnnotation={editor.updateAnnotation}
+          onDeleteAnnotation={editor.deleteAnnotation}
+          onSelectAnnotation={editor.setSelectedId}
+          stageRef={stageRef}
+        />
+      </div>
+    );
+  }
+
   if (isCaptionMode) {
     return (
       <div className="share-editor">
@@ -368,13 +558,16 @@ export default function Preview() {
               <div className="flex items-center gap-2 text-xs text-muted-foreground whitespace-nowrap">
                 <span>风格</span>
                 <Select value={template} onValueChange={(v) => setTemplate(v as TemplateId)}>
-                  <SelectTrigger className="h-7 w-20 text-xs">
+                  <SelectTrigger className="h-7 w-28 text-xs">
                     <SelectValue />
                   </SelectTrigger>
                   <SelectContent>
                     {SHARE_TEMPLATES.map((t) => (
                       <SelectItem key={t.id} value={t.id}>{t.label}</SelectItem>
                     ))}
+                    {shareTemplates.map((t) => (
+                      <SelectItem key={t.id} value={`${RUST_TEMPLATE_PREFIX}${t.id}`}>{t.name}</SelectItem>
+                    ))}
                   </SelectContent>
                 </Select>
               </div>
@@ -421,6 +614,16 @@ export default function Preview() {
               </div>
             </div>
             <div className="share-actions">
+              {layers && (
+                <button
+                  className="share-btn secondary"
+                  onClick={startAnnotating}
+                  disabled={composing}
+                  title="重新编辑截图上的标注"
+                >
+                  标注
+                </button>
+              )}
               <button
                 className="share-btn"
                 onClick={handleCopyComposed}
@@ -434,6 +637,10 @@ export default function Preview() {
                   if (composing || !caption.trim()) return;
                   setComposing(true);
                   try {
+                    if (rustTemplate) {
+                      await composeRustTemplate(rustTemplate);
+                      return;
+                    }
                     const result = await captureTemplate();
                     if (!result) return;
 
@@ -473,7 +680,7 @@ export default function Preview() {
 
   return (
     <div className="preview-container" onClick={handleClick}>
-      {path && <img src={convertFileSrc(path)} alt="Screenshot" />}
+      {path && <img src={imageSrc} alt="Screenshot" />}
       <div className="preview-label">已保存到剪贴板</div>
     </div>
   );
@@ -48,6 +48,7 @@ function matchesAnyShortcut(e: KeyboardEvent, shortcuts: ShortcutConfig[]): bool
 export default function RecordingOverlay() {
   const [region, setRegion] = useState<OverlayRegion | null>(null);
   const [isStatic, setIsStatic] = useState(false);
+  const [countdown, setCountdown] = useState<number | null>(null);
 
   useEffect(() => {
     // Get region from window label query params or listen for it
@@ -57,9 +58,19 @@ export default function RecordingOverlay() {
     const w = parseInt(params.get("w") || "200");
     const h = parseInt(params.get("h") || "200");
     const staticMode = params.get("static") === "1";
+    const countdownSecs = parseInt(params.get("countdown") || "0");
     setRegion({ x, y, width: w, height: h });
     setIsStatic(staticMode);
 
+    // Delayed capture: count down once per second; the backend closes the window
+    let countdownTimer: ReturnType<typeof setInterval> | null = null;
+    if (countdownSecs > 0) {
+      setCountdown(countdownSecs);
+      countdownTimer = setInterval(() => {
+        setCountdown((n) => (n !== null && n > 1 ? n - 1 : n));
+      }, 1000);
+    }
+
     // Listen for recording stop to close (for GIF recording)
     const unlistenRecording = listen("recording-stopped", async () => {
       await getCurrentWindow().close();
@@ -78,7 +89,7 @@ export default function RecordingOverlay() {
       if (stopShortcuts.length > 0) {
         handleKeyDown = async (e: KeyboardEvent) => {
           if (matchesAnyShortcut(e, stopShortcuts)) {
-            await invoke("stop_recording");
+            await invoke(countdownSecs > 0 ? "cancel_delayed_capture" : "stop_recording");
           }
         };
         document.addEventListener("keydown", handleKeyDown);
@@ -88,6 +99,7 @@ export default function RecordingOverlay() {
     return () => {
       unlistenRecording.then((fn) => fn());
       unlistenScroll.then((fn) => fn());
+      if (countdownTimer) clearInterval(countdownTimer);
       if (handleKeyDown) {
         document.removeEventListener("keydown", handleKeyDown);
       }
@@ -98,7 +110,7 @@ export default function RecordingOverlay() {
 
   const cornerLen = 20;
   const borderWidth = 3;
-  const cornerClass = isStatic ? "corner static" : "corner";
+  const cornerClass = isStatic || countdown !== null ? "corner static" : "corner";
 
   return (
     <div className="recording-overlay">
@@ -146,6 +158,14 @@ export default function RecordingOverlay() {
           borderWidth: `0 ${borderWidth}px ${borderWidth}px 0`,
         }}
       />
+      {countdown !== null && (
+        <div
+          className="countdown"
+          style={{ left: region.x + region.width / 2, top: region.y + region.height / 2 }}
+        >
+          <span key={countdown}>{countdown}</span>
+        </div>
+      )}
     </div>
   );
 }
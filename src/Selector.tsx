@@ -28,6 +28,30 @@ interface WindowInfo {
   titlebar_height: number;
 }
 
+// Rect spanned by a drag, shrunk to the locked aspect ratio (width / height) around its start corner
+function dragRect(start: { x: number; y: number }, end: { x: number; y: number }, ratio: number | null): SelectionRect {
+  let w = Math.abs(end.x - start.x);
+  let h = Math.abs(end.y - start.y);
+  if (ratio && w > 0 && h > 0) {
+    if (w / h > ratio) w = Math.round(h * ratio);
+    else h = Math.round(w / ratio);
+  }
+  return {
+    x: end.x < start.x ? start.x - w : start.x,
+    y: end.y < start.y ? start.y - h : start.y,
+    w,
+    h,
+  };
+}
+
+// Shrink a rect to the aspect ratio, keeping its top-left corner (same as the backend's fit_aspect)
+function fitAspect(rect: SelectionRect, ratio: number | null): SelectionRect {
+  if (!ratio || rect.w <= 0 || rect.h <= 0) return rect;
+  return rect.w / rect.h > ratio
+    ? { ...rect, w: Math.round(rect.h * ratio) }
+    : { ...rect, h: Math.round(rect.w / ratio) };
+}
+
 export default function Selector() {
   const [isSelecting, setIsSelecting] = useState(false);
   const [selectionRect, setSelectionRect] = useState<SelectionRect | null>(null);
@@ -43,6 +67,8 @@ export default function Selector() {
   const [originalWindowInfo, setOriginalWindowInfo] = useState<WindowInfo | null>(null);
   const [scrollCaptureEnabled, setScrollCaptureEnabled] = useState(false);
   const [screenSnapshot, setScreenSnapshot] = useState<string | null>(null);
+  const [delaySecs, setDelaySecs] = useState<number | null>(null); // Set when opened for a delayed screenshot
+  const [aspectRatio, setAspectRatio] = useState<number | null>(null); // Locked width / height from settings
   const [magnifierReady, setMagnifierReady] = useState(false);
   const [captionEnabled, setCaptionEnabled] = useState(() => {
     return localStorage.getItem("captionEnabled") === "true";
@@ -63,6 +89,11 @@ export default function Selector() {
   const [scrollFrameCount, setScrollFrameCount] = useState(0);
   const [scrollTotalHeight, setScrollTotalHeight] = useState(0);
 
+  // Aspect-ratio lock applies to drawing and resizing the selection
+  useEffect(() => {
+    invoke<number | null>("get_aspect_ratio_lock").then(setAspectRatio).catch(console.error);
+  }, []);
+
   // Persist captionEnabled
   useEffect(() => {
     localStorage.setItem("captionEnabled", String(captionEnabled));
@@ -198,6 +229,10 @@ export default function Selector() {
         invoke("clear_pending_mode");
       }
     });
+    // Opened via the delayed screenshot shortcut: confirm starts a countdown instead
+    invoke<number | null>("take_pending_delay").then((secs) => {
+      if (secs) setDelaySecs(secs);
+    });
     // Check if scroll capture is enabled
     invoke<{ scroll_capture_enabled: boolean }>("get_shortcuts_config").then((cfg) => {
       setScrollCaptureEnabled(cfg.scroll_capture_enabled);
@@ -289,7 +324,13 @@ export default function Selector() {
           transformer?.show();
 
           await win.hide();
-          await invoke("save_annotated_screenshot", { imageData: base64, captionMode: captionEnabled });
+          // The unannotated capture and the scene are embedded so the file stays editable
+          await invoke("save_annotated_screenshot", {
+            imageData: base64,
+            captionMode: captionEnabled,
+            originalData: previewImage,
+            scene: { scale: window.devicePixelRatio, annotations: editor.annotations },
+          });
           await win.close();
           return;
         } catch (e) {
@@ -297,6 +338,14 @@ export default function Selector() {
         }
       }
 
+      // Delayed flow: the backend counts down over the region, then captures it live
+      if (delaySecs && mode === "image") {
+        await win.hide();
+        await invoke("start_delayed_capture", { seconds: delaySecs, region });
+        await win.close();
+        return;
+      }
+
       // Normal screenshot flow
       await win.hide();
       // Static mode doesn't need delay since we use cached screenshot
@@ -329,7 +378,7 @@ export default function Selector() {
         await invoke("set_selector_mouse_passthrough", { enabled: false });
       }
     }
-  }, [selectionRect, mode, closeWindow, isEditing, editor.annotations.length, captionEnabled]);
+  }, [selectionRect, mode, closeWindow, isEditing, editor.annotations, previewImage, captionEnabled, delaySecs]);
 
   // Resize handle start
   const handleResizeStart = useCallback(
@@ -437,6 +486,19 @@ export default function Selector() {
         if (w < 10) { w = 10; x = resizeDir.includes("w") ? r.x + r.w - 10 : x; }
         if (h < 10) { h = 10; y = resizeDir.includes("n") ? r.y + r.h - 10 : y; }
 
+        // Keep the locked ratio: edge handles drive their own axis, corners the larger change
+        if (aspectRatio) {
+          const vertical = resizeDir === "n" || resizeDir === "s";
+          const horizontal = resizeDir === "e" || resizeDir === "w";
+          if (vertical || (!horizontal && Math.abs(dy) * aspectRatio > Math.abs(dx))) {
+            w = Math.round(h * aspectRatio);
+          } else {
+            h = Math.round(w / aspectRatio);
+          }
+          if (resizeDir.includes("w")) x = r.x + r.w - w;
+          if (resizeDir.includes("n")) y = r.y + r.h - h;
+        }
+
         setSelectionRect({ x, y, w, h });
         if (selectionRef.current) {
           selectionRef.current.style.left = `${x}px`;
@@ -455,10 +517,7 @@ export default function Selector() {
 
       if (!isSelecting) return;
 
-      const x = Math.min(e.clientX, startPos.current.x);
-      const y = Math.min(e.clientY, startPos.current.y);
-      const w = Math.abs(e.clientX - startPos.current.x);
-      const h = Math.abs(e.clientY - startPos.current.y);
+      const { x, y, w, h } = dragRect(startPos.current, { x: e.clientX, y: e.clientY }, aspectRatio);
 
       if (selectionRef.current) {
         selectionRef.current.style.left = `${x}px`;
@@ -475,7 +534,7 @@ export default function Selector() {
         sizeRef.current.style.display = "block";
       }
     },
-    [isSelecting, resizeDir]
+    [isSelecting, resizeDir, aspectRatio]
   );
 
   const handleMouseUp = useCallback(
@@ -493,10 +552,7 @@ export default function Selector() {
       setIsSelecting(false);
 
       endPos.current = { x: e.clientX, y: e.clientY };
-      const x = Math.min(e.clientX, startPos.current.x);
-      const y = Math.min(e.clientY, startPos.current.y);
-      const w = Math.abs(e.clientX - startPos.current.x);
-      const h = Math.abs(e.clientY - startPos.current.y);
+      const { x, y, w, h } = dragRect(startPos.current, endPos.current, aspectRatio);
 
       if (w > 10 && h > 10) {
         const newRect = { x, y, w, h };
@@ -545,31 +601,31 @@ export default function Selector() {
           const finalY = excludeTitlebar ? windowInfo.y + windowInfo.titlebar_height : windowInfo.y;
           const finalH = excludeTitlebar ? windowInfo.height - windowInfo.titlebar_height : windowInfo.height;
 
-          const newRect = {
+          const newRect = fitAspect({
             x: windowInfo.x,
             y: finalY,
             w: windowInfo.width,
             h: finalH,
-          };
+          }, aspectRatio);
           setSelectionRect(newRect);
           setShowHint(false);
           setHoveredWindow(null);
 
           if (selectionRef.current) {
-            selectionRef.current.style.left = `${windowInfo.x}px`;
-            selectionRef.current.style.top = `${finalY}px`;
-            selectionRef.current.style.width = `${windowInfo.width}px`;
-            selectionRef.current.style.height = `${finalH}px`;
+            selectionRef.current.style.left = `${newRect.x}px`;
+            selectionRef.current.style.top = `${newRect.y}px`;
+            selectionRef.current.style.width = `${newRect.w}px`;
+            selectionRef.current.style.height = `${newRect.h}px`;
             selectionRef.current.style.display = "block";
           }
 
           // scroll 模式：选区确认后直接进入滚动捕获（内联）
           if (mode === "scroll") {
             const region = {
-              x: Math.round(windowInfo.x),
-              y: Math.round(finalY),
-              width: Math.round(windowInfo.width),
-              height: Math.round(finalH),
+              x: Math.round(newRect.x),
+              y: Math.round(newRect.y),
+              width: Math.round(newRect.w),
+              height: Math.round(newRect.h),
             };
             try {
               await invoke("set_region", { region });
@@ -594,7 +650,7 @@ export default function Selector() {
         }
       }
     },
-    [isSelecting, resizeDir, excludeTitlebar, mode, closeWindow]
+    [isSelecting, resizeDir, excludeTitlebar, mode, closeWindow, aspectRatio]
   );
 
   // Re-calculate selection when excludeTitlebar changes (only for window selections)
@@ -608,20 +664,21 @@ export default function Selector() {
       ? originalWindowInfo.height - originalWindowInfo.titlebar_height
       : originalWindowInfo.height;
 
-    setSelectionRect({
+    const rect = fitAspect({
       x: originalWindowInfo.x,
       y: finalY,
       w: originalWindowInfo.width,
       h: finalH,
-    });
+    }, aspectRatio);
+    setSelectionRect(rect);
 
     if (selectionRef.current) {
-      selectionRef.current.style.left = `${originalWindowInfo.x}px`;
-      selectionRef.current.style.top = `${finalY}px`;
-      selectionRef.current.style.width = `${originalWindowInfo.width}px`;
-      selectionRef.current.style.height = `${finalH}px`;
+      selectionRef.current.style.left = `${rect.x}px`;
+      selectionRef.current.style.top = `${rect.y}px`;
+      selectionRef.current.style.width = `${rect.w}px`;
+      selectionRef.current.style.height = `${rect.h}px`;
     }
-  }, [excludeTitlebar, originalWindowInfo, showToolbar]);
+  }, [excludeTitlebar, originalWindowInfo, showToolbar, aspectRatio]);
 
   // Toggle between static and dynamic screenshot mode
   const toggleStaticMode = useCallback(async () => {
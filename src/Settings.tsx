@@ -17,8 +17,23 @@ interface AppConfig {
   scroll_capture_enabled: boolean;
   screenshot_preview_enabled: boolean;
   image_export_format: string;
+  region_presets: RegionPreset[];
+  aspect_ratio_lock: string | null;
 }
 
+interface RegionPreset {
+  id: string;
+  name: string;
+  width: number;
+  height: number;
+  x: number | null;
+  y: number | null;
+  mode: string;
+}
+
+// Shortcut actions for presets are named "preset:<id>"
+const PRESET_ACTION_PREFIX = "preset:";
+
 type EditingState = {
   action: string;
   index: number; // -1 means adding new
@@ -33,8 +48,19 @@ const ACTION_LABELS: Record<string, string> = {
   scroll: "Scroll Capture",
   stop_scroll: "Stop Scroll (extra)",
   show_main: "Open Main Panel",
+  repeat_last: "Repeat Last Capture",
+  delayed_screenshot: "Delayed Screenshot",
 };
 
+function actionLabel(action: string, presets: RegionPreset[]): string {
+  if (action.startsWith(PRESET_ACTION_PREFIX)) {
+    const id = action.slice(PRESET_ACTION_PREFIX.length);
+    const preset = presets.find((p) => p.id === id);
+    return `Preset: ${preset?.name ?? id}`;
+  }
+  return ACTION_LABELS[action] ?? action;
+}
+
 function formatShortcut(cfg: ShortcutConfig): string {
   const mods = cfg.modifiers.map((m) => {
     switch (m.toLowerCase()) {
@@ -94,11 +120,16 @@ export default function Settings() {
   const [pendingShortcut, setPendingShortcut] = useState<{ modifiers: string[]; key: string } | null>(null);
   const [error, setError] = useState<string | null>(null);
   const [debugInfo, setDebugInfo] = useState<string>("");
+  const [newPreset, setNewPreset] = useState({ name: "", width: "", height: "", mode: "image" });
+  const [aspectInput, setAspectInput] = useState("");
   const containerRef = useRef<HTMLDivElement>(null);
 
   // Load config on mount
   useEffect(() => {
-    invoke<AppConfig>("get_shortcuts_config").then(setConfig);
+    invoke<AppConfig>("get_shortcuts_config").then((cfg) => {
+      setConfig(cfg);
+      setAspectInput(cfg.aspect_ratio_lock ?? "");
+    });
   }, []);
 
   // Global keyboard listener when editing
@@ -255,6 +286,47 @@ export default function Settings() {
     }
   }, [config]);
 
+  const handleAddPreset = useCallback(async () => {
+    const name = newPreset.name.trim();
+    const width = parseInt(newPreset.width, 10);
+    const height = parseInt(newPreset.height, 10);
+    if (!name || !(width > 0) || !(height > 0)) {
+      setError("Preset needs a name and a size");
+      return;
+    }
+    // Centred on the cursor when captured; the id doubles as the shortcut action suffix
+    const preset: RegionPreset = { id: `p${Date.now().toString(36)}`, name, width, height, x: null, y: null, mode: newPreset.mode };
+    try {
+      const newConfig = await invoke<AppConfig>("save_region_preset", { preset });
+      setConfig(newConfig);
+      setNewPreset({ name: "", width: "", height: "", mode: newPreset.mode });
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  }, [newPreset]);
+
+  const handleRemovePreset = useCallback(async (id: string) => {
+    try {
+      const newConfig = await invoke<AppConfig>("remove_region_preset", { id });
+      setConfig(newConfig);
+    } catch (e) {
+      setError(String(e));
+    }
+  }, []);
+
+  const handleAspectRatioChange = useCallback(async (value: string) => {
+    const ratio = value.trim();
+    try {
+      const newConfig = await invoke<AppConfig>("set_aspect_ratio_lock", { ratio: ratio || null });
+      setConfig(newConfig);
+      setAspectInput(newConfig.aspect_ratio_lock ?? "");
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  }, []);
+
   const handleClose = useCallback(async () => {
     await getCurrentWindow().close();
   }, []);
@@ -263,11 +335,14 @@ export default function Settings() {
     return <div className="settings-container">Loading...</div>;
   }
 
-  const actions = ["screenshot_static", "screenshot", "gif", "stop_recording", "scroll", "stop_scroll", "video", "show_main"];
+  const actions = [
+    "screenshot_static", "screenshot", "gif", "stop_recording", "scroll", "stop_scroll", "video", "delayed_screenshot", "repeat_last", "show_main",
+    ...config.region_presets.map((p) => `${PRESET_ACTION_PREFIX}${p.id}`),
+  ];
 
   return (
     <div className="settings-container" ref={containerRef} tabIndex={-1}>
-      <Accordion type="multiple" defaultValue={["shortcuts", "general", "advanced"]}>
+      <Accordion type="multiple" defaultValue={["shortcuts", "presets", "general", "advanced"]}>
         <AccordionItem value="shortcuts">
           <AccordionTrigger>Shortcuts</AccordionTrigger>
           <AccordionContent>
@@ -284,7 +359,7 @@ export default function Settings() {
                     className={`setting-row ${actionIndex < actions.length - 1 ? "has-border" : ""} ${isDisabled ? "disabled" : ""}`}
                     data-tooltip={isDisabled ? "Enable Scroll Capture (Preview) in Advanced settings first" : undefined}
                   >
-                    <span className="setting-label">{ACTION_LABELS[action]}</span>
+                    <span className="setting-label">{actionLabel(action, config.region_presets)}</span>
                     <div className="setting-control">
                       {shortcuts.map((cfg, idx) => {
                         const isEditingThis = isEditingThisAction && editing?.index === idx;
@@ -362,6 +437,78 @@ export default function Settings() {
           </AccordionContent>
         </AccordionItem>
 
+        <AccordionItem value="presets">
+          <AccordionTrigger>Region Presets</AccordionTrigger>
+          <AccordionContent>
+            <div className="settings-card">
+              {config.region_presets.map((preset) => (
+                <div key={preset.id} className="setting-row has-border">
+                  <span className="setting-label">{preset.name}</span>
+                  <div className="setting-control">
+                    <span className="shortcut-key">
+                      {preset.width} × {preset.height} · {preset.mode}
+                    </span>
+                    <button className="btn-icon" onClick={() => handleRemovePreset(preset.id)} title="Remove preset">
+                      ×
+                    </button>
+                  </div>
+                </div>
+              ))}
+              <div className="setting-row has-border">
+                <input
+                  className="setting-input"
+                  placeholder="Name"
+                  value={newPreset.name}
+                  onChange={(e) => setNewPreset({ ...newPreset, name: e.target.value })}
+                />
+                <div className="setting-control">
+                  <input
+                    className="setting-input setting-input-num"
+                    placeholder="W"
+                    inputMode="numeric"
+                    value={newPreset.width}
+                    onChange={(e) => setNewPreset({ ...newPreset, width: e.target.value })}
+                  />
+                  <input
+                    className="setting-input setting-input-num"
+                    placeholder="H"
+                    inputMode="numeric"
+                    value={newPreset.height}
+                    onChange={(e) => setNewPreset({ ...newPreset, height: e.target.value })}
+                  />
+                  <select
+                    className="setting-select setting-select-small"
+                    value={newPreset.mode}
+                    onChange={(e) => setNewPreset({ ...newPreset, mode: e.target.value })}
+                  >
+                    <option value="image">Image</option>
+                    <option value="gif">GIF</option>
+                    <option value="video">Video</option>
+                  </select>
+                  <button className="btn-icon" onClick={handleAddPreset} title="Add preset">
+                    +
+                  </button>
+                </div>
+              </div>
+              <div className="setting-row">
+                <span className="setting-label">Lock Aspect Ratio</span>
+                <input
+                  className="setting-input"
+                  placeholder="Off (e.g. 16:9)"
+                  value={aspectInput}
+                  onChange={(e) => setAspectInput(e.target.value)}
+                  onBlur={() => {
+                    if (aspectInput.trim() !== (config.aspect_ratio_lock ?? "")) handleAspectRatioChange(aspectInput);
+                  }}
+                  onKeyDown={(e) => {
+                    if (e.key === "Enter") (e.target as HTMLInputElement).blur();
+                  }}
+                />
+              </div>
+            </div>
+          </AccordionContent>
+        </AccordionItem>
+
         <AccordionItem value="general">
           <AccordionTrigger>General</AccordionTrigger>
           <AccordionContent>
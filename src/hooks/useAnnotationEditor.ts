@@ -103,6 +103,14 @@ export function useAnnotationEditor() {
     setHistoryIndex(0);
   }, []);
 
+  // Start from a saved scene (reopened screenshot); undo stops there
+  const load = useCallback((saved: Annotation[]) => {
+    setSelectedId(null);
+    setActiveTool('select');
+    setHistory([saved]);
+    setHistoryIndex(0);
+  }, []);
+
   const canUndo = historyIndex > 0;
   const canRedo = historyIndex < history.length - 1;
 
@@ -136,6 +144,7 @@ export function useAnnotationEditor() {
     undo,
     redo,
     reset,
+    load,
   };
 }
 